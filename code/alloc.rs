@@ -0,0 +1,398 @@
+//! A size-class allocator which can be installed as the global allocator of a binary:
+//!
+//! ```ignore
+//! extern crate alloc;
+//!
+//! #[global_allocator]
+//! static ALLOCATOR: linux::alloc::Allocator = linux::alloc::Allocator::new();
+//! ```
+//!
+//! Small objects (up to 8 KiB) are carved out of 64 KiB spans which are taken from the program
+//! break. Every span serves a single size class, the objects are aligned to their size and the
+//! free objects are kept on a list inside the span. When a span becomes empty its pages are given
+//! back to the kernel with madvise(MADV_DONTNEED) and the span is reused by any size class.
+//! Larger objects get their own anonymous mapping which is unmapped when they are freed.
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::UnsafeCell;
+use core::ptr::null_mut;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::syscall::*;
+
+const PAGE: usize = 4096;
+const SPAN: usize = 64 * 1024;
+const MIN_CLASS: usize = 16;
+const MAX_SMALL: usize = 8 * 1024;
+const CLASSES: usize = (MAX_SMALL / MIN_CLASS).trailing_zeros() as usize + 1;
+
+/// Counters for monitoring the memory usage of the program
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Stats {
+    /// Bytes requested by the live allocations
+    pub in_use: usize,
+
+    /// The highest value of in_use so far
+    pub peak: usize,
+
+    /// Number of live allocations
+    pub allocations: usize,
+
+    /// Number of live anonymous mappings of the large objects
+    pub mappings: usize,
+
+    /// Bytes mapped for the large objects
+    pub mapped: usize,
+
+    /// Bytes of the program break used for spans
+    pub heap: usize,
+}
+
+/// Header at the beginning of every span
+#[repr(C)]
+struct Span {
+    next: *mut Span,
+    prev: *mut Span,
+    free: *mut Node,
+    class: usize,
+    used: usize,
+    bump: usize,
+    listed: bool,
+}
+
+struct Node {
+    next: *mut Node,
+}
+
+struct State {
+    /// Spans which have at least one free object, per size class
+    partial: [*mut Span; CLASSES],
+
+    /// Empty spans which have been returned to the kernel with madvise
+    empty: *mut Span,
+
+    /// The program break as we left it
+    heap_end: usize,
+
+    stats: Stats,
+}
+
+pub struct Allocator {
+    lock: AtomicBool,
+    state: UnsafeCell<State>,
+}
+
+unsafe impl Sync for Allocator {}
+
+impl Allocator {
+    pub const fn new() -> Self {
+        Self {
+            lock: AtomicBool::new(false),
+            state: UnsafeCell::new(State {
+                partial: [null_mut(); CLASSES],
+                empty: null_mut(),
+                heap_end: 0,
+                stats: Stats {
+                    in_use: 0,
+                    peak: 0,
+                    allocations: 0,
+                    mappings: 0,
+                    mapped: 0,
+                    heap: 0,
+                },
+            }),
+        }
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.with(|state| state.stats)
+    }
+
+    fn with<T>(&self, f: impl FnOnce(&mut State) -> T) -> T {
+        while self.lock.compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            sched_yield();
+        }
+        let ret = f(unsafe { &mut *self.state.get() });
+        self.lock.store(false, Ordering::Release);
+        ret
+    }
+}
+
+/// Index of the size class which can hold the layout, the size of a class is 16 << class
+fn class_of(layout: &Layout) -> Option<usize> {
+    let size = layout.size().max(layout.align()).max(MIN_CLASS).next_power_of_two();
+    match size <= MAX_SMALL {
+        true => Some((size / MIN_CLASS).trailing_zeros() as usize),
+        false => None,
+    }
+}
+
+fn page_align(len: usize) -> usize {
+    (len + PAGE - 1) & !(PAGE - 1)
+}
+
+unsafe impl GlobalAlloc for Allocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.with(|state| {
+            let p = match class_of(&layout) {
+                Some(class) => state.alloc_small(class),
+                None => state.alloc_large(&layout),
+            };
+            if !p.is_null() {
+                state.stats.in_use += layout.size();
+                state.stats.peak = state.stats.peak.max(state.stats.in_use);
+                state.stats.allocations += 1;
+            }
+            p
+        })
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.with(|state| {
+            match class_of(&layout) {
+                Some(_) => state.dealloc_small(ptr),
+                None => state.dealloc_large(ptr, &layout),
+            }
+            state.stats.in_use -= layout.size();
+            state.stats.allocations -= 1;
+        })
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+
+        match (class_of(&layout), class_of(&new_layout)) {
+            // The object still fits into its slot
+            (Some(old), Some(new)) if old == new => {
+                self.with(|state| {
+                    state.stats.in_use = state.stats.in_use - layout.size() + new_size;
+                    state.stats.peak = state.stats.peak.max(state.stats.in_use);
+                });
+                ptr
+            }
+
+            // Let the kernel move the pages instead of copying them
+            (None, None) if layout.align() <= PAGE => {
+                self.with(|state| {
+                    let old_len = page_align(layout.size());
+                    let new_len = page_align(new_size);
+                    match mremap(ptr, old_len, new_len, MREMAP_MAYMOVE, null_mut()) {
+                        Ok(p) => {
+                            state.stats.mapped = state.stats.mapped - old_len + new_len;
+                            state.stats.in_use = state.stats.in_use - layout.size() + new_size;
+                            state.stats.peak = state.stats.peak.max(state.stats.in_use);
+                            p
+                        }
+                        Err(_) => null_mut(),
+                    }
+                })
+            }
+
+            _ => {
+                let new = self.alloc(new_layout);
+                if !new.is_null() {
+                    core::ptr::copy_nonoverlapping(ptr, new, layout.size().min(new_size));
+                    self.dealloc(ptr, layout);
+                }
+                new
+            }
+        }
+    }
+}
+
+impl State {
+    unsafe fn alloc_small(&mut self, class: usize) -> *mut u8 {
+        let size = MIN_CLASS << class;
+
+        let span = match self.partial[class].is_null() {
+            false => self.partial[class],
+            true => {
+                let span = self.new_span(class);
+                if span.is_null() {
+                    return null_mut();
+                }
+                self.link(span);
+                span
+            }
+        };
+
+        let s = &mut *span;
+        let p = match s.free.is_null() {
+            false => {
+                let node = s.free;
+                s.free = (*node).next;
+                node as *mut u8
+            }
+            true => {
+                let p = (span as *mut u8).add(s.bump);
+                s.bump += size;
+                p
+            }
+        };
+        s.used += 1;
+
+        if s.free.is_null() && s.bump + size > SPAN {
+            self.unlink(span);
+        }
+
+        p
+    }
+
+    unsafe fn dealloc_small(&mut self, ptr: *mut u8) {
+        let span = (ptr as usize & !(SPAN - 1)) as *mut Span;
+        let s = &mut *span;
+
+        let node = ptr as *mut Node;
+        (*node).next = s.free;
+        s.free = node;
+        s.used -= 1;
+
+        if !s.listed {
+            self.link(span);
+        }
+
+        // Keep the last partial span of the class to avoid faulting in the pages again
+        // when a single object is allocated and freed in a loop.
+        let last = self.partial[s.class] == span && s.next.is_null();
+        if s.used == 0 && !last {
+            self.unlink(span);
+            let _ = madvise((span as *mut u8).add(PAGE), SPAN - PAGE, MADV_DONTNEED);
+            s.next = self.empty;
+            self.empty = span;
+        }
+    }
+
+    unsafe fn alloc_large(&mut self, layout: &Layout) -> *mut u8 {
+        let len = page_align(layout.size());
+        let extra = match layout.align() > PAGE {
+            true => layout.align(),
+            false => 0,
+        };
+
+        let prot = PROT_READ | PROT_WRITE;
+        let flags = MAP_PRIVATE | MAP_ANONYMOUS;
+        let p = match mmap(null_mut(), len + extra, prot, flags, u32::MAX, 0) {
+            Ok(p) => p,
+            Err(_) => return null_mut(),
+        };
+
+        // Trim the mapping to the requested alignment
+        let p = match extra {
+            0 => p,
+            _ => {
+                let aligned = (p as usize + layout.align() - 1) & !(layout.align() - 1);
+                let head = aligned - p as usize;
+                if head > 0 {
+                    let _ = munmap(p, head);
+                }
+                if extra - head > 0 {
+                    let _ = munmap((aligned + len) as *mut u8, extra - head);
+                }
+                aligned as *mut u8
+            }
+        };
+
+        self.stats.mappings += 1;
+        self.stats.mapped += len;
+        p
+    }
+
+    unsafe fn dealloc_large(&mut self, ptr: *mut u8, layout: &Layout) {
+        let len = page_align(layout.size());
+        let _ = munmap(ptr, len);
+        self.stats.mappings -= 1;
+        self.stats.mapped -= len;
+    }
+
+    /// Reuse an empty span or grow the heap by one span
+    unsafe fn new_span(&mut self, class: usize) -> *mut Span {
+        let span = match self.empty.is_null() {
+            false => {
+                let span = self.empty;
+                self.empty = (*span).next;
+                span
+            }
+            true => {
+                let span = self.grow();
+                if span.is_null() {
+                    return null_mut();
+                }
+                span
+            }
+        };
+
+        // The objects are aligned to their size because the span is aligned to SPAN
+        let size = MIN_CLASS << class;
+        span.write(Span {
+            next: null_mut(),
+            prev: null_mut(),
+            free: null_mut(),
+            class,
+            used: 0,
+            bump: size.max(core::mem::size_of::<Span>().next_power_of_two()),
+            listed: false,
+        });
+        span
+    }
+
+    /// Move the program break to make room for a new span. Somebody else might have moved
+    /// the break in the meantime so the new span is aligned again each time.
+    unsafe fn grow(&mut self) -> *mut Span {
+        self.heap_end = brk(0) as usize;
+
+        let start = (self.heap_end + SPAN - 1) & !(SPAN - 1);
+        let end = start + SPAN;
+        if (brk(end as isize) as usize) < end {
+            return self.grow_mmap();
+        }
+
+        self.stats.heap += end - self.heap_end;
+        self.heap_end = end;
+        start as *mut Span
+    }
+
+    /// Fallback when the program break can't be moved (e.g. it would collide with a mapping)
+    unsafe fn grow_mmap(&mut self) -> *mut Span {
+        let prot = PROT_READ | PROT_WRITE;
+        let flags = MAP_PRIVATE | MAP_ANONYMOUS;
+        let p = match mmap(null_mut(), 2 * SPAN, prot, flags, u32::MAX, 0) {
+            Ok(p) => p as usize,
+            Err(_) => return null_mut(),
+        };
+
+        let start = (p + SPAN - 1) & !(SPAN - 1);
+        if start > p {
+            let _ = munmap(p as *mut u8, start - p);
+        }
+        if p + 2 * SPAN > start + SPAN {
+            let _ = munmap((start + SPAN) as *mut u8, p + SPAN - start);
+        }
+
+        self.stats.heap += SPAN;
+        start as *mut Span
+    }
+
+    unsafe fn link(&mut self, span: *mut Span) {
+        let s = &mut *span;
+        s.prev = null_mut();
+        s.next = self.partial[s.class];
+        if !s.next.is_null() {
+            (*s.next).prev = span;
+        }
+        self.partial[s.class] = span;
+        s.listed = true;
+    }
+
+    unsafe fn unlink(&mut self, span: *mut Span) {
+        let s = &mut *span;
+        match s.prev.is_null() {
+            true => { self.partial[s.class] = s.next; }
+            false => { (*s.prev).next = s.next; }
+        }
+        if !s.next.is_null() {
+            (*s.next).prev = s.prev;
+        }
+        s.next = null_mut();
+        s.prev = null_mut();
+        s.listed = false;
+    }
+}
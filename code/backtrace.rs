@@ -0,0 +1,280 @@
+//! Backtraces of the calling thread.
+//!
+//! The crate is built with frame pointers, every frame starts with the saved rbp of the caller
+//! followed by the return address. The chain ends at the frame of `_start`, which clears rbp.
+//! The return addresses are resolved against the symbol table of /proc/self/exe, which is
+//! loaded where AT_PHDR tells.
+//!
+//! The panic handler prints a backtrace depending on RUST_BACKTRACE like std: unset or `0` turns
+//! it off, `full` prints the addresses and the complete names, anything else only the frames
+//! between the panic and main.
+use core::fmt::{self, Write};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::constants::*;
+use crate::demangle::demangle;
+use crate::elf::{self, Phdr, Sym, PT, STT, PF_X};
+use crate::fd::{AsRawFd, OwnedFd};
+use crate::syscall::*;
+use crate::types::*;
+
+/// The deepest frame which is printed
+const MAX_FRAMES: usize = 64;
+
+/// The frames of the panic handler and of core which lead to it, left out of short backtraces
+const PANIC_FRAMES: [&str; 8] = [
+    "core::panicking::",
+    "core::result::unwrap_failed",
+    "core::option::unwrap_failed",
+    "core::option::expect_failed",
+    "linux::backtrace::",
+    "linux::panic_handler",
+    "rust_begin_unwind",
+    "__rustc::rust_begin_unwind",
+];
+
+/// Set while a backtrace is printed, a panic meanwhile doesn't print another one
+static PRINTING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Off,
+
+    /// The names without the hashes from the panic up to main
+    Short,
+
+    /// All the frames with the addresses and the mangling hashes
+    Full,
+}
+
+impl Style {
+    /// The style of RUST_BACKTRACE, off when it is unset or `0`
+    pub fn from_env() -> Self {
+        match crate::env::var("RUST_BACKTRACE") {
+            None | Some("0") => Style::Off,
+            Some("full") => Style::Full,
+            _ => Style::Short,
+        }
+    }
+}
+
+/// The return addresses of the frames of the calling thread, the innermost first
+pub struct Frames {
+    fp: usize,
+}
+
+impl Iterator for Frames {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.fp == 0 || self.fp % 8 != 0 {
+            return None;
+        }
+
+        let (next, ret) = unsafe {
+            let frame = self.fp as *const usize;
+            (*frame, *frame.add(1))
+        };
+
+        // The stack grows down, the frame of the caller lies above
+        self.fp = match next > self.fp {
+            true => next,
+            false => 0,
+        };
+        match ret {
+            0 => None,
+            ret => Some(ret),
+        }
+    }
+}
+
+/// Walk the frames starting at the caller
+#[inline(never)]
+pub fn frames() -> Frames {
+    let fp: usize;
+    unsafe { core::arch::asm!("mov {}, rbp", out(reg) fp, options(nomem, nostack)) };
+    Frames { fp }
+}
+
+/// The symbols of the running executable
+pub struct Symbols {
+    elf: Option<elf::File<'static>>,
+    map: (*mut u8, usize),
+    _fd: Option<OwnedFd>,
+
+    /// The address the executable is loaded at minus the addresses in the file
+    bias: usize,
+    phdrs: &'static [Phdr],
+}
+
+impl Symbols {
+    /// Map /proc/self/exe, a stripped or unreadable executable gives no names
+    pub fn load() -> Self {
+        let mut phdrs: &'static [Phdr] = &[];
+        let (mut phdr, mut phnum) = (core::ptr::null(), 0);
+        for aux in crate::env::auxv() {
+            match aux {
+                AT::AT_PHDR(p) => phdr = p,
+                AT::AT_PHNUM(n) => phnum = n as usize,
+                _ => {}
+            }
+        }
+        if !phdr.is_null() {
+            phdrs = unsafe { core::slice::from_raw_parts(phdr as *const Phdr, phnum) };
+        }
+
+        let mut symbols = Self { elf: None, map: (core::ptr::null_mut(), 0), _fd: None, bias: 0, phdrs };
+        let Ok(fd) = open("/proc/self/exe", O_RDONLY | O_CLOEXEC, 0) else {
+            return symbols;
+        };
+        let mut stat = stat64::default();
+        if fstat(fd.as_raw_fd(), &mut stat).is_err() {
+            return symbols;
+        }
+        let len = stat.st_size as usize;
+        let Ok(p) = (unsafe { mmap(core::ptr::null_mut(), len, PROT_READ, MAP_PRIVATE, fd.as_raw_fd(), 0) }) else {
+            return symbols;
+        };
+        let Ok(file) = elf::File::parse(unsafe { core::slice::from_raw_parts(p, len) }) else {
+            let _ = unsafe { munmap(p, len) };
+            return symbols;
+        };
+
+        // PT_PHDR is where the program headers are, otherwise they follow the ELF header
+        // at the start of the first segment
+        let phdr_vaddr = match phdrs.iter().find(|h| h.p_type == PT::PT_PHDR as u32) {
+            Some(h) => Some(h.p_vaddr),
+            None => phdrs.iter()
+                .find(|h| h.p_type == PT::PT_LOAD as u32 && h.p_offset == 0)
+                .map(|h| h.p_vaddr + file.ehdr.e_phoff),
+        };
+        if let Some(vaddr) = phdr_vaddr {
+            symbols.bias = (phdr as usize).wrapping_sub(vaddr as usize);
+        }
+
+        symbols.elf = Some(file);
+        symbols.map = (p, len);
+        symbols._fd = Some(fd);
+        symbols
+    }
+
+    /// The name of the function containing `addr` and the offset into it
+    pub fn resolve(&self, addr: usize) -> Option<(&str, usize)> {
+        let elf = self.elf.as_ref()?;
+
+        // Only the code of the executable, not of the vdso for example
+        let mem = elf::Memory::new(self.bias as u64);
+        let in_code = self.phdrs.iter().any(|h| {
+            let start = unsafe { mem.offset(h.p_vaddr) } as usize;
+            h.p_type == PT::PT_LOAD as u32 && h.p_flags & PF_X != 0 && (start..start + h.p_memsz as usize).contains(&addr)
+        });
+        if !in_code {
+            return None;
+        }
+
+        let vaddr = (addr - self.bias) as u64;
+        let contains = |sym: &&Sym| {
+            sym.st_info & 0xf == STT::STT_FUNC as u8 && sym.st_value <= vaddr && vaddr < sym.st_value + sym.st_size
+        };
+        if let Some(sym) = elf.symtab().iter().find(contains) {
+            let name = elf.symtab_str(sym.st_name);
+            return Some((name, (vaddr - sym.st_value) as usize));
+        }
+        // The dynamic symbols, which are named in .dynstr
+        if let Some(sym) = elf.dynsym().iter().find(contains) {
+            let name = elf.strtab(sym.st_name);
+            return Some((name, (vaddr - sym.st_value) as usize));
+        }
+        None
+    }
+}
+
+impl Drop for Symbols {
+    fn drop(&mut self) {
+        if !self.map.0.is_null() {
+            let _ = unsafe { munmap(self.map.0, self.map.1) };
+        }
+    }
+}
+
+/// Print the backtrace of the calling thread to `out` in the given style
+pub fn print<W: Write>(out: &mut W, style: Style) -> fmt::Result {
+    if style == Style::Off {
+        return writeln!(out, "note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace");
+    }
+    if PRINTING.swap(true, Ordering::Acquire) {
+        return Ok(());
+    }
+
+    let res = print_frames(out, style);
+    PRINTING.store(false, Ordering::Release);
+    res
+}
+
+fn print_frames<W: Write>(out: &mut W, style: Style) -> fmt::Result {
+    let symbols = Symbols::load();
+    writeln!(out, "stack backtrace:")?;
+
+    let mut n = 0;
+    let mut skipping = style == Style::Short;
+    for ret in frames().take(MAX_FRAMES) {
+        // The call instruction before the return address, a call may be the last
+        // instruction of a function which doesn't return
+        let name = symbols.resolve(ret - 1).map(|(name, _)| name);
+
+        if style == Style::Full {
+            match name {
+                Some(name) => writeln!(out, "{:>4}: {:#018x} - {}", n, ret, demangle(name))?,
+                None => writeln!(out, "{:>4}: {:#018x} - <unknown>", n, ret)?,
+            }
+            n += 1;
+            continue;
+        }
+
+        if skipping {
+            if name.map_or(false, |name| PANIC_FRAMES.iter().any(|p| demangled_starts_with(name, p))) {
+                continue;
+            }
+            skipping = false;
+        }
+        match name {
+            Some(name) => writeln!(out, "{:>4}: {:#}", n, demangle(name))?,
+            None => writeln!(out, "{:>4}: <unknown>", n)?,
+        }
+        n += 1;
+
+        // What comes after main is the runtime
+        if name == Some("main") {
+            break;
+        }
+    }
+
+    if style == Style::Short {
+        writeln!(out, "note: Some details are omitted, run with `RUST_BACKTRACE=full` for a verbose backtrace.")?;
+    }
+    Ok(())
+}
+
+fn demangled_starts_with(name: &str, prefix: &str) -> bool {
+    let mut check = Prefix { prefix: prefix.as_bytes(), matched: true };
+    let _ = write!(check, "{:#}", demangle(name));
+    check.matched && check.prefix.is_empty()
+}
+
+/// Compares the output with a prefix as it is written, without a buffer
+struct Prefix<'a> {
+    prefix: &'a [u8],
+    matched: bool,
+}
+
+impl Write for Prefix<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let n = s.len().min(self.prefix.len());
+        if s.as_bytes()[..n] != self.prefix[..n] {
+            self.matched = false;
+            return Err(fmt::Error);
+        }
+        self.prefix = &self.prefix[n..];
+        Ok(())
+    }
+}
@@ -9,25 +9,48 @@ use linux::ldso::*;
 use linux::types::*;
 
 
-fn readelf(path: &str) {
+fn readelf(path: &str, demangle: bool) {
     use linux::syscall::*;
     use linux::constants::*;
-    let fd = open(path, O_RDONLY, 0).unwrap();
+    use linux::fd::AsRawFd;
+    let fd = match open(path, O_RDONLY, 0) {
+        Ok(fd) => fd,
+        Err(err) => {
+            eprintln!("readelf: {}: {}", path, err);
+            return;
+        }
+    };
     let mut stat = stat64::default();
-    fstat(fd, &mut stat).unwrap();
-    unsafe {
-        let p = mmap(
-            core::ptr::null_mut(),
-            stat.st_size as usize,
-            PROT_READ,
-            MAP_PRIVATE,
-            fd, 0
-        ).unwrap();
-        let elf = linux::elf::File::from_ptr(p);
-        elf.dump_dynsym();
-        elf.dump_rel();
-        elf.dump_rela();
+    if let Err(err) = fstat(fd.as_raw_fd(), &mut stat) {
+        eprintln!("readelf: {}: {}", path, err);
+        return;
     }
+
+    // An empty file can't be mapped, a directory fails with ENODEV
+    let buf = match stat.st_size {
+        0 => &[][..],
+        size => unsafe {
+            let p = match mmap(core::ptr::null_mut(), size as usize, PROT_READ, MAP_PRIVATE, fd.as_raw_fd(), 0) {
+                Ok(p) => p,
+                Err(err) => {
+                    eprintln!("readelf: {}: {}", path, err);
+                    return;
+                }
+            };
+            core::slice::from_raw_parts(p, size as usize)
+        }
+    };
+
+    let elf = match linux::elf::File::parse(buf) {
+        Ok(elf) => elf,
+        Err(err) => {
+            eprintln!("readelf: {}: {}", path, err);
+            return;
+        }
+    };
+    elf.dump_dynsym(demangle);
+    elf.dump_rel(demangle);
+    elf.dump_rela(demangle);
 }
 
 fn link() {
@@ -19,6 +19,24 @@ build() {
         -C panic=abort \
         -C link-args='-nostartfiles -pie -Wl,--no-dynamic-linker' \
         -L target ./bin.rs -o target/bin
+    rustc -g -O \
+        -C force-frame-pointers=yes \
+        -C force-unwind-tables=false \
+        -C panic=abort \
+        -C link-args='-nostartfiles -static -no-pie' \
+        -L target ./container.rs -o target/container
+    rustc -g -O \
+        -C force-frame-pointers=yes \
+        -C force-unwind-tables=false \
+        -C panic=abort \
+        -C link-args='-nostartfiles -static -no-pie' \
+        -L target ./trace.rs -o target/trace
+    rustc -g -O \
+        -C force-frame-pointers=yes \
+        -C force-unwind-tables=false \
+        -C panic=abort \
+        -C link-args='-nostartfiles -static -no-pie' \
+        -L target ./dbg.rs -o target/dbg
     #rustc -g  --crate-type=lib lib.rs -o target/liblinux.rlib
     #rustc -g  -C link-args='-nostartfiles -static' -L target ./bin.rs -o target/bin
 }
@@ -28,6 +46,21 @@ run() {
     ./target/bin
 }
 
+test() {
+    build
+    failed=0
+    for test in tests/*.rs; do
+        rustc -g -O \
+            -C force-frame-pointers=yes \
+            -C force-unwind-tables=false \
+            -C panic=abort \
+            -C link-args='-nostartfiles -static -no-pie' \
+            -L target ./$test -o target/test-$(basename $test .rs)
+        ./target/test-$(basename $test .rs) || failed=1
+    done
+    return $failed
+}
+
 dump() {
     objdump --disassemble=$1 -M intel --visualize-jumps=extended-color target/bin
     #objdump --disassemble=$1 -M intel target/bin
@@ -37,6 +70,7 @@ case "$1" in
     clean) clean;;
     build) build;;
     run) run;;
+    test) test;;
     dump) dump $2;;
     *) echo "Invalid argument '$1'";;
 esac
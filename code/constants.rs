@@ -22,6 +22,19 @@ pub const O_PATH:      i32 = 0o10000000;
 pub const O_TMPFILE:   i32 = 0o20000000;
 pub const O_NDELAY:    i32 = O_NONBLOCK;
 
+pub const S_IFMT:   mode_t = 0o170000;
+pub const S_IFSOCK: mode_t = 0o140000;
+pub const S_IFLNK:  mode_t = 0o120000;
+pub const S_IFREG:  mode_t = 0o100000;
+pub const S_IFBLK:  mode_t = 0o060000;
+pub const S_IFDIR:  mode_t = 0o040000;
+pub const S_IFCHR:  mode_t = 0o020000;
+pub const S_IFIFO:  mode_t = 0o010000;
+
+pub const S_ISUID: mode_t = 0o4000;
+pub const S_ISGID: mode_t = 0o2000;
+pub const S_ISVTX: mode_t = 0o1000;
+
 pub const S_IRWXU: mode_t = 0o700;
 pub const S_IRUSR: mode_t = 0o400;
 pub const S_IWUSR: mode_t = 0o200;
@@ -44,6 +57,15 @@ pub const SEEK_DATA: i32 = 3;
 pub const SEEK_HOLE: i32 = 4;
 pub const SEEK_MAX:  i32 = SEEK_HOLE;
 
+pub const F_DUPFD:         i32 = 0;
+pub const F_GETFD:         i32 = 1;
+pub const F_SETFD:         i32 = 2;
+pub const F_GETFL:         i32 = 3;
+pub const F_SETFL:         i32 = 4;
+pub const F_DUPFD_CLOEXEC: i32 = 1030;
+
+pub const FD_CLOEXEC: i32 = 1;
+
 // AUX Vector types
 pub const AT_NULL:              u64 = 0;
 pub const AT_IGNORE:            u64 = 1;
@@ -0,0 +1,110 @@
+//! container <rootfs> <command> [args...]
+//!
+//! Run a command in new user, mount, pid, uts, net and ipc namespaces with `rootfs` as the root
+//! directory. The user running it is root in the container, no privileges are needed outside.
+//! The command runs under a small init, which passes signals on to it and reaps the orphans.
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate linux;
+use core::sync::atomic::{AtomicI32, Ordering};
+use linux::mount::FsContext;
+use linux::namespace::*;
+use linux::process::{Command, ExitStatus};
+use linux::signal::{self, SIGHUP, SIGINT, SIGQUIT, SIGTERM, SIGUSR1, SIGUSR2, SIGWINCH};
+use linux::syscall::{exit_group, fork, getgid, getuid, kill, wait4};
+use linux::types::siginfo_t;
+use linux::Result;
+
+/// The signals init passes on to the command, pid 1 would ignore them otherwise
+const FORWARDED: [i32; 7] = [SIGHUP, SIGINT, SIGQUIT, SIGTERM, SIGUSR1, SIGUSR2, SIGWINCH];
+
+/// The pid of the command in the container
+static COMMAND: AtomicI32 = AtomicI32::new(0);
+
+#[no_mangle]
+fn main() -> u8 {
+    let mut args = linux::env::args().skip(1);
+    let (root, program) = match (args.next(), args.next()) {
+        (Some(root), Some(program)) => (root, program),
+        _ => {
+            eprintln!("usage: container <rootfs> <command> [args...]");
+            return 2;
+        }
+    };
+
+    match run(root, program, args) {
+        Ok(status) => exit_code(status),
+        Err(err) => {
+            eprintln!("container: {}", err);
+            125
+        }
+    }
+}
+
+fn run<'a>(root: &str, program: &'a str, args: impl Iterator<Item = &'a str>) -> Result<ExitStatus> {
+    // The ids have to be read before, in the new namespace they are unmapped
+    let uid = getuid()? as u32;
+    let gid = getgid()? as u32;
+
+    unshare(
+        CloneFlags::NEWUSER | CloneFlags::NEWNS | CloneFlags::NEWPID
+        | CloneFlags::NEWUTS | CloneFlags::NEWNET | CloneFlags::NEWIPC
+    )?;
+    map_user(0, uid, gid)?;
+
+    // Only the children are in the new pid namespace, the first one is its init
+    let pid = fork()?;
+    if pid == 0 {
+        let code = match init(root, program, args) {
+            Ok(status) => exit_code(status),
+            Err(err) => {
+                eprintln!("container: {}", err);
+                125
+            }
+        };
+        exit_group(code);
+    }
+
+    let mut status = 0;
+    wait4(pid, &mut status, 0, None)?;
+    Ok(ExitStatus(status))
+}
+
+/// Runs as pid 1 of the container
+fn init<'a>(root: &str, program: &'a str, args: impl Iterator<Item = &'a str>) -> Result<ExitStatus> {
+    set_hostname("container")?;
+
+    // A new instance of proc shows the processes of the new pid namespace. It has to be created
+    // while the proc of the host is still visible, a user namespace may not mount one otherwise.
+    let proc = FsContext::new("proc")?.create()?;
+    pivot_root(root)?;
+    proc.mount_at_cwd("/proc")?;
+
+    let command = Command::new(program).args(args).spawn()?;
+    COMMAND.store(command.id(), Ordering::Relaxed);
+    for sig in FORWARDED {
+        signal::register(sig, forward)?;
+    }
+
+    // Orphans in the container are reparented to init, they are reaped until the command exits
+    loop {
+        let mut status = 0;
+        if wait4(-1, &mut status, 0, None)? == command.id() {
+            return Ok(ExitStatus(status));
+        }
+    }
+}
+
+fn forward(sig: i32, _info: &siginfo_t) {
+    let _ = kill(COMMAND.load(Ordering::Relaxed), sig);
+}
+
+fn exit_code(status: ExitStatus) -> u8 {
+    match (status.code(), status.signal()) {
+        (Some(code), _) => code as u8,
+        (_, Some(sig)) => 128 + sig as u8,
+        _ => 125,
+    }
+}
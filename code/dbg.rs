@@ -0,0 +1,508 @@
+//! dbg <program> [args...]
+//!
+//! A minimal debugger. It starts the program stopped right after execve and reads commands
+//! from stdin, type `help` for the list. Breakpoints are set at the names of the symbol table
+//! of the executable, so it has to be built with symbols.
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate linux;
+use core::fmt::{self, Write};
+use linux::elf::{self, Sym, STT};
+use linux::fd::AsRawFd;
+use linux::io::Buf;
+use linux::process::Command;
+use linux::ptrace::{self, Stop};
+use linux::syscall::*;
+use linux::constants::*;
+use linux::types::*;
+use linux::{Error, Result};
+
+const MAX_BREAKPOINTS: usize = 32;
+
+/// The deepest backtrace
+const MAX_FRAMES: usize = 64;
+
+const INT3: usize = 0xcc;
+
+#[derive(Clone, Copy)]
+struct Breakpoint {
+    addr: usize,
+
+    /// The byte replaced by int3
+    orig: u8,
+}
+
+struct Debugger {
+    pid: pid_t,
+    elf: elf::File<'static>,
+
+    /// Where the executable is loaded, 0 unless it is position independent
+    base: usize,
+    breakpoints: [Option<Breakpoint>; MAX_BREAKPOINTS],
+
+    /// The signal which stopped the program, delivered when it is resumed
+    signal: i32,
+    exited: bool,
+}
+
+#[no_mangle]
+fn main() -> u8 {
+    let mut args = linux::env::args().skip(1);
+    let program = match args.next() {
+        Some(program) => program,
+        None => {
+            eprintln!("usage: dbg <program> [args...]");
+            return 2;
+        }
+    };
+
+    match run(program, args) {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("dbg: {}", err);
+            1
+        }
+    }
+}
+
+fn run<'a>(program: &'a str, args: impl Iterator<Item = &'a str>) -> Result<()> {
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    unsafe { cmd.pre_exec(&ptrace::traceme) };
+    let pid = cmd.spawn()?.id();
+
+    match ptrace::wait(pid)? {
+        (_, Stop::Signal(SIGTRAP)) => {}
+        (_, stop) => {
+            eprintln!("dbg: unexpected stop {:?}", stop);
+            return Err(Error::ECHILD);
+        }
+    }
+    ptrace::set_options(pid, PTRACE_O_EXITKILL)?;
+
+    let mut dbg = Debugger::new(pid)?;
+    dbg.show_location();
+
+    let mut input = Input { buf: [0; 512], len: 0 };
+    let mut buf = [0u8; 256];
+    loop {
+        print!("(dbg) ");
+        let Some(line) = input.read_line(&mut buf) else {
+            let _ = ptrace::kill(pid);
+            return Ok(());
+        };
+
+        let mut words = line.split_whitespace();
+        let Some(cmd) = words.next() else {
+            continue;
+        };
+        let arg = words.next();
+        let res = match (cmd, arg) {
+            ("b" | "break", Some(loc)) => dbg.set_breakpoint(loc),
+            ("d" | "delete", Some(loc)) => dbg.delete_breakpoint(loc),
+            ("i" | "info", _) => {
+                dbg.info();
+                Ok(())
+            }
+            ("c" | "continue", _) => dbg.cont(),
+            ("s" | "step", count) => dbg.step(count.and_then(parse).unwrap_or(1)),
+            ("r" | "regs", _) => dbg.regs(),
+            ("x", Some(loc)) => dbg.examine(loc, words.next().and_then(parse).unwrap_or(4)),
+            ("set", Some(loc)) => match words.next().and_then(parse) {
+                Some(value) => dbg.set(loc, value),
+                None => Err(Error::EINVAL),
+            },
+            ("bt" | "backtrace", _) => dbg.backtrace(),
+            ("q" | "quit", _) => {
+                let _ = ptrace::kill(pid);
+                return Ok(());
+            }
+            ("h" | "help", _) => {
+                help();
+                Ok(())
+            }
+            _ => {
+                println!("unknown command, type help for the list");
+                Ok(())
+            }
+        };
+
+        if let Err(err) = res {
+            println!("error: {}", err);
+        }
+    }
+}
+
+fn help() {
+    println!("break <loc>       set a breakpoint, loc is a symbol or an address like 0x401000");
+    println!("delete <loc>      delete the breakpoint");
+    println!("info              list the breakpoints");
+    println!("continue          run until a breakpoint or a signal");
+    println!("step [n]          execute n instructions");
+    println!("regs              print the registers");
+    println!("x <loc> [n]       print n words of memory");
+    println!("set <loc> <value> write a word of memory");
+    println!("backtrace         print the stack frames");
+    println!("quit              kill the program and exit");
+}
+
+impl Debugger {
+    fn new(pid: pid_t) -> Result<Self> {
+        let mut path = Buf::<64>::new();
+        let _ = write!(path, "/proc/{}/exe", pid);
+        let fd = open(path.as_str(), O_RDONLY | O_CLOEXEC, 0)?;
+        let mut stat = stat64::default();
+        fstat(fd.as_raw_fd(), &mut stat)?;
+
+        // The mapping lives as long as the debugger
+        let elf = unsafe {
+            let len = stat.st_size as usize;
+            let p = mmap(core::ptr::null_mut(), len, PROT_READ, MAP_PRIVATE, fd.as_raw_fd(), 0)?;
+            elf::File::parse(core::slice::from_raw_parts(p, len))?
+        };
+
+        // A position independent executable is loaded anywhere, its entry point tells where
+        let entry = aux_entry(pid)?;
+        let base = entry - elf.ehdr.e_entry as usize;
+
+        Ok(Self { pid, elf, base, breakpoints: [None; MAX_BREAKPOINTS], signal: 0, exited: false })
+    }
+
+    /// The address of a symbol or of a number
+    fn resolve(&self, loc: &str) -> Result<usize> {
+        if let Some(addr) = parse(loc) {
+            return Ok(addr);
+        }
+
+        let sym = self.elf.symtab().iter()
+            .find(|sym| sym.st_value != 0 && self.elf.symtab_str(sym.st_name) == loc)
+            .ok_or(Error::ENOENT)?;
+        Ok(self.base + sym.st_value as usize)
+    }
+
+    /// The function containing `addr` and the offset into it
+    fn symbolize(&self, addr: usize) -> Option<(&str, usize)> {
+        let addr = addr.checked_sub(self.base)? as u64;
+        let sym = self.elf.symtab().iter().find(|sym| is_func(sym) && sym.st_value <= addr && addr < sym.st_value + sym.st_size)?;
+        let name = self.elf.symtab_str(sym.st_name);
+        Some((name, (addr - sym.st_value) as usize))
+    }
+
+    fn set_breakpoint(&mut self, loc: &str) -> Result<()> {
+        self.alive()?;
+        let addr = self.resolve(loc)?;
+        if self.breakpoint(addr).is_some() {
+            return Err(Error::EEXIST);
+        }
+        let slot = self.breakpoints.iter().position(Option::is_none).ok_or(Error::ENOSPC)?;
+
+        let word = ptrace::peek_data(self.pid, addr)?;
+        ptrace::poke_data(self.pid, addr, (word & !0xff) | INT3)?;
+        self.breakpoints[slot] = Some(Breakpoint { addr, orig: word as u8 });
+        println!("Breakpoint {} at {}", slot + 1, Location(self, addr));
+        Ok(())
+    }
+
+    fn delete_breakpoint(&mut self, loc: &str) -> Result<()> {
+        let addr = self.resolve(loc)?;
+        let slot = self.breakpoint(addr).ok_or(Error::ENOENT)?;
+        if !self.exited {
+            self.remove(addr)?;
+        }
+        self.breakpoints[slot] = None;
+        Ok(())
+    }
+
+    fn info(&self) {
+        for (slot, bp) in self.breakpoints.iter().enumerate() {
+            if let Some(bp) = bp {
+                println!("{:<3} {}", slot + 1, Location(self, bp.addr));
+            }
+        }
+    }
+
+    fn cont(&mut self) -> Result<()> {
+        self.alive()?;
+        self.step_over_breakpoint()?;
+        if self.exited {
+            return Ok(());
+        }
+
+        ptrace::cont(self.pid, core::mem::take(&mut self.signal))?;
+        self.wait_stop(false)
+    }
+
+    fn step(&mut self, count: usize) -> Result<()> {
+        self.alive()?;
+        for _ in 0..count {
+            if self.breakpoint(self.rip()?).is_some() {
+                self.step_over_breakpoint()?;
+            } else {
+                ptrace::single_step(self.pid, core::mem::take(&mut self.signal))?;
+                self.wait_stop(true)?;
+            }
+            if self.exited || self.signal != 0 {
+                break;
+            }
+        }
+        self.show_location();
+        Ok(())
+    }
+
+    fn regs(&self) -> Result<()> {
+        self.alive()?;
+        let r = ptrace::get_regs(self.pid)?;
+        let regs = [
+            ("rax", r.rax), ("rbx", r.rbx), ("rcx", r.rcx), ("rdx", r.rdx),
+            ("rsi", r.rsi), ("rdi", r.rdi), ("rbp", r.rbp), ("rsp", r.rsp),
+            ("r8", r.r8), ("r9", r.r9), ("r10", r.r10), ("r11", r.r11),
+            ("r12", r.r12), ("r13", r.r13), ("r14", r.r14), ("r15", r.r15),
+            ("rip", r.rip), ("eflags", r.eflags), ("fs_base", r.fs_base), ("gs_base", r.gs_base),
+        ];
+        for row in regs.chunks(4) {
+            for (name, value) in row {
+                print!("{:<7} {:#018x}  ", name, value);
+            }
+            println!("");
+        }
+        Ok(())
+    }
+
+    fn examine(&self, loc: &str, count: usize) -> Result<()> {
+        self.alive()?;
+        let addr = self.resolve(loc)?;
+        for i in 0..count {
+            let addr = addr + i * 8;
+            println!("{:#x}: {:#018x}", addr, self.read_word(addr)?);
+        }
+        Ok(())
+    }
+
+    fn set(&mut self, loc: &str, value: usize) -> Result<()> {
+        self.alive()?;
+        let addr = self.resolve(loc)?;
+        let mut word = value;
+
+        // Keep the breakpoints in the word, with the new bytes behind them
+        for bp in self.breakpoints.iter_mut().flatten() {
+            if (addr..addr + 8).contains(&bp.addr) {
+                let shift = (bp.addr - addr) * 8;
+                bp.orig = (value >> shift) as u8;
+                word = (word & !(0xff << shift)) | INT3 << shift;
+            }
+        }
+        ptrace::poke_data(self.pid, addr, word)
+    }
+
+    /// The frames linked by the saved frame pointers, which needs -C force-frame-pointers=yes
+    fn backtrace(&self) -> Result<()> {
+        self.alive()?;
+        let regs = ptrace::get_regs(self.pid)?;
+        let mut pc = regs.rip as usize;
+        let mut fp = regs.rbp as usize;
+        let mut frame = 0;
+        println!("#0  {}", Location(self, pc));
+
+        // At the first instruction of a function the frame isn't set up yet, the return
+        // address is on top of the stack and rbp is still the frame of the caller
+        if let Some((_, 0)) = self.symbolize(pc) {
+            pc = self.read_word(regs.rsp as usize)?;
+            frame += 1;
+            println!("#{:<2} {}", frame, Location(self, pc));
+        }
+
+        while frame < MAX_FRAMES {
+            if fp == 0 || fp % 8 != 0 {
+                break;
+            }
+            let (Ok(next), Ok(ret)) = (self.read_word(fp), self.read_word(fp + 8)) else {
+                break;
+            };
+            if ret == 0 {
+                break;
+            }
+            frame += 1;
+            println!("#{:<2} {}", frame, Location(self, ret));
+
+            // The stack grows down, a caller's frame is always above
+            if next <= fp {
+                break;
+            }
+            fp = next;
+        }
+        Ok(())
+    }
+
+    /// Print where the program has stopped
+    fn show_location(&self) {
+        if self.exited {
+            return;
+        }
+        match self.rip() {
+            Ok(rip) => println!("stopped at {}", Location(self, rip)),
+            Err(err) => println!("error: {}", err),
+        }
+    }
+
+    /// Execute the instruction replaced by the breakpoint at rip, if there is one
+    fn step_over_breakpoint(&mut self) -> Result<()> {
+        let rip = self.rip()?;
+        if self.breakpoint(rip).is_none() {
+            return Ok(());
+        }
+
+        self.remove(rip)?;
+        ptrace::single_step(self.pid, core::mem::take(&mut self.signal))?;
+        self.wait_stop(true)?;
+        if !self.exited {
+            let word = ptrace::peek_data(self.pid, rip)?;
+            ptrace::poke_data(self.pid, rip, (word & !0xff) | INT3)?;
+        }
+        Ok(())
+    }
+
+    /// Wait until the program stops and report why. After a single step SIGTRAP only means
+    /// that the instruction has been executed.
+    fn wait_stop(&mut self, stepping: bool) -> Result<()> {
+        match ptrace::wait(self.pid)?.1 {
+            Stop::Signal(SIGTRAP) if stepping => {}
+            Stop::Signal(SIGTRAP) => {
+                // int3 has been executed, the breakpoint is one byte before rip
+                let mut regs = ptrace::get_regs(self.pid)?;
+                let addr = regs.rip as usize - 1;
+                if let Some(slot) = self.breakpoint(addr) {
+                    regs.rip = addr as u64;
+                    ptrace::set_regs(self.pid, &regs)?;
+                    println!("Breakpoint {}, {}", slot + 1, Location(self, addr));
+                }
+            }
+            Stop::Signal(sig) => {
+                let rip = self.rip()?;
+                println!("Program received {} at {}", linux::signal::name(sig), Location(self, rip));
+                self.signal = sig;
+            }
+            Stop::Exited(code) => {
+                println!("Program exited with code {}", code);
+                self.exited = true;
+            }
+            Stop::Killed(sig) => {
+                println!("Program terminated with {}", linux::signal::name(sig));
+                self.exited = true;
+            }
+            Stop::Syscall | Stop::Event(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Restore the original byte of the breakpoint at `addr`
+    fn remove(&self, addr: usize) -> Result<()> {
+        let slot = self.breakpoint(addr).ok_or(Error::ENOENT)?;
+        let orig = self.breakpoints[slot].unwrap().orig;
+        let word = ptrace::peek_data(self.pid, addr)?;
+        ptrace::poke_data(self.pid, addr, (word & !0xff) | orig as usize)
+    }
+
+    /// The word at `addr` as the program sees it, without the breakpoints
+    fn read_word(&self, addr: usize) -> Result<usize> {
+        let mut word = ptrace::peek_data(self.pid, addr)?;
+        for bp in self.breakpoints.iter().flatten() {
+            if (addr..addr + 8).contains(&bp.addr) {
+                let shift = (bp.addr - addr) * 8;
+                word = (word & !(0xff << shift)) | (bp.orig as usize) << shift;
+            }
+        }
+        Ok(word)
+    }
+
+    fn breakpoint(&self, addr: usize) -> Option<usize> {
+        self.breakpoints.iter().position(|bp| bp.map_or(false, |bp| bp.addr == addr))
+    }
+
+    fn rip(&self) -> Result<usize> {
+        Ok(ptrace::get_regs(self.pid)?.rip as usize)
+    }
+
+    fn alive(&self) -> Result<()> {
+        match self.exited {
+            true => Err(Error::ESRCH),
+            false => Ok(()),
+        }
+    }
+}
+
+/// An address with the function containing it
+struct Location<'a>(&'a Debugger, usize);
+
+impl fmt::Display for Location<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0.symbolize(self.1) {
+            Some((name, 0)) => write!(f, "{:#x} <{}>", self.1, name),
+            Some((name, off)) => write!(f, "{:#x} <{}+{:#x}>", self.1, name, off),
+            None => write!(f, "{:#x}", self.1),
+        }
+    }
+}
+
+fn is_func(sym: &Sym) -> bool {
+    sym.st_info & 0xf == STT::STT_FUNC as u8
+}
+
+/// The entry point of the program from its auxiliary vector
+fn aux_entry(pid: pid_t) -> Result<usize> {
+    let mut path = Buf::<64>::new();
+    let _ = write!(path, "/proc/{}/auxv", pid);
+    let fd = open(path.as_str(), O_RDONLY | O_CLOEXEC, 0)?;
+
+    let mut buf = [0u8; 1024];
+    let n = read(fd.as_raw_fd(), &mut buf)?;
+    for pair in buf[..n].chunks_exact(16) {
+        let (mut key, mut val) = ([0u8; 8], [0u8; 8]);
+        key.copy_from_slice(&pair[..8]);
+        val.copy_from_slice(&pair[8..]);
+        if u64::from_ne_bytes(key) == AT_ENTRY {
+            return Ok(u64::from_ne_bytes(val) as usize);
+        }
+    }
+    Err(Error::ENOENT)
+}
+
+/// A number, hexadecimal with 0x
+fn parse(s: &str) -> Option<usize> {
+    match s.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
+
+/// Lines of stdin
+struct Input {
+    buf: [u8; 512],
+    len: usize,
+}
+
+impl Input {
+    /// The next line without the newline, None at the end of the input
+    fn read_line<'b>(&mut self, line: &'b mut [u8]) -> Option<&'b str> {
+        loop {
+            if let Some(nl) = self.buf[..self.len].iter().position(|&c| c == b'\n') {
+                let n = nl.min(line.len());
+                line[..n].copy_from_slice(&self.buf[..n]);
+                self.buf.copy_within(nl + 1..self.len, 0);
+                self.len -= nl + 1;
+                return Some(core::str::from_utf8(&line[..n]).unwrap_or(""));
+            }
+
+            // A line which doesn't fit is dropped
+            if self.len == self.buf.len() {
+                self.len = 0;
+            }
+            match read(0, &mut self.buf[self.len..]) {
+                Ok(0) | Err(_) => return None,
+                Ok(n) => self.len += n,
+            }
+        }
+    }
+}
+
@@ -0,0 +1,710 @@
+//! Demangling of Rust symbol names.
+//!
+//! Two schemes are in use. The legacy one reuses the C++ scheme, `_ZN` followed by the length
+//! prefixed components of the path and a hash, like `_ZN5linux7syscall2io4read17h0123456789abcdefE`
+//! for `linux::syscall::io::read::h0123456789abcdef`. The v0 scheme starting with `_R` encodes
+//! the generic arguments and the types of impls as well.
+//!
+//! [demangle] doesn't allocate, the name is decoded while it is formatted. The alternate form
+//! `{:#}` leaves out the hash of legacy names and the crate disambiguators of v0 names. Names
+//! which aren't mangled by Rust are printed unchanged.
+//!
+//! ```ignore
+//! println!("{:#}", demangle("_ZN5linux7syscall2io4read17h0123456789abcdefE")); // linux::syscall::io::read
+//! ```
+use core::fmt::{self, Write};
+
+/// Recursion limit of nested v0 paths and types
+const MAX_DEPTH: u32 = 64;
+
+/// The most bytes a name may expand to. Every level of nested v0 backrefs can double the
+/// output, a longer name is printed as it is.
+const MAX_OUTPUT: usize = 1 << 20;
+
+pub fn demangle(name: &str) -> Demangle<'_> {
+    Demangle { name }
+}
+
+/// A symbol name which is demangled when it is formatted
+#[derive(Debug, Clone, Copy)]
+pub struct Demangle<'a> {
+    name: &'a str,
+}
+
+impl<'a> Demangle<'a> {
+    /// Whether the name is mangled by one of the Rust schemes
+    pub fn is_rust(&self) -> bool {
+        self.print(&mut Sink::new(), false).is_ok()
+    }
+
+    /// The mangled name
+    pub fn mangled(&self) -> &'a str {
+        self.name
+    }
+
+    fn print<W: Write>(&self, out: &mut W, short: bool) -> core::result::Result<(), Invalid> {
+        let name = self.name;
+
+        // An LLVM suffix like .llvm.1234 of a symbol which was renamed
+        let name = match name.find(".llvm.") {
+            Some(dot) => &name[..dot],
+            None => name,
+        };
+
+        // Other suffixes like .0 of a static are printed as they are
+        let suffix = if let Some(rest) = strip_any(name, &["_ZN", "ZN", "__ZN"]) {
+            legacy(rest, out, short)?
+        } else if let Some(rest) = strip_any(name, &["_R", "R", "__R"]) {
+            let (sym, suffix) = rest.split_at(rest.find('.').unwrap_or(rest.len()));
+            let mut p = V0 { sym: sym.as_bytes(), pos: 0, depth: 0, lifetimes: 0, short, skipped: Sink::new() };
+            p.path(out, false)?;
+
+            // The instantiating crate may follow, which isn't printed
+            if p.pos < p.sym.len() {
+                p.skip_path()?;
+            }
+            if p.pos != p.sym.len() {
+                return Err(Invalid);
+            }
+            suffix
+        } else {
+            return Err(Invalid);
+        };
+        Ok(out.write_str(suffix)?)
+    }
+}
+
+impl fmt::Display for Demangle<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // A dry run first, an invalid or too long name is printed as it is and not half decoded
+        match self.print(&mut Sink::new(), f.alternate()) {
+            Ok(()) => self.print(f, f.alternate()).map_err(|_| fmt::Error),
+            Err(_) => f.write_str(self.name),
+        }
+    }
+}
+
+/// The name isn't a valid mangled name, or writing has failed
+#[derive(Debug)]
+struct Invalid;
+
+impl From<fmt::Error> for Invalid {
+    fn from(_: fmt::Error) -> Self {
+        Invalid
+    }
+}
+
+/// Discards the output of a dry run, it fails once more than MAX_OUTPUT bytes are written
+struct Sink {
+    len: usize,
+}
+
+impl Sink {
+    const fn new() -> Self {
+        Self { len: 0 }
+    }
+}
+
+impl Write for Sink {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.len += s.len();
+        match self.len > MAX_OUTPUT {
+            true => Err(fmt::Error),
+            false => Ok(()),
+        }
+    }
+}
+
+fn strip_any<'a>(name: &'a str, prefixes: &[&str]) -> Option<&'a str> {
+    prefixes.iter().find_map(|prefix| name.strip_prefix(prefix))
+}
+
+// ==============================================================================
+// Legacy
+// ==============================================================================
+/// Returns the suffix after the closing `E`
+fn legacy<'a, W: Write>(mut rest: &'a str, out: &mut W, short: bool) -> core::result::Result<&'a str, Invalid> {
+    let mut first = true;
+    while !rest.starts_with('E') {
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        let len: usize = rest[..digits].parse().map_err(|_| Invalid)?;
+        let ident = rest.get(digits..digits + len).ok_or(Invalid)?;
+        rest = &rest[digits + len..];
+
+        // The last component is the hash
+        if short && rest.starts_with('E') && is_hash(ident) {
+            break;
+        }
+        if !first {
+            out.write_str("::")?;
+        }
+        first = false;
+        legacy_ident(ident, out)?;
+    }
+    match &rest[1..] {
+        suffix if suffix.is_empty() || suffix.starts_with('.') => Ok(suffix),
+        _ => Err(Invalid),
+    }
+}
+
+fn is_hash(ident: &str) -> bool {
+    ident.len() == 17 && ident.starts_with('h') && ident[1..].bytes().all(|c| c.is_ascii_hexdigit())
+}
+
+/// The characters which aren't allowed in symbols are escaped like $LT$ for <
+fn legacy_ident<W: Write>(mut ident: &str, out: &mut W) -> core::result::Result<(), Invalid> {
+    if ident.starts_with("_$") {
+        ident = &ident[1..];
+    }
+
+    while !ident.is_empty() {
+        if let Some(rest) = ident.strip_prefix('$') {
+            let end = rest.find('$').ok_or(Invalid)?;
+            let c = match &rest[..end] {
+                "SP" => '@',
+                "BP" => '*',
+                "RF" => '&',
+                "LT" => '<',
+                "GT" => '>',
+                "LP" => '(',
+                "RP" => ')',
+                "C" => ',',
+                esc => {
+                    let hex = esc.strip_prefix('u').ok_or(Invalid)?;
+                    let code = u32::from_str_radix(hex, 16).map_err(|_| Invalid)?;
+                    char::from_u32(code).ok_or(Invalid)?
+                }
+            };
+            out.write_char(c)?;
+            ident = &rest[end + 1..];
+        } else if let Some(rest) = ident.strip_prefix("..") {
+            out.write_str("::")?;
+            ident = rest;
+        } else {
+            // The first character is not an escape, it may be longer than a byte
+            let first = ident.chars().next().map_or(0, char::len_utf8);
+            let end = ident[first..].find(['$', '.']).map_or(ident.len(), |i| i + first);
+            out.write_str(&ident[..end])?;
+            ident = &ident[end..];
+        }
+    }
+    Ok(())
+}
+
+// ==============================================================================
+// v0
+// ==============================================================================
+/// The most lifetimes a `for<...>` binder may introduce
+const MAX_BOUND_LIFETIMES: u64 = 64;
+
+struct V0<'s> {
+    sym: &'s [u8],
+    pos: usize,
+    depth: u32,
+
+    /// The lifetimes bound by the enclosing `for<...>`
+    lifetimes: u64,
+
+    /// Leave out the crate disambiguators
+    short: bool,
+
+    /// The paths which are parsed but not printed, they count against MAX_OUTPUT as well
+    skipped: Sink,
+}
+
+type V0Result = core::result::Result<(), Invalid>;
+
+impl<'s> V0<'s> {
+    fn peek(&self) -> Option<u8> {
+        self.sym.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> core::result::Result<u8, Invalid> {
+        let c = self.peek().ok_or(Invalid)?;
+        self.pos += 1;
+        Ok(c)
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        match self.peek() == Some(c) {
+            true => {
+                self.pos += 1;
+                true
+            }
+            false => false,
+        }
+    }
+
+    /// `_` is 0, otherwise the digits 0-9a-zA-Z followed by `_` are the number minus one
+    fn base62(&mut self) -> core::result::Result<u64, Invalid> {
+        if self.eat(b'_') {
+            return Ok(0);
+        }
+        let mut n: u64 = 0;
+        loop {
+            let c = self.next()?;
+            let d = match c {
+                b'0'..=b'9' => c - b'0',
+                b'a'..=b'z' => c - b'a' + 10,
+                b'A'..=b'Z' => c - b'A' + 36,
+                b'_' => return n.checked_add(1).ok_or(Invalid),
+                _ => return Err(Invalid),
+            };
+            n = n.checked_mul(62).and_then(|n| n.checked_add(d as u64)).ok_or(Invalid)?;
+        }
+    }
+
+    fn decimal(&mut self) -> core::result::Result<usize, Invalid> {
+        // No leading zeros, the length of an empty identifier may be followed by one
+        if self.eat(b'0') {
+            return Ok(0);
+        }
+        let start = self.pos;
+        while self.peek().map_or(false, |c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let digits = core::str::from_utf8(&self.sym[start..self.pos]).map_err(|_| Invalid)?;
+        digits.parse().map_err(|_| Invalid)
+    }
+
+    /// `s` and a number, which tells apart items of the same name
+    fn disambiguator(&mut self) -> core::result::Result<u64, Invalid> {
+        match self.eat(b's') {
+            true => self.base62().map(|n| n + 1),
+            false => Ok(0),
+        }
+    }
+
+    fn ident(&mut self) -> core::result::Result<Ident<'s>, Invalid> {
+        let punycode = self.eat(b'u');
+        let len = self.decimal()?;
+        self.eat(b'_');
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.sym.len()).ok_or(Invalid)?;
+        let name = core::str::from_utf8(&self.sym[self.pos..end]).map_err(|_| Invalid)?;
+        self.pos = end;
+        Ok(Ident { name, punycode })
+    }
+
+    /// Continue at an earlier position and come back
+    fn backref<F>(&mut self, f: F) -> V0Result
+    where
+        F: FnOnce(&mut Self) -> V0Result,
+    {
+        let start = self.pos - 1;
+        let target = self.base62()? as usize;
+        if target >= start {
+            return Err(Invalid);
+        }
+        let pos = core::mem::replace(&mut self.pos, target);
+        let res = f(self);
+        self.pos = pos;
+        res
+    }
+
+    /// A path which isn't printed, like the impl path before the type of an impl
+    fn skip_path(&mut self) -> V0Result {
+        let mut skipped = core::mem::replace(&mut self.skipped, Sink::new());
+        let res = self.path(&mut skipped, false);
+        self.skipped = skipped;
+        res
+    }
+
+    fn enter(&mut self) -> V0Result {
+        self.depth += 1;
+        match self.depth > MAX_DEPTH {
+            true => Err(Invalid),
+            false => Ok(()),
+        }
+    }
+
+    /// A path, whose generic arguments are written `::<T>` in expressions and `<T>` in types
+    fn path<W: Write>(&mut self, out: &mut W, in_type: bool) -> V0Result {
+        self.enter()?;
+        match self.next()? {
+            b'C' => {
+                let dis = self.disambiguator()?;
+                let ident = self.ident()?;
+                ident.print(out)?;
+                if !self.short && dis != 0 {
+                    write!(out, "[{:x}]", dis)?;
+                }
+            }
+            b'N' => {
+                let ns = self.next()?;
+                self.path(out, in_type)?;
+                let dis = self.disambiguator()?;
+                let ident = self.ident()?;
+                match ns {
+                    b'a'..=b'z' => {
+                        out.write_str("::")?;
+                        ident.print(out)?;
+                    }
+                    b'A'..=b'Z' => {
+                        out.write_str("::{")?;
+                        match ns {
+                            b'C' => out.write_str("closure")?,
+                            b'S' => out.write_str("shim")?,
+                            other => out.write_char(other as char)?,
+                        }
+                        if !ident.name.is_empty() {
+                            out.write_char(':')?;
+                            ident.print(out)?;
+                        }
+                        write!(out, "#{}}}", dis)?;
+                    }
+                    _ => return Err(Invalid),
+                }
+            }
+            b'M' => {
+                self.disambiguator()?;
+                self.skip_path()?;
+                out.write_char('<')?;
+                self.ty(out)?;
+                out.write_char('>')?;
+            }
+            b'X' => {
+                self.disambiguator()?;
+                self.skip_path()?;
+                out.write_char('<')?;
+                self.ty(out)?;
+                out.write_str(" as ")?;
+                self.path(out, true)?;
+                out.write_char('>')?;
+            }
+            b'Y' => {
+                out.write_char('<')?;
+                self.ty(out)?;
+                out.write_str(" as ")?;
+                self.path(out, true)?;
+                out.write_char('>')?;
+            }
+            b'I' => {
+                self.path(out, in_type)?;
+                out.write_str(if in_type { "<" } else { "::<" })?;
+                self.generic_args(out)?;
+                out.write_char('>')?;
+            }
+            b'B' => self.backref(|p| p.path(out, in_type))?,
+            _ => return Err(Invalid),
+        }
+        self.depth -= 1;
+        Ok(())
+    }
+
+    /// Generic arguments up to `E`, separated by commas
+    fn generic_args<W: Write>(&mut self, out: &mut W) -> V0Result {
+        let mut first = true;
+        while !self.eat(b'E') {
+            if !first {
+                out.write_str(", ")?;
+            }
+            first = false;
+            match self.peek() {
+                Some(b'L') => {
+                    self.pos += 1;
+                    let lifetime = self.base62()?;
+                    self.lifetime(out, lifetime)?;
+                }
+                Some(b'K') => {
+                    self.pos += 1;
+                    self.konst(out)?;
+                }
+                _ => self.ty(out)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn ty<W: Write>(&mut self, out: &mut W) -> V0Result {
+        self.enter()?;
+        let c = self.next()?;
+        if let Some(name) = basic_type(c) {
+            out.write_str(name)?;
+            self.depth -= 1;
+            return Ok(());
+        }
+
+        match c {
+            b'A' | b'S' => {
+                out.write_char('[')?;
+                self.ty(out)?;
+                if c == b'A' {
+                    out.write_str("; ")?;
+                    self.konst(out)?;
+                }
+                out.write_char(']')?;
+            }
+            b'T' => {
+                out.write_char('(')?;
+                let mut n = 0;
+                while !self.eat(b'E') {
+                    if n > 0 {
+                        out.write_str(", ")?;
+                    }
+                    self.ty(out)?;
+                    n += 1;
+                }
+                if n == 1 {
+                    out.write_char(',')?;
+                }
+                out.write_char(')')?;
+            }
+            b'R' | b'Q' => {
+                out.write_char('&')?;
+                if self.eat(b'L') {
+                    let lifetime = self.base62()?;
+                    if lifetime != 0 {
+                        self.lifetime(out, lifetime)?;
+                        out.write_char(' ')?;
+                    }
+                }
+                if c == b'Q' {
+                    out.write_str("mut ")?;
+                }
+                self.ty(out)?;
+            }
+            b'P' => {
+                out.write_str("*const ")?;
+                self.ty(out)?;
+            }
+            b'O' => {
+                out.write_str("*mut ")?;
+                self.ty(out)?;
+            }
+            b'F' => self.fn_sig(out)?,
+            b'D' => {
+                out.write_str("dyn ")?;
+                let bound = self.binder(out)?;
+                let mut first = true;
+                while !self.eat(b'E') {
+                    if !first {
+                        out.write_str(" + ")?;
+                    }
+                    first = false;
+                    self.dyn_trait(out)?;
+                }
+                self.lifetimes -= bound;
+
+                // The lifetime of the object
+                if !self.eat(b'L') {
+                    return Err(Invalid);
+                }
+                let lifetime = self.base62()?;
+                if lifetime != 0 {
+                    out.write_str(" + ")?;
+                    self.lifetime(out, lifetime)?;
+                }
+            }
+            b'B' => self.backref(|p| p.ty(out))?,
+            _ => {
+                // A named type is a path
+                self.pos -= 1;
+                self.path(out, true)?;
+            }
+        }
+        self.depth -= 1;
+        Ok(())
+    }
+
+    fn fn_sig<W: Write>(&mut self, out: &mut W) -> V0Result {
+        let bound = self.binder(out)?;
+        if self.eat(b'U') {
+            out.write_str("unsafe ")?;
+        }
+        if self.eat(b'K') {
+            out.write_str("extern \"")?;
+            match self.eat(b'C') {
+                true => out.write_char('C')?,
+                // The dashes of an ABI like C-unwind are written as underscores
+                false => for c in self.ident()?.name.chars() {
+                    out.write_char(if c == '_' { '-' } else { c })?;
+                },
+            }
+            out.write_str("\" ")?;
+        }
+
+        out.write_str("fn(")?;
+        let mut first = true;
+        while !self.eat(b'E') {
+            if !first {
+                out.write_str(", ")?;
+            }
+            first = false;
+            self.ty(out)?;
+        }
+        out.write_char(')')?;
+
+        if !self.eat(b'u') {
+            out.write_str(" -> ")?;
+            self.ty(out)?;
+        }
+        self.lifetimes -= bound;
+        Ok(())
+    }
+
+    /// `G` and the number of lifetimes bound by `for<'a, ...>` minus one
+    fn binder<W: Write>(&mut self, out: &mut W) -> core::result::Result<u64, Invalid> {
+        if !self.eat(b'G') {
+            return Ok(0);
+        }
+        let bound = self.base62()?.checked_add(1).ok_or(Invalid)?;
+        // The count comes from the symbol, a corrupt one would keep the loop busy
+        if bound > MAX_BOUND_LIFETIMES {
+            return Err(Invalid);
+        }
+        out.write_str("for<")?;
+        for i in 0..bound {
+            if i > 0 {
+                out.write_str(", ")?;
+            }
+            self.lifetimes += 1;
+            self.lifetime(out, 1)?;
+        }
+        out.write_str("> ")?;
+        Ok(bound)
+    }
+
+    /// A lifetime by its De Bruijn index, counted from the innermost binder, 0 is erased
+    fn lifetime<W: Write>(&mut self, out: &mut W, index: u64) -> V0Result {
+        if index == 0 {
+            return Ok(out.write_str("'_")?);
+        }
+        match self.lifetimes.checked_sub(index) {
+            Some(depth) if depth < 26 => write!(out, "'{}", (b'a' + depth as u8) as char)?,
+            Some(depth) => write!(out, "'_{}", depth)?,
+            None => return Err(Invalid),
+        }
+        Ok(())
+    }
+
+    /// A trait with its associated types, like Iterator<Item = u8>
+    fn dyn_trait<W: Write>(&mut self, out: &mut W) -> V0Result {
+        let mut open = self.path_open(out)?;
+        while self.eat(b'p') {
+            out.write_str(if open { ", " } else { "<" })?;
+            open = true;
+            self.ident()?.print(out)?;
+            out.write_str(" = ")?;
+            self.ty(out)?;
+        }
+        if open {
+            out.write_char('>')?;
+        }
+        Ok(())
+    }
+
+    /// A path in a type whose generic arguments aren't closed with `>`, so that associated
+    /// types can follow. Whether there are generic arguments is returned.
+    fn path_open<W: Write>(&mut self, out: &mut W) -> core::result::Result<bool, Invalid> {
+        match self.peek() {
+            Some(b'I') => {
+                self.pos += 1;
+                self.path(out, true)?;
+                out.write_char('<')?;
+                self.generic_args(out)?;
+                Ok(true)
+            }
+            Some(b'B') => {
+                self.pos += 1;
+                let mut open = false;
+                self.backref(|p| {
+                    open = p.path_open(out)?;
+                    Ok(())
+                })?;
+                Ok(open)
+            }
+            _ => {
+                self.path(out, true)?;
+                Ok(false)
+            }
+        }
+    }
+
+    /// A constant generic argument, its type followed by the value in hexadecimal
+    fn konst<W: Write>(&mut self, out: &mut W) -> V0Result {
+        if self.eat(b'B') {
+            return self.backref(|p| p.konst(out));
+        }
+        let ty = self.next()?;
+        if ty == b'p' {
+            return Ok(out.write_char('_')?);
+        }
+
+        let negative = self.eat(b'n');
+        let start = self.pos;
+        while self.peek().map_or(false, |c| c.is_ascii_hexdigit()) {
+            self.pos += 1;
+        }
+        let hex = core::str::from_utf8(&self.sym[start..self.pos]).map_err(|_| Invalid)?;
+        if !self.eat(b'_') {
+            return Err(Invalid);
+        }
+
+        let value = match hex.len() {
+            0 => Some(0),
+            1..=16 => u64::from_str_radix(hex, 16).ok(),
+            _ => None,
+        };
+        match (ty, value) {
+            (b'b', Some(0)) => out.write_str("false")?,
+            (b'b', Some(1)) => out.write_str("true")?,
+            (b'c', Some(v)) => {
+                let c = char::from_u32(v as u32).ok_or(Invalid)?;
+                write!(out, "{:?}", c)?;
+            }
+            (_, Some(v)) => {
+                if negative {
+                    out.write_char('-')?;
+                }
+                write!(out, "{}", v)?;
+                if let Some(name) = basic_type(ty).filter(|_| !self.short) {
+                    out.write_str(name)?;
+                }
+            }
+            (_, None) => write!(out, "0x{}", hex)?,
+        }
+        Ok(())
+    }
+}
+
+fn basic_type(c: u8) -> Option<&'static str> {
+    Some(match c {
+        b'a' => "i8",
+        b'b' => "bool",
+        b'c' => "char",
+        b'd' => "f64",
+        b'e' => "str",
+        b'f' => "f32",
+        b'h' => "u8",
+        b'i' => "isize",
+        b'j' => "usize",
+        b'l' => "i32",
+        b'm' => "u32",
+        b'n' => "i128",
+        b'o' => "u128",
+        b's' => "i16",
+        b't' => "u16",
+        b'u' => "()",
+        b'v' => "...",
+        b'x' => "i64",
+        b'y' => "u64",
+        b'z' => "!",
+        b'p' => "_",
+        _ => return None,
+    })
+}
+
+struct Ident<'s> {
+    name: &'s str,
+    punycode: bool,
+}
+
+impl Ident<'_> {
+    fn print<W: Write>(&self, out: &mut W) -> fmt::Result {
+        // Decoding punycode isn't supported, the encoded name is shown
+        match self.punycode {
+            true => write!(out, "punycode{{{}}}", self.name),
+            false => out.write_str(self.name),
+        }
+    }
+}
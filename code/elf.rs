@@ -1,6 +1,7 @@
 #![allow(unused)]
 #![allow(non_camel_case_types)]
 use core::ffi::CStr;
+use crate::demangle::demangle;
 
 // ==============================================================================
 // Elf header
@@ -240,6 +241,7 @@ pub enum SHT {
     SHT_PREINIT_ARRAY   = 16,
     SHT_GROUP           = 17,
     SHT_SYMTAB_SHNDX    = 18,
+    SHT_RELR            = 19,
     SHT_GNU_ATTRIBUTES  = 0x6ffffff5,
     SHT_GNU_HASH	    = 0x6ffffff6,
     SHT_GNU_LIBLIST	    = 0x6ffffff7,
@@ -275,6 +277,7 @@ impl From<u32> for SHT {
             16 => Self::SHT_PREINIT_ARRAY,
             17 => Self::SHT_GROUP,
             18 => Self::SHT_SYMTAB_SHNDX,
+            19 => Self::SHT_RELR,
             0x6ffffff5 => Self::SHT_GNU_ATTRIBUTES, 
             0x6ffffff6 => Self::SHT_GNU_HASH,
             0x6ffffff7 => Self::SHT_GNU_LIBLIST,
@@ -308,6 +311,7 @@ impl SHT {
             Self::SHT_PREINIT_ARRAY => "PREINIT_ARRAY",
             Self::SHT_GROUP         => "GROUP",
             Self::SHT_SYMTAB_SHNDX  => "SYMTAB_SHNDX",
+            Self::SHT_RELR          => "RELR",
             Self::SHT_GNU_ATTRIBUTES=> "GNU_ATTR", 
             Self::SHT_GNU_HASH      => "GNU_HASH",
             Self::SHT_GNU_LIBLIST   => "GNU_LIBLIST",
@@ -324,6 +328,7 @@ impl SHT {
 pub const SHF_WRITE:          u64 = 0x00000001;
 pub const SHF_ALLOC:          u64 = 0x00000002;
 pub const SHF_EXECINSTR:      u64 = 0x00000004;
+pub const SHF_INFO_LINK:      u64 = 0x00000040;
 pub const SHF_RELA_LIVEPATCH: u64 = 0x00100000;
 pub const SHF_RO_AFTER_INIT:  u64 = 0x00200000;
 pub const SHF_MASKPROC:       u64 = 0xf0000000;
@@ -743,6 +748,73 @@ impl Rela {
     }
 }
 
+/// The entries of a SHT_REL, SHT_RELA or SHT_RELR section
+#[derive(Debug, Clone, Copy)]
+pub enum Relocations<'a> {
+    Rel(&'a [Rel]),
+    Rela(&'a [Rela]),
+
+    /// Packed R_X86_64_RELATIVE relocations, see [RelrOffsets]
+    Relr(&'a [u64]),
+}
+
+impl Relocations<'_> {
+    /// The number of entries, which are words for SHT_RELR like in readelf
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Rel(entries) => entries.len(),
+            Self::Rela(entries) => entries.len(),
+            Self::Relr(entries) => entries.len(),
+        }
+    }
+}
+
+/// The offsets of the relative relocations of a SHT_RELR section. An even entry is the
+/// offset of a relocation, an odd one a bitmap of the 63 words after the last one.
+#[derive(Debug, Clone)]
+pub struct RelrOffsets<'a> {
+    entries: &'a [u64],
+    next: usize,
+
+    /// The word the bitmap at `next` starts at
+    base: u64,
+
+    /// The bit of the bitmap at `next`, 0 before it is started
+    bit: u32,
+}
+
+impl<'a> RelrOffsets<'a> {
+    pub fn new(entries: &'a [u64]) -> Self {
+        Self { entries, next: 0, base: 0, bit: 0 }
+    }
+}
+
+impl Iterator for RelrOffsets<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let entry = *self.entries.get(self.next)?;
+            if entry & 1 == 0 {
+                self.next += 1;
+                self.base = entry.wrapping_add(8);
+                return Some(entry);
+            }
+
+            while self.bit < 63 {
+                let bit = self.bit;
+                self.bit += 1;
+                if (entry >> 1) & (1 << bit) != 0 {
+                    return Some(self.base.wrapping_add(bit as u64 * 8));
+                }
+            }
+            self.next += 1;
+            self.base = self.base.wrapping_add(63 * 8);
+            self.bit = 0;
+        }
+    }
+}
+
 #[repr(u32)]
 #[derive(Debug, Clone)]
 pub enum R {
@@ -935,16 +1007,110 @@ pub enum NT {
 // ==============================================================================
 // Elf file
 // ==============================================================================
+/// Why File::parse() or a checked accessor has rejected a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElfError {
+    /// The file doesn't start with ELFMAG
+    BadMagic,
+
+    /// e_ident[EI_CLASS], only ELFCLASS64 is supported
+    UnsupportedClass(u8),
+
+    /// e_ident[EI_DATA], only ELFDATA2LSB is supported
+    UnsupportedEncoding(u8),
+
+    /// The file is shorter than the ELF header
+    TruncatedHeader,
+
+    /// The buffer isn't aligned for the ELF header
+    Misaligned,
+
+    /// A table lies outside of the file or its entries don't have the expected size
+    OutOfRange(&'static str),
+
+    /// A name isn't inside its string table, isn't terminated or isn't UTF-8
+    BadStringIndex(u32),
+}
+
+impl core::fmt::Display for ElfError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::BadMagic               => write!(f, "not an ELF file"),
+            Self::UnsupportedClass(c)    => write!(f, "unsupported ELF class {}", c),
+            Self::UnsupportedEncoding(d) => write!(f, "unsupported data encoding {}", d),
+            Self::TruncatedHeader        => write!(f, "truncated ELF header"),
+            Self::Misaligned             => write!(f, "misaligned ELF header"),
+            Self::OutOfRange(table)      => write!(f, "{} out of range", table),
+            Self::BadStringIndex(idx)    => write!(f, "bad string index {}", idx),
+        }
+    }
+}
+
+impl From<ElfError> for crate::Error {
+    fn from(_: ElfError) -> Self {
+        crate::Error::ENOEXEC
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct File<'a>{
     pub ehdr: &'a Ehdr,
     pub phdrs: &'a [Phdr],
     pub shdrs: &'a [Shdr],
+
+    /// The size of the file, which from_ptr() doesn't know and doesn't check
+    len: usize,
 }
 
 impl<'a> File<'a> {
+    /// Check the ELF header and that the program and the section headers lie within `buf`.
+    /// The other tables are checked when they are accessed.
+    pub fn parse(buf: &'a [u8]) -> core::result::Result<Self, ElfError> {
+        if buf.len() < ELFMAG.len() {
+            return Err(ElfError::TruncatedHeader);
+        }
+        if buf[..ELFMAG.len()] != ELFMAG {
+            return Err(ElfError::BadMagic);
+        }
+        if buf.len() < core::mem::size_of::<Ehdr>() {
+            return Err(ElfError::TruncatedHeader);
+        }
+        match buf[EI_CLASS] {
+            ELFCLASS64 => {}
+            class => return Err(ElfError::UnsupportedClass(class)),
+        }
+        match buf[EI_DATA] {
+            ELFDATA2LSB => {}
+            data => return Err(ElfError::UnsupportedEncoding(data)),
+        }
+        if buf.as_ptr() as usize % core::mem::align_of::<Ehdr>() != 0 {
+            return Err(ElfError::Misaligned);
+        }
+
+        let ehdr = unsafe { &*(buf.as_ptr() as *const Ehdr) };
+        let mut file = Self { ehdr, phdrs: &[], shdrs: &[], len: buf.len() };
+        if ehdr.e_phnum > 0 {
+            if ehdr.e_phentsize as usize != core::mem::size_of::<Phdr>() {
+                return Err(ElfError::OutOfRange("program headers"));
+            }
+            let size = ehdr.e_phnum as u64 * ehdr.e_phentsize as u64;
+            file.phdrs = file.table(ehdr.e_phoff, size, "program headers")?;
+        }
+        if ehdr.e_shnum > 0 {
+            if ehdr.e_shentsize as usize != core::mem::size_of::<Shdr>() {
+                return Err(ElfError::OutOfRange("section headers"));
+            }
+            let size = ehdr.e_shnum as u64 * ehdr.e_shentsize as u64;
+            file.shdrs = file.table(ehdr.e_shoff, size, "section headers")?;
+        }
+        Ok(file)
+    }
+
+    /// Doesn't check the headers, only the tables the checked accessors read are kept
+    /// within `buf`
+    #[deprecated(note = "use File::parse, which checks the headers")]
     pub unsafe fn from_slice(buf: &'a [u8]) -> Self {
-        Self::from_ptr(buf.as_ptr())
+        Self { len: buf.len(), ..Self::from_ptr(buf.as_ptr()) }
     }
 
     pub unsafe fn from_ptr(p: *const u8) -> Self {
@@ -957,99 +1123,159 @@ impl<'a> File<'a> {
             p.offset(ehdr.e_shoff as isize) as *const Shdr,
             ehdr.e_shnum as usize
         );
-        Self { ehdr, phdrs, shdrs }
+        Self { ehdr, phdrs, shdrs, len: usize::MAX }
     }
 
-    pub unsafe fn shstr(&self, sh_name: u32) -> &str {
-        let h = &self.shdrs[self.ehdr.e_shstrndx as usize];
-        let p = (self.ehdr as *const _ as *const i8)
-            .add(h.sh_offset as usize)
-            .add(sh_name as usize);
-        CStr::from_ptr(p).to_str().unwrap()
+    /// `size` bytes at `offset` in the file
+    fn bytes(&self, offset: u64, size: u64) -> Option<&'a [u8]> {
+        let end = offset.checked_add(size)?;
+        if end > self.len as u64 {
+            return None;
+        }
+        let base = self.ehdr as *const Ehdr as *const u8;
+        Some(unsafe { core::slice::from_raw_parts(base.add(offset as usize), size as usize) })
     }
 
-    pub unsafe fn strtab(&self, st_name: u32) -> &str {
-        for h in self.shdrs.into_iter() {
-            if h.sh_type == SHT::SHT_STRTAB as u32 {
-                let p = (self.ehdr as *const _ as *const i8)
-                    .add(h.sh_offset as usize)
-                    .add(st_name as usize);
-                return CStr::from_ptr(p).to_str().unwrap();
-            }
+    /// `size` bytes at `offset` as a table of the repr(C) entries T
+    fn table<T>(&self, offset: u64, size: u64, name: &'static str) -> core::result::Result<&'a [T], ElfError> {
+        let bytes = self.bytes(offset, size).ok_or(ElfError::OutOfRange(name))?;
+        if bytes.as_ptr() as usize % core::mem::align_of::<T>() != 0
+            || bytes.len() % core::mem::size_of::<T>() != 0 {
+            return Err(ElfError::OutOfRange(name));
         }
-        panic!("Missing strtab");
+        let len = bytes.len() / core::mem::size_of::<T>();
+        Ok(unsafe { core::slice::from_raw_parts(bytes.as_ptr() as *const T, len) })
     }
 
-    pub fn symtab(&self) -> &[Sym] {
-        for h in self.shdrs.into_iter() {
-            if h.sh_type == SHT::SHT_SYMTAB as u32 {
-                return unsafe {
-                    let p = (self.ehdr as *const _ as *const i8)
-                        .add(h.sh_offset as usize) as *const Sym;
-                    core::slice::from_raw_parts(p, (h.sh_size / h.sh_entsize) as usize)
-                };
-            }
+    /// The entries of a section like .symtab, whose sh_entsize has to match T
+    fn section_table<T>(&self, h: &Shdr, name: &'static str) -> core::result::Result<&'a [T], ElfError> {
+        if h.sh_entsize as usize != core::mem::size_of::<T>() {
+            return Err(ElfError::OutOfRange(name));
         }
+        self.table(h.sh_offset, h.sh_size, name)
+    }
 
-        &[]
+    /// The first section of the type
+    pub fn section(&self, sh_type: SHT) -> Option<&'a Shdr> {
+        let sh_type = sh_type as u32;
+        self.shdrs.iter().find(|h| h.sh_type == sh_type)
     }
 
-    pub fn dynsym(&self) -> &[Sym] {
-        for h in self.shdrs.into_iter() {
-            if h.sh_type == SHT::SHT_DYNSYM as u32 {
-                return unsafe {
-                    let p = (self.ehdr as *const _ as *const i8)
-                        .add(h.sh_offset as usize) as *const Sym;
-                    core::slice::from_raw_parts(p, (h.sh_size / h.sh_entsize) as usize)
-                };
-            }
+    /// The contents of a section, SHT_NOBITS like .bss has none in the file
+    pub fn section_data(&self, h: &Shdr) -> core::result::Result<&'a [u8], ElfError> {
+        if h.sh_type == SHT::SHT_NOBITS as u32 {
+            return Ok(&[]);
         }
+        self.bytes(h.sh_offset, h.sh_size).ok_or(ElfError::OutOfRange("section"))
+    }
 
-        &[]
+    /// The contents of a segment in the file, without the zeroed rest up to p_memsz
+    pub fn segment_data(&self, h: &Phdr) -> core::result::Result<&'a [u8], ElfError> {
+        self.bytes(h.p_offset, h.p_filesz).ok_or(ElfError::OutOfRange("segment"))
     }
 
-    pub fn dynamic(&self) -> &[Dyn] {
-        for h in self.shdrs.into_iter() {
-            if h.sh_type == SHT::SHT_DYNAMIC as u32 {
-                return unsafe {
-                    let p = (self.ehdr as *const _ as *const i8)
-                        .add(h.sh_offset as usize) as *const Dyn;
-                    core::slice::from_raw_parts(p, (h.sh_size / h.sh_entsize) as usize)
-                };
-            }
-        }
+    /// The section sh_link of `h` refers to, the string table of a symbol table for example
+    pub fn linked(&self, h: &Shdr) -> core::result::Result<&'a Shdr, ElfError> {
+        self.shdrs.get(h.sh_link as usize).ok_or(ElfError::OutOfRange("linked section"))
+    }
 
-        &[]
+    /// The null-terminated string at `idx` in the string table `h`
+    pub fn string(&self, h: &Shdr, idx: u32) -> core::result::Result<&'a str, ElfError> {
+        let data = self.section_data(h)?;
+        let s = data.get(idx as usize..).ok_or(ElfError::BadStringIndex(idx))?;
+        let len = s.iter().position(|&c| c == 0).ok_or(ElfError::BadStringIndex(idx))?;
+        core::str::from_utf8(&s[..len]).map_err(|_| ElfError::BadStringIndex(idx))
     }
 
-    pub fn rel(&self) -> &[Rel] {
-        // TODO: Fix this bug: This returns only with the first relocation table but there might be more
-        for h in self.shdrs.into_iter() {
-            if h.sh_type == SHT::SHT_REL as u32 {
-                return unsafe {
-                    let p = (self.ehdr as *const _ as *const i8)
-                        .add(h.sh_offset as usize) as *const Rel;
-                    core::slice::from_raw_parts(p, (h.sh_size / h.sh_entsize) as usize)
-                };
-            }
-        }
+    /// The name of the section `h` in the section header string table
+    pub fn section_name(&self, h: &Shdr) -> core::result::Result<&'a str, ElfError> {
+        let names = self.shdrs.get(self.ehdr.e_shstrndx as usize).ok_or(ElfError::OutOfRange("section names"))?;
+        self.string(names, h.sh_name)
+    }
 
-        &[]
+    /// The entries of a SHT_SYMTAB or SHT_DYNSYM section
+    pub fn symbols(&self, h: &Shdr) -> core::result::Result<&'a [Sym], ElfError> {
+        self.section_table(h, "symbol table")
     }
 
-    pub fn rela(&self) -> &[Rela] {
-        // TODO: Fix this bug: This returns only with the first relocation table but there might be more
-        for h in self.shdrs.into_iter() {
-            if h.sh_type == SHT::SHT_RELA as u32 {
-                return unsafe {
-                    let p = (self.ehdr as *const _ as *const i8)
-                        .add(h.sh_offset as usize) as *const Rela;
-                    core::slice::from_raw_parts(p, (h.sh_size / h.sh_entsize) as usize)
-                };
-            }
-        }
+    /// The name of a symbol of the table `h`, in the string table it links to
+    pub fn symbol_name(&self, h: &Shdr, sym: &Sym) -> core::result::Result<&'a str, ElfError> {
+        self.string(self.linked(h)?, sym.st_name)
+    }
+
+    /// The entries of a SHT_DYNAMIC section
+    pub fn dyns(&self, h: &Shdr) -> core::result::Result<&'a [Dyn], ElfError> {
+        self.section_table(h, "dynamic section")
+    }
+
+    /// The entries of a SHT_REL section
+    pub fn rels(&self, h: &Shdr) -> core::result::Result<&'a [Rel], ElfError> {
+        self.section_table(h, "relocation table")
+    }
+
+    /// The entries of a SHT_RELA section
+    pub fn relas(&self, h: &Shdr) -> core::result::Result<&'a [Rela], ElfError> {
+        self.section_table(h, "relocation table")
+    }
+
+    /// The entries of a SHT_RELR section
+    pub fn relrs(&self, h: &Shdr) -> core::result::Result<&'a [u64], ElfError> {
+        self.section_table(h, "relocation table")
+    }
 
-        &[]
+    /// Every SHT_REL, SHT_RELA and SHT_RELR section, an object file has one for each section
+    /// with code or data like .rela.text.main
+    pub fn relocation_sections(&self) -> RelocationSections<'a> {
+        RelocationSections { file: self.clone(), next: 0 }
+    }
+
+    // The accessors below don't fail, a missing or broken table is empty and a broken name
+    // is <corrupt> like in readelf.
+
+    pub fn shstr(&self, sh_name: u32) -> &str {
+        let names = self.shdrs.get(self.ehdr.e_shstrndx as usize);
+        names.and_then(|h| self.string(h, sh_name).ok()).unwrap_or("<corrupt>")
+    }
+
+    pub fn strtab(&self, st_name: u32) -> &str {
+        let strtab = self.section(SHT::SHT_STRTAB);
+        strtab.and_then(|h| self.string(h, st_name).ok()).unwrap_or("<corrupt>")
+    }
+
+    /// The name of a symbol of symtab(), which are in the string table linked to .symtab.
+    /// strtab() takes the first one, which is .dynstr in a dynamically linked file.
+    pub fn symtab_str(&self, st_name: u32) -> &str {
+        let Some(h) = self.section(SHT::SHT_SYMTAB) else {
+            return self.strtab(st_name);
+        };
+        self.linked(h).and_then(|strtab| self.string(strtab, st_name)).unwrap_or("<corrupt>")
+    }
+
+    pub fn symtab(&self) -> &[Sym] {
+        let symtab = self.section(SHT::SHT_SYMTAB);
+        symtab.and_then(|h| self.symbols(h).ok()).unwrap_or(&[])
+    }
+
+    pub fn dynsym(&self) -> &[Sym] {
+        let dynsym = self.section(SHT::SHT_DYNSYM);
+        dynsym.and_then(|h| self.symbols(h).ok()).unwrap_or(&[])
+    }
+
+    pub fn dynamic(&self) -> &[Dyn] {
+        let dynamic = self.section(SHT::SHT_DYNAMIC);
+        dynamic.and_then(|h| self.dyns(h).ok()).unwrap_or(&[])
+    }
+
+    /// The first SHT_REL section, relocation_sections() has all of them
+    pub fn rel(&self) -> &[Rel] {
+        let rel = self.section(SHT::SHT_REL);
+        rel.and_then(|h| self.rels(h).ok()).unwrap_or(&[])
+    }
+
+    /// The first SHT_RELA section, relocation_sections() has all of them
+    pub fn rela(&self) -> &[Rela] {
+        let rela = self.section(SHT::SHT_RELA);
+        rela.and_then(|h| self.relas(h).ok()).unwrap_or(&[])
     }
 
     pub fn dump_phdrs(&self) {
@@ -1115,14 +1341,15 @@ impl<'a> File<'a> {
                 h.sh_link,
                 h.sh_info,
                 h.sh_addralign,
-                unsafe { self.shstr(h.sh_name) }
+                self.shstr(h.sh_name)
             );
         }
 
         crate::println!("");
     }
 
-    fn dump_symbols(&self, symbols: &[Sym]) {
+    /// `name` looks up the names in the string table of `symbols`
+    fn dump_symbols(&self, symbols: &[Sym], name: fn(&Self, u32) -> &str, demangle: bool) {
         crate::println!("  {:<3} {:<8} {:<18} {:<10} {:<6} {:<10} {:<5} {}", 
             "Idx",
             "Type",
@@ -1144,29 +1371,31 @@ impl<'a> File<'a> {
                 s.st_bind().as_str(),
                 s.st_visibility().as_str(),
                 s.st_shndx,
-                unsafe { self.strtab(s.st_name) }
+                SymName(name(self, s.st_name), demangle)
             );
         }
     }
 
-    pub fn dump_symtab(&self) {
+    /// With `demangle` the Rust names are demangled like readelf -C
+    pub fn dump_symtab(&self, demangle: bool) {
         //crate::println!("{:?}", ""); // NOTE: Without this it will segfault in the for loop...
         let symtab = self.symtab();
 
         crate::println!("Static symbols: {:?}", symtab.len());
         if symtab.len() > 0 {
-            self.dump_symbols(symtab);
+            self.dump_symbols(symtab, Self::symtab_str, demangle);
         }
 
         crate::println!("");
     }
 
-    pub fn dump_dynsym(&self) {
+    /// With `demangle` the Rust names are demangled like readelf -C
+    pub fn dump_dynsym(&self, demangle: bool) {
         //crate::println!("{:?}", ""); // NOTE: Without this it will segfault in the for loop...
         let dynsym = self.dynsym();
         crate::println!("Dynamic symbols: {:?}", dynsym.len());
         if dynsym.len() > 0 {
-            self.dump_symbols(dynsym);
+            self.dump_symbols(dynsym, Self::strtab, demangle);
         }
 
         crate::println!("");
@@ -1189,7 +1418,7 @@ impl<'a> File<'a> {
                     crate::println!("  {:<3} {:<15} {}",
                         idx,
                         s.d_tag().as_str(),
-                        unsafe { self.strtab(s.d_val as u32) }
+                        self.strtab(s.d_val as u32)
                     );
                 }
                 _ => {
@@ -1209,45 +1438,188 @@ impl<'a> File<'a> {
         crate::println!("");
     }
 
-    pub fn dump_rel(&self) {
-        let slice = self.rel();
-        crate::println!("Dynamic reloaction: {:?}", slice.len());
+    /// The REL and RELR sections, with `demangle` the Rust names are demangled like readelf -C
+    pub fn dump_rel(&self, demangle: bool) {
+        for section in self.relocation_sections() {
+            let section = match section {
+                Ok(section) => section,
+                Err(err) => {
+                    crate::println!("Relocation section: {}", err);
+                    continue;
+                }
+            };
+            let header = || {
+                self.dump_relocation_header(&section);
+                crate::println!("  {:<18} {:<18} {:<22} {:<10} {}", "Offset", "Info", "Type", "Symbol", "Name");
+            };
+            match section.entries {
+                Relocations::Rela(_) => continue,
+                Relocations::Rel(entries) => {
+                    header();
+                    for e in entries {
+                        crate::println!("  0x{:0>16x} {:0>16x} {:<22} 0x{:0>8} {}",
+                            e.r_offset,
+                            e.r_info,
+                            e.r_type().as_str(),
+                            e.r_sym(),
+                            SymName(self.relocation_symbol(&section, e.r_sym()), demangle)
+                        );
+                    }
+                }
+                Relocations::Relr(entries) => {
+                    header();
+                    for offset in RelrOffsets::new(entries) {
+                        crate::println!("  0x{:0>16x} {:0>16x} {:<22} 0x{:0>8} {}",
+                            offset,
+                            R::R_X86_64_RELATIVE as u64,
+                            R::R_X86_64_RELATIVE.as_str(),
+                            0,
+                            ""
+                        );
+                    }
+                }
+            }
+            crate::println!("");
+        }
+    }
 
-        if slice.len() == 0 {
-            return;
+    /// The RELA sections, with `demangle` the Rust names are demangled like readelf -C
+    pub fn dump_rela(&self, demangle: bool) {
+        for section in self.relocation_sections() {
+            let section = match section {
+                Ok(section) => section,
+                Err(err) => {
+                    crate::println!("Relocation section: {}", err);
+                    continue;
+                }
+            };
+            let Relocations::Rela(entries) = section.entries else {
+                continue;
+            };
+            self.dump_relocation_header(&section);
+
+            crate::println!("  {:<18} {:<18} {:<6} {:<22} {:<10} {}", "Offset", "Info", "Addend", "Type", "Symbol", "Name");
+            for e in entries {
+                crate::println!("  0x{:0>16x} 0x{:0>16x} {:<6} {:<22} 0x{:0>8} {}",
+                    e.r_offset,
+                    e.r_info,
+                    e.r_addend,
+                    e.r_type().as_str(),
+                    e.r_sym(),
+                    SymName(self.relocation_symbol(&section, e.r_sym()), demangle)
+                );
+            }
+            crate::println!("");
         }
+    }
 
-        crate::println!("  {:<18} {:<18} {:<22} {:<10} {}", "Offset", "Info", "Type", "Symbol", "Name");
-        for e in slice.into_iter() {
-            crate::println!("  0x{:0>16x} {:0>16x} {:<22} 0x{:0>8} {}", 
-                e.r_offset,
-                e.r_info,
-                e.r_type().as_str(),
-                e.r_sym(),
-                "hardcoded-symbol-name"
+    fn dump_relocation_header(&self, section: &RelocationSection) {
+        let name = |h: &Shdr| self.section_name(h).unwrap_or("<corrupt>");
+        crate::println!("Relocation section '{}' at offset 0x{:x} contains {} entries:",
+            name(section.header),
+            section.header.sh_offset,
+            section.entries.len()
+        );
+        if let Some(target) = section.target {
+            crate::println!("  Applies to '{}', symbols in '{}'",
+                name(target),
+                section.symtab.map_or("", name)
             );
         }
     }
 
-    pub fn dump_rela(&self) {
-        let slice = self.rela();
-        crate::println!("Dynamic reloaction addend: {:?}", slice.len());
+    /// The name of the symbol `idx` of a relocation, the section name for STT_SECTION
+    fn relocation_symbol(&self, section: &RelocationSection, idx: u32) -> &'a str {
+        if idx == 0 {
+            return "";
+        }
+        let Some(symtab) = section.symtab else {
+            return "<corrupt>";
+        };
+        let Some(sym) = self.symbols(symtab).ok().and_then(|syms| syms.get(idx as usize)) else {
+            return "<corrupt>";
+        };
+        if sym.st_info & 0xf == STT::STT_SECTION as u8 {
+            let h = self.shdrs.get(sym.st_shndx as usize);
+            return h.and_then(|h| self.section_name(h).ok()).unwrap_or("<corrupt>");
+        }
+        self.symbol_name(symtab, sym).unwrap_or("<corrupt>")
+    }
+}
 
-        if slice.len() == 0 {
-            return;
+/// A relocation section with the section it applies to and the symbol table it refers to
+#[derive(Debug, Clone)]
+pub struct RelocationSection<'a> {
+    pub header: &'a Shdr,
+
+    /// The section of sh_info with SHF_INFO_LINK, none for the dynamic relocations which apply
+    /// to the whole image
+    pub target: Option<&'a Shdr>,
+
+    /// The symbol table of sh_link, none for SHT_RELR
+    pub symtab: Option<&'a Shdr>,
+    pub entries: Relocations<'a>,
+}
+
+/// The iterator of File::relocation_sections()
+#[derive(Debug, Clone)]
+pub struct RelocationSections<'a> {
+    file: File<'a>,
+    next: usize,
+}
+
+impl<'a> Iterator for RelocationSections<'a> {
+    type Item = core::result::Result<RelocationSection<'a>, ElfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let file = &self.file;
+        loop {
+            let header = file.shdrs.get(self.next)?;
+            self.next += 1;
+
+            let entries = match header.sh_type() {
+                SHT::SHT_REL => file.rels(header).map(Relocations::Rel),
+                SHT::SHT_RELA => file.relas(header).map(Relocations::Rela),
+                SHT::SHT_RELR => file.relrs(header).map(Relocations::Relr),
+                _ => continue,
+            };
+            let entries = match entries {
+                Ok(entries) => entries,
+                Err(err) => return Some(Err(err)),
+            };
+
+            // sh_info names the target with SHF_INFO_LINK. In an object file every section
+            // applies to one, the flag is missing in a section group for example.
+            let info_link = header.sh_flags & SHF_INFO_LINK != 0
+                || file.ehdr.e_type == ET::ET_REL as u16;
+            let target = match (info_link, header.sh_info) {
+                (false, _) | (_, 0) => None,
+                (true, idx) => match file.shdrs.get(idx as usize) {
+                    Some(target) => Some(target),
+                    None => return Some(Err(ElfError::OutOfRange("relocation target"))),
+                },
+            };
+            let symtab = match entries {
+                Relocations::Relr(_) => None,
+                _ if header.sh_link == 0 => None,
+                _ => match file.linked(header) {
+                    Ok(symtab) => Some(symtab),
+                    Err(err) => return Some(Err(err)),
+                },
+            };
+            return Some(Ok(RelocationSection { header, target, symtab, entries }));
         }
+    }
+}
 
-        crate::println!("  {:<18} {:<18} {:<6} {:<22} {:<10} {}", "Offset", "Info", "Addend", "Type", "Symbol", "Name");
-        for e in slice.into_iter() {
-            let sym = &self.dynsym()[e.r_sym() as usize];
-            crate::println!("  0x{:0>16x} 0x{:0>16x} {:<6} {:<22} 0x{:0>8} {}", 
-                e.r_offset,
-                e.r_info,
-                e.r_addend,
-                e.r_type().as_str(),
-                e.r_sym(),
-                unsafe { self.strtab(sym.st_name) }
-            );
+/// A symbol name in the dumps, demangled without the hash if the flag is set
+struct SymName<'a>(&'a str, bool);
+
+impl core::fmt::Display for SymName<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self.1 {
+            true => write!(f, "{:#}", demangle(self.0)),
+            false => f.write_str(self.0),
         }
     }
 }
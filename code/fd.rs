@@ -0,0 +1,138 @@
+//! Owned and borrowed file descriptors.
+//!
+//! The wrappers in [crate::syscall] which create a descriptor return an [OwnedFd] which is closed
+//! when it is dropped. The other wrappers still take the raw number so they can be used with
+//! descriptors the program doesn't own, like the standard streams. [BorrowedFd] ties a raw
+//! descriptor to the lifetime of its owner.
+//!
+//! ```ignore
+//! let fd = open("/etc/hostname", O_RDONLY | O_CLOEXEC, 0)?;
+//! let n = read(fd.as_raw_fd(), &mut buf)?;
+//! // closed here
+//! ```
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+
+use crate::syscall::{close, fcntl};
+use crate::constants::F_DUPFD_CLOEXEC;
+use crate::error::Result;
+
+/// The number of a file descriptor as the kernel sees it
+pub type RawFd = u32;
+
+/// A file descriptor which is closed on drop
+pub struct OwnedFd {
+    fd: RawFd,
+}
+
+impl OwnedFd {
+    /// Duplicate the descriptor with FD_CLOEXEC set on the copy
+    pub fn try_clone(&self) -> Result<Self> {
+        self.as_fd().try_clone_to_owned()
+    }
+}
+
+impl Drop for OwnedFd {
+    fn drop(&mut self) {
+        let _ = close(self.fd);
+    }
+}
+
+impl fmt::Debug for OwnedFd {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("OwnedFd").field(&self.fd).finish()
+    }
+}
+
+/// A file descriptor owned by someone else who keeps it open for 'a
+#[derive(Clone, Copy)]
+pub struct BorrowedFd<'a> {
+    fd: RawFd,
+    owner: PhantomData<&'a OwnedFd>,
+}
+
+impl BorrowedFd<'_> {
+    /// # Safety
+    /// The descriptor has to stay open for the returned lifetime
+    pub const unsafe fn borrow_raw(fd: RawFd) -> Self {
+        Self { fd, owner: PhantomData }
+    }
+
+    /// Duplicate the descriptor with FD_CLOEXEC set on the copy
+    pub fn try_clone_to_owned(&self) -> Result<OwnedFd> {
+        let fd = fcntl(self.fd, F_DUPFD_CLOEXEC, 0)?;
+        Ok(OwnedFd { fd: fd as RawFd })
+    }
+}
+
+impl fmt::Debug for BorrowedFd<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("BorrowedFd").field(&self.fd).finish()
+    }
+}
+
+// ==============================================================================
+// Conversions
+// ==============================================================================
+/// Borrow the descriptor of an object without taking ownership of it
+pub trait AsFd {
+    fn as_fd(&self) -> BorrowedFd<'_>;
+}
+
+pub trait AsRawFd {
+    fn as_raw_fd(&self) -> RawFd;
+}
+
+/// Give up the ownership of the descriptor, the caller has to close it
+pub trait IntoRawFd {
+    fn into_raw_fd(self) -> RawFd;
+}
+
+pub trait FromRawFd {
+    /// # Safety
+    /// The descriptor has to be open and must not be owned by anyone else
+    unsafe fn from_raw_fd(fd: RawFd) -> Self;
+}
+
+impl AsFd for OwnedFd {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw(self.fd) }
+    }
+}
+
+impl AsFd for BorrowedFd<'_> {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        *self
+    }
+}
+
+impl<T: AsFd> AsFd for &T {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        T::as_fd(self)
+    }
+}
+
+impl AsRawFd for OwnedFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl AsRawFd for BorrowedFd<'_> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl IntoRawFd for OwnedFd {
+    fn into_raw_fd(self) -> RawFd {
+        ManuallyDrop::new(self).fd
+    }
+}
+
+impl FromRawFd for OwnedFd {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Self { fd }
+    }
+}
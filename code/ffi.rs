@@ -23,6 +23,23 @@ unsafe fn memcpy(dst: *mut u8, src: *const u8, len: usize) -> *mut u8 {
     dst
 }
 
+#[no_mangle]
+unsafe fn memmove(dst: *mut u8, src: *const u8, len: usize) -> *mut u8 {
+    if (dst as usize) <= (src as usize) {
+        return memcpy(dst, src, len);
+    }
+
+    // Copy backwards so the overlapping tail of src is read before it is overwritten
+    for idx in (0..len).rev() {
+        let offset = idx.try_into().unwrap();
+        unsafe {
+            let byte = src.offset(offset).read();
+            dst.offset(offset).write(byte);
+        }
+    }
+    dst
+}
+
 #[no_mangle]
 unsafe fn strlen(buf: *const u8) -> usize {
     let mut len = 0;
@@ -0,0 +1,1055 @@
+//! Directory listing with getdents64 and path operations relative to a directory.
+//!
+//! [ReadDir] reads the records of a directory into a fixed buffer and hands out one [DirEntry]
+//! at a time. An entry borrows the buffer which is overwritten when the next records are read,
+//! so ReadDir has next_entry() instead of implementing Iterator. "." and ".." are skipped.
+//!
+//! [WalkDir] and [walk_dir] visit a whole tree depth first. Subdirectories are opened relative to
+//! their parent with openat, the full path is only built for the callback and is limited to
+//! PATH_MAX bytes.
+//!
+//! [Metadata] is the status of a file from statx, with the birth time, the mount id, the
+//! attributes and the O_DIRECT alignment besides the fields of stat.
+//!
+//! [XattrTarget] reads and changes the extended attributes of a file, [Xattrs] lists their
+//! names.
+//!
+//! [Dir] is a handle for the *at() system calls. With [Resolve] flags it confines the paths
+//! to the directory, e.g. to extract an untrusted archive.
+//!
+//! ```ignore
+//! let mut dir: ReadDir = ReadDir::open("/etc")?;
+//! while let Some(entry) = dir.next_entry() {
+//!     println!("{:?}", entry?.name);
+//! }
+//!
+//! walk_dir("/usr/share", |path, entry, _depth| {
+//!     if entry.is_file() { println!("{:?}", path); }
+//!     Ok(())
+//! })?;
+//! ```
+use core::ffi::CStr;
+use core::mem::offset_of;
+use core::ops::BitOr;
+
+use crate::fd::{AsFd, AsRawFd, BorrowedFd, OwnedFd};
+use crate::io::File;
+use crate::types::*;
+use crate::syscall::*;
+use crate::constants::*;
+use crate::limits::PATH_MAX;
+use crate::error::{Error, Result};
+
+/// Offset of the name in a linux_dirent64 record
+const NAME: usize = offset_of!(linux_dirent64, d_type) + 1;
+
+/// A record of a directory, the name borrows the buffer of the [ReadDir]
+#[derive(Debug, Clone, Copy)]
+pub struct DirEntry<'a> {
+    pub ino: u64,
+
+    /// Position of the next record, for lseek on the directory
+    pub off: i64,
+
+    /// One of the DT_* types, DT_UNKNOWN when the filesystem doesn't provide it
+    pub d_type: u8,
+
+    pub name: &'a CStr,
+}
+
+impl DirEntry<'_> {
+    pub fn is_dir(&self) -> bool {
+        self.d_type == DT_DIR
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.d_type == DT_REG
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        self.d_type == DT_LNK
+    }
+}
+
+/// An open directory with a buffer of N bytes for the records. A record takes up to 280 bytes,
+/// smaller buffers fail with EINVAL.
+pub struct ReadDir<const N: usize = 4096> {
+    fd: OwnedFd,
+    buf: [u8; N],
+    pos: usize,
+    len: usize,
+}
+
+impl<const N: usize> ReadDir<N> {
+    pub fn open(path: &str) -> Result<Self> {
+        let fd = open(path, O_RDONLY | O_DIRECTORY | O_CLOEXEC, 0)?;
+        Ok(Self::from_fd(fd))
+    }
+
+    /// Open `path` relative to the directory `dirfd`. `flags` are added to
+    /// O_RDONLY | O_DIRECTORY | O_CLOEXEC, e.g. O_NOFOLLOW.
+    pub fn openat(dirfd: i32, path: &CStr, flags: i32) -> Result<Self> {
+        let fd = openat(dirfd, path, O_RDONLY | O_DIRECTORY | O_CLOEXEC | flags, 0)?;
+        Ok(Self::from_fd(fd))
+    }
+
+    pub fn from_fd(fd: OwnedFd) -> Self {
+        Self { fd, buf: [0; N], pos: 0, len: 0 }
+    }
+
+    pub fn fd(&self) -> u32 {
+        self.fd.as_raw_fd()
+    }
+
+    /// The next entry or None at the end of the directory
+    pub fn next_entry(&mut self) -> Option<Result<DirEntry<'_>>> {
+        let (raw, pos) = loop {
+            if self.pos >= self.len {
+                match getdents64(self.fd.as_raw_fd(), &mut self.buf) {
+                    Ok(0) => return None,
+                    Ok(n) => { self.len = n; self.pos = 0; }
+                    Err(Error::EINTR) => continue,
+                    Err(err) => return Some(Err(err)),
+                }
+            }
+
+            let pos = self.pos;
+            let raw = unsafe { (self.buf.as_ptr().add(pos) as *const linux_dirent64).read_unaligned() };
+            self.pos += raw.d_reclen as usize;
+
+            match &self.buf[pos + NAME..self.pos] {
+                [b'.', 0, ..] | [b'.', b'.', 0, ..] => continue,
+                _ => break (raw, pos),
+            }
+        };
+
+        match CStr::from_bytes_until_nul(&self.buf[pos + NAME..self.pos]) {
+            Ok(name) => Some(Ok(DirEntry { ino: raw.d_ino, off: raw.d_off, d_type: raw.d_type, name })),
+            Err(_) => Some(Err(Error::EINVAL)),
+        }
+    }
+
+    /// Start again at the first entry
+    pub fn rewind(&mut self) -> Result<()> {
+        lseek(self.fd.as_raw_fd(), 0, SEEK_SET)?;
+        self.pos = 0;
+        self.len = 0;
+        Ok(())
+    }
+}
+
+impl<const N: usize> AsFd for ReadDir<N> {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+/// A directory on the way from the root of a walk, to detect symlink loops
+struct Ancestor<'a> {
+    dev: u64,
+    ino: u64,
+    parent: Option<&'a Ancestor<'a>>,
+}
+
+impl Ancestor<'_> {
+    fn contains(&self, dev: u64, ino: u64) -> bool {
+        (self.dev == dev && self.ino == ino) || self.parent.is_some_and(|p| p.contains(dev, ino))
+    }
+}
+
+/// The deepest directory a walk enters, every level holds an open descriptor and a ReadDir
+/// buffer on the stack
+const MAX_NESTING: usize = 128;
+
+/// Options of a recursive directory walk
+pub struct WalkDir {
+    max_depth: usize,
+    follow_links: bool,
+}
+
+impl WalkDir {
+    pub fn new() -> Self {
+        Self { max_depth: usize::MAX, follow_links: false }
+    }
+
+    /// The entries of the root have depth 1, the directories at `depth` are not entered.
+    /// No limit by default.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Enter the directories symlinks point to. A directory which is already on the path from
+    /// the root is reported but not entered again. Off by default.
+    pub fn follow_links(mut self, follow: bool) -> Self {
+        self.follow_links = follow;
+        self
+    }
+
+    /// Call `f` with the path, the entry and the depth of every entry below `root`, parents
+    /// before their children. The walk stops at the first error of `f` or of a directory, with
+    /// ENAMETOOLONG when a path doesn't fit into PATH_MAX bytes and with ELOOP when a directory
+    /// is nested more than 128 levels deep and max_depth doesn't stop before it.
+    pub fn walk<F>(&self, root: &str, mut f: F) -> Result<()>
+    where
+        F: FnMut(&CStr, &DirEntry, usize) -> Result<()>,
+    {
+        let mut path = [0u8; PATH_MAX];
+        crate::cstr(root.as_bytes(), &mut path)?;
+        let len = root.len();
+
+        let mut dir: ReadDir = ReadDir::open(root)?;
+        let mut stat = stat64::default();
+        fstat(dir.fd(), &mut stat)?;
+        let root = Ancestor { dev: stat.st_dev, ino: stat.st_ino, parent: None };
+        self.walk_dir(&mut dir, &mut path, len, 1, &root, &mut f)
+    }
+
+    fn walk_dir<F>(&self, dir: &mut ReadDir, path: &mut [u8; PATH_MAX], len: usize, depth: usize,
+                   parent: &Ancestor, f: &mut F) -> Result<()>
+    where
+        F: FnMut(&CStr, &DirEntry, usize) -> Result<()>,
+    {
+        let fd = dir.fd() as i32;
+        while let Some(entry) = dir.next_entry() {
+            let entry = entry?;
+
+            // <path>/<name>\0, the name starts at `start`
+            let name = entry.name.to_bytes_with_nul();
+            let start = if len > 0 && path[len - 1] == b'/' { len } else { len + 1 };
+            if start + name.len() > path.len() {
+                return Err(Error::ENAMETOOLONG);
+            }
+            path[start - 1] = b'/';
+            path[start..start + name.len()].copy_from_slice(name);
+
+            let full = unsafe { CStr::from_bytes_with_nul_unchecked(&path[..start + name.len()]) };
+            f(full, &entry, depth)?;
+
+            let candidate = match entry.d_type {
+                DT_DIR | DT_UNKNOWN => true,
+                DT_LNK => self.follow_links,
+                _ => false,
+            };
+            if !candidate || depth >= self.max_depth {
+                continue;
+            }
+
+            let name = unsafe { CStr::from_bytes_with_nul_unchecked(&path[start..start + name.len()]) };
+            let flags = if self.follow_links { 0 } else { O_NOFOLLOW };
+            let mut child: ReadDir = match ReadDir::openat(fd, name, flags) {
+                Ok(child) => child,
+                // Not a directory after all: an unknown type, a symlink to something else,
+                // a dangling symlink or, without follow_links, a symlink
+                Err(Error::ENOTDIR | Error::ENOENT | Error::ELOOP) => continue,
+                Err(err) => return Err(err),
+            };
+
+            let mut stat = stat64::default();
+            fstat(child.fd(), &mut stat)?;
+            if parent.contains(stat.st_dev, stat.st_ino) {
+                continue;
+            }
+            if depth >= MAX_NESTING {
+                return Err(Error::ELOOP);
+            }
+            let this = Ancestor { dev: stat.st_dev, ino: stat.st_ino, parent: Some(parent) };
+
+            let end = start + name.to_bytes().len();
+            self.walk_dir(&mut child, path, end, depth + 1, &this, f)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for WalkDir {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Walk the whole tree below `root` without following symlinks, see [WalkDir::walk]
+pub fn walk_dir<F>(root: &str, f: F) -> Result<()>
+where
+    F: FnMut(&CStr, &DirEntry, usize) -> Result<()>,
+{
+    WalkDir::new().walk(root, f)
+}
+
+// ==============================================================================
+// Metadata
+// ==============================================================================
+/// The type of a file from the S_IFMT bits of its mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileType {
+    Fifo,
+    CharDevice,
+    Dir,
+    BlockDevice,
+    File,
+    Symlink,
+    Socket,
+    Unknown,
+}
+
+impl FileType {
+    pub fn from_mode(mode: mode_t) -> Self {
+        match mode & S_IFMT {
+            S_IFIFO  => FileType::Fifo,
+            S_IFCHR  => FileType::CharDevice,
+            S_IFDIR  => FileType::Dir,
+            S_IFBLK  => FileType::BlockDevice,
+            S_IFREG  => FileType::File,
+            S_IFLNK  => FileType::Symlink,
+            S_IFSOCK => FileType::Socket,
+            _        => FileType::Unknown,
+        }
+    }
+}
+
+/// The status of a file as returned by statx.
+///
+/// The fields outside of STATX_BASIC_STATS are only there when the filesystem provides them,
+/// their accessors return None otherwise. A Metadata converted from a [stat64] has the basic
+/// fields only.
+#[derive(Debug, Clone, Copy)]
+pub struct Metadata {
+    stx: statx,
+}
+
+impl Metadata {
+    pub fn raw(&self) -> &statx {
+        &self.stx
+    }
+
+    /// The STATX_* bits of the fields which are set
+    pub fn mask(&self) -> u32 {
+        self.stx.stx_mask
+    }
+
+    fn has(&self, mask: u32) -> bool {
+        self.stx.stx_mask & mask == mask
+    }
+
+    pub fn file_type(&self) -> FileType {
+        FileType::from_mode(self.stx.stx_mode as mode_t)
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.file_type() == FileType::Dir
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.file_type() == FileType::File
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        self.file_type() == FileType::Symlink
+    }
+
+    /// The whole mode including the S_IFMT bits
+    pub fn mode(&self) -> mode_t {
+        self.stx.stx_mode as mode_t
+    }
+
+    /// The permission bits with setuid, setgid and sticky
+    pub fn permissions(&self) -> mode_t {
+        self.mode() & !S_IFMT
+    }
+
+    pub fn len(&self) -> u64 {
+        self.stx.stx_size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of 512 byte blocks allocated
+    pub fn blocks(&self) -> u64 {
+        self.stx.stx_blocks
+    }
+
+    /// Preferred size for I/O
+    pub fn blksize(&self) -> u32 {
+        self.stx.stx_blksize
+    }
+
+    pub fn nlink(&self) -> u32 {
+        self.stx.stx_nlink
+    }
+
+    pub fn uid(&self) -> u32 {
+        self.stx.stx_uid
+    }
+
+    pub fn gid(&self) -> u32 {
+        self.stx.stx_gid
+    }
+
+    pub fn ino(&self) -> u64 {
+        self.stx.stx_ino
+    }
+
+    /// The device which contains the file as (major, minor)
+    pub fn dev(&self) -> (u32, u32) {
+        (self.stx.stx_dev_major, self.stx.stx_dev_minor)
+    }
+
+    /// The device a character or a block special file stands for as (major, minor)
+    pub fn rdev(&self) -> (u32, u32) {
+        (self.stx.stx_rdev_major, self.stx.stx_rdev_minor)
+    }
+
+    pub fn accessed(&self) -> timespec {
+        time(&self.stx.stx_atime)
+    }
+
+    pub fn modified(&self) -> timespec {
+        time(&self.stx.stx_mtime)
+    }
+
+    /// The last change of the inode, e.g. of its mode or owner
+    pub fn changed(&self) -> timespec {
+        time(&self.stx.stx_ctime)
+    }
+
+    /// The creation time, not every filesystem records it
+    pub fn created(&self) -> Option<timespec> {
+        self.has(STATX_BTIME).then(|| time(&self.stx.stx_btime))
+    }
+
+    /// The id of the mount as in /proc/self/mountinfo, or the unique id with STATX_MNT_ID_UNIQUE
+    pub fn mount_id(&self) -> Option<u64> {
+        (self.has(STATX_MNT_ID) || self.has(STATX_MNT_ID_UNIQUE)).then_some(self.stx.stx_mnt_id)
+    }
+
+    /// The STATX_ATTR_* flags of the file
+    pub fn attributes(&self) -> u64 {
+        self.stx.stx_attributes
+    }
+
+    /// The STATX_ATTR_* flags the filesystem supports, an attribute outside of it is unknown
+    pub fn attributes_mask(&self) -> u64 {
+        self.stx.stx_attributes_mask
+    }
+
+    fn attribute(&self, attr: u64) -> bool {
+        self.stx.stx_attributes & attr != 0
+    }
+
+    pub fn is_immutable(&self) -> bool {
+        self.attribute(STATX_ATTR_IMMUTABLE)
+    }
+
+    pub fn is_append_only(&self) -> bool {
+        self.attribute(STATX_ATTR_APPEND)
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        self.attribute(STATX_ATTR_COMPRESSED)
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.attribute(STATX_ATTR_ENCRYPTED)
+    }
+
+    /// fs-verity is enabled, the contents are checked against a Merkle tree when read
+    pub fn is_verity(&self) -> bool {
+        self.attribute(STATX_ATTR_VERITY)
+    }
+
+    pub fn is_dax(&self) -> bool {
+        self.attribute(STATX_ATTR_DAX)
+    }
+
+    /// The file is the root of a mount
+    pub fn is_mount_root(&self) -> bool {
+        self.attribute(STATX_ATTR_MOUNT_ROOT)
+    }
+
+    /// Alignment of the user buffers for O_DIRECT, None when O_DIRECT isn't supported
+    pub fn dio_mem_align(&self) -> Option<u32> {
+        let align = self.stx.stx_dio_mem_align;
+        (self.has(STATX_DIOALIGN) && align != 0).then_some(align)
+    }
+
+    /// Alignment of the file offsets and lengths for O_DIRECT
+    pub fn dio_offset_align(&self) -> Option<u32> {
+        let align = self.stx.stx_dio_offset_align;
+        (self.has(STATX_DIOALIGN) && align != 0).then_some(align)
+    }
+
+    /// Alignment of the file offsets for O_DIRECT reads, which may be smaller than for writes
+    pub fn dio_read_offset_align(&self) -> Option<u32> {
+        if !self.has(STATX_DIO_READ_ALIGN) {
+            return self.dio_offset_align();
+        }
+        let align = self.stx.stx_dio_read_offset_align;
+        (align != 0).then_some(align)
+    }
+
+    /// The id of the subvolume on btrfs and bcachefs
+    pub fn subvol(&self) -> Option<u64> {
+        self.has(STATX_SUBVOL).then_some(self.stx.stx_subvol)
+    }
+}
+
+impl From<statx> for Metadata {
+    fn from(stx: statx) -> Self {
+        Self { stx }
+    }
+}
+
+impl From<stat64> for Metadata {
+    fn from(st: stat64) -> Self {
+        let timestamp = |tv_sec: i64, tv_nsec: i64| {
+            let mut ts = statx_timestamp::default();
+            ts.tv_sec = tv_sec;
+            ts.tv_nsec = tv_nsec as u32;
+            ts
+        };
+
+        let mut stx = statx::default();
+        stx.stx_mask = STATX_BASIC_STATS;
+        stx.stx_blksize = st.st_blksize as u32;
+        stx.stx_nlink = st.st_nlink as u32;
+        stx.stx_uid = st.st_uid;
+        stx.stx_gid = st.st_gid;
+        stx.stx_mode = st.st_mode as u16;
+        stx.stx_ino = st.st_ino;
+        stx.stx_size = st.st_size as u64;
+        stx.stx_blocks = st.st_blocks as u64;
+        stx.stx_atime = timestamp(st.st_atime, st.st_atime_nsec);
+        stx.stx_mtime = timestamp(st.st_mtime, st.st_mtime_nsec);
+        stx.stx_ctime = timestamp(st.st_ctime, st.st_ctime_nsec);
+        stx.stx_rdev_major = major(st.st_rdev);
+        stx.stx_rdev_minor = minor(st.st_rdev);
+        stx.stx_dev_major = major(st.st_dev);
+        stx.stx_dev_minor = minor(st.st_dev);
+        Self { stx }
+    }
+}
+
+fn time(ts: &statx_timestamp) -> timespec {
+    timespec { tv_sec: ts.tv_sec, tv_nsec: ts.tv_nsec as i64 }
+}
+
+/// The major number of a dev_t as encoded by the kernel (include/linux/kdev_t.h)
+pub fn major(dev: u64) -> u32 {
+    (((dev >> 32) & 0xfffff000) | ((dev >> 8) & 0xfff)) as u32
+}
+
+pub fn minor(dev: u64) -> u32 {
+    (((dev >> 12) & 0xffffff00) | (dev & 0xff)) as u32
+}
+
+/// The STATX_* fields in `mask` of `path` relative to `dirfd`. With AT_EMPTY_PATH and an
+/// empty path it is the file `dirfd` refers to.
+pub fn statx(dirfd: i32, path: &CStr, flags: i32, mask: u32) -> Result<Metadata> {
+    let mut stx = statx::default();
+    crate::syscall::statx(dirfd, path, flags, mask, &mut stx)?;
+    Ok(Metadata { stx })
+}
+
+/// Everything statx knows about the file, following symlinks
+pub fn metadata(path: &str) -> Result<Metadata> {
+    let mut buf = [0u8; PATH_MAX];
+    statx(AT_FDCWD, cstr(path, &mut buf)?, 0, STATX_ALL)
+}
+
+/// Like [metadata] but about a symlink itself instead of the file it points to
+pub fn symlink_metadata(path: &str) -> Result<Metadata> {
+    let mut buf = [0u8; PATH_MAX];
+    statx(AT_FDCWD, cstr(path, &mut buf)?, AT_SYMLINK_NOFOLLOW, STATX_ALL)
+}
+
+/// The metadata of an open file
+pub fn fd_metadata(fd: impl AsFd) -> Result<Metadata> {
+    statx(fd.as_fd().as_raw_fd() as i32, empty(), AT_EMPTY_PATH, STATX_ALL)
+}
+
+/// The fields this module asks for, the kernel ignores the bits it doesn't know
+const STATX_ALL: u32 = STATX_BASIC_STATS | STATX_BTIME | STATX_MNT_ID | STATX_DIOALIGN
+    | STATX_SUBVOL | STATX_DIO_READ_ALIGN;
+
+pub(crate) fn cstr<'a>(path: &str, buf: &'a mut [u8; PATH_MAX]) -> Result<&'a CStr> {
+    crate::cstr(path.as_bytes(), buf)?;
+    CStr::from_bytes_with_nul(&buf[..path.len() + 1]).map_err(|_| Error::EINVAL)
+}
+
+// ==============================================================================
+// Extended attributes
+// ==============================================================================
+/// The file whose extended attributes are read or changed
+#[derive(Debug, Clone, Copy)]
+pub enum XattrTarget<'a> {
+    /// The file at the path, following symlinks
+    Path(&'a CStr),
+
+    /// The file at the path or the symlink itself
+    Link(&'a CStr),
+
+    Fd(BorrowedFd<'a>),
+}
+
+impl XattrTarget<'_> {
+    /// The value of the attribute `name`, ERANGE when it doesn't fit into `buf`. The length is
+    /// probed again when the value grew since the last call, so a concurrent change which
+    /// still fits doesn't fail.
+    pub fn get<'b>(&self, name: &CStr, buf: &'b mut [u8]) -> Result<&'b [u8]> {
+        let n = probe(buf, |buf| self.getxattr(name, buf))?;
+        Ok(&buf[..n])
+    }
+
+    /// The length of the value of the attribute `name`
+    pub fn size(&self, name: &CStr) -> Result<usize> {
+        self.getxattr(name, &mut [])
+    }
+
+    fn getxattr(&self, name: &CStr, buf: &mut [u8]) -> Result<usize> {
+        match *self {
+            XattrTarget::Path(path) => getxattr(path, name, buf),
+            XattrTarget::Link(path) => lgetxattr(path, name, buf),
+            XattrTarget::Fd(fd) => fgetxattr(fd.as_raw_fd(), name, buf),
+        }
+    }
+
+    /// Set the attribute, `flags` are XATTR_CREATE or XATTR_REPLACE or 0 for either
+    pub fn set(&self, name: &CStr, value: &[u8], flags: i32) -> Result<()> {
+        match *self {
+            XattrTarget::Path(path) => setxattr(path, name, value, flags),
+            XattrTarget::Link(path) => lsetxattr(path, name, value, flags),
+            XattrTarget::Fd(fd) => fsetxattr(fd.as_raw_fd(), name, value, flags),
+        }
+    }
+
+    pub fn remove(&self, name: &CStr) -> Result<()> {
+        match *self {
+            XattrTarget::Path(path) => removexattr(path, name),
+            XattrTarget::Link(path) => lremovexattr(path, name),
+            XattrTarget::Fd(fd) => fremovexattr(fd.as_raw_fd(), name),
+        }
+    }
+
+    /// The names of all attributes the caller may see, in a buffer of N bytes
+    pub fn list<const N: usize>(&self) -> Result<Xattrs<N>> {
+        let mut list = Xattrs { buf: [0; N], len: 0 };
+        list.len = probe(&mut list.buf, |buf| match *self {
+            XattrTarget::Path(path) => listxattr(path, buf),
+            XattrTarget::Link(path) => llistxattr(path, buf),
+            XattrTarget::Fd(fd) => flistxattr(fd.as_raw_fd(), buf),
+        })?;
+        Ok(list)
+    }
+}
+
+/// Call `f` with `buf` until it doesn't fail with ERANGE. That only happens when the list or
+/// the value grew between two calls, the size is probed with an empty buffer to tell this
+/// apart from a buffer which is too small.
+fn probe<F>(buf: &mut [u8], mut f: F) -> Result<usize>
+where
+    F: FnMut(&mut [u8]) -> Result<usize>,
+{
+    loop {
+        match f(buf) {
+            Err(Error::ERANGE) => {
+                if f(&mut [])? > buf.len() {
+                    return Err(Error::ERANGE);
+                }
+            }
+            res => return res,
+        }
+    }
+}
+
+/// The namespace of an attribute from the prefix of its name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Namespace {
+    /// Arbitrary attributes of the owner of a regular file or a directory
+    User,
+
+    /// Only visible to a process with CAP_SYS_ADMIN
+    Trusted,
+
+    /// Labels of the security modules like SELinux and file capabilities
+    Security,
+
+    /// Attributes of the kernel like ACLs as system.posix_acl_access
+    System,
+
+    Unknown,
+}
+
+impl Namespace {
+    const ALL: [Namespace; 4] = [Namespace::User, Namespace::Trusted, Namespace::Security, Namespace::System];
+
+    pub fn prefix(&self) -> &'static [u8] {
+        match self {
+            Namespace::User => b"user.",
+            Namespace::Trusted => b"trusted.",
+            Namespace::Security => b"security.",
+            Namespace::System => b"system.",
+            Namespace::Unknown => b"",
+        }
+    }
+}
+
+/// The name of an attribute in a [Xattrs] list
+#[derive(Debug, Clone, Copy)]
+pub struct Xattr<'a> {
+    pub name: &'a CStr,
+}
+
+impl<'a> Xattr<'a> {
+    pub fn namespace(&self) -> Namespace {
+        let name = self.name.to_bytes();
+        Namespace::ALL.iter().copied()
+            .find(|ns| name.starts_with(ns.prefix()))
+            .unwrap_or(Namespace::Unknown)
+    }
+
+    /// The name without the prefix of the namespace
+    pub fn key(&self) -> &'a [u8] {
+        &self.name.to_bytes()[self.namespace().prefix().len()..]
+    }
+}
+
+/// The names returned by listxattr in a buffer of N bytes. The list of a file can take up to
+/// XATTR_LIST_MAX bytes, a smaller N fails with ERANGE for a file with more attributes.
+///
+/// ```ignore
+/// let file = XattrTarget::Link(path);
+/// let mut value = [0u8; XATTR_SIZE_MAX];
+/// for attr in &file.list::<4096>()? {
+///     if attr.namespace() == Namespace::User {
+///         backup(attr.name, file.get(attr.name, &mut value)?);
+///     }
+/// }
+/// ```
+pub struct Xattrs<const N: usize = 4096> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> Xattrs<N> {
+    pub fn iter(&self) -> XattrIter<'_> {
+        XattrIter { list: &self.buf[..self.len] }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<'a, const N: usize> IntoIterator for &'a Xattrs<N> {
+    type Item = Xattr<'a>;
+    type IntoIter = XattrIter<'a>;
+
+    fn into_iter(self) -> XattrIter<'a> {
+        self.iter()
+    }
+}
+
+/// Splits the nul terminated names of a [Xattrs] list
+pub struct XattrIter<'a> {
+    list: &'a [u8],
+}
+
+impl<'a> Iterator for XattrIter<'a> {
+    type Item = Xattr<'a>;
+
+    fn next(&mut self) -> Option<Xattr<'a>> {
+        let name = CStr::from_bytes_until_nul(self.list).ok()?;
+        self.list = &self.list[name.to_bytes_with_nul().len()..];
+        Some(Xattr { name })
+    }
+}
+
+// ==============================================================================
+// Dir
+// ==============================================================================
+/// Restrictions for the resolution of the paths given to a [Dir], combined with `|`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Resolve(u64);
+
+impl Resolve {
+    pub const NONE: Self = Self(0);
+
+    /// Fail with EXDEV when the path leaves the directory through "..", an absolute path or
+    /// a symlink
+    pub const BENEATH: Self = Self(RESOLVE_BENEATH);
+
+    /// Treat the directory as the root: "..", absolute paths and symlinks stay inside it
+    pub const IN_ROOT: Self = Self(RESOLVE_IN_ROOT);
+
+    /// Fail with ELOOP on any symlink in the path
+    pub const NO_SYMLINKS: Self = Self(RESOLVE_NO_SYMLINKS);
+
+    /// Fail with ELOOP on the links in /proc, e.g. /proc/self/fd/*
+    pub const NO_MAGICLINKS: Self = Self(RESOLVE_NO_MAGICLINKS);
+
+    /// Fail with EXDEV when the path crosses a mount point
+    pub const NO_XDEV: Self = Self(RESOLVE_NO_XDEV);
+
+    /// Only look in the cache of the kernel, fail with EAGAIN when a lookup needs I/O
+    pub const CACHED: Self = Self(RESOLVE_CACHED);
+
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for Resolve {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+/// Options of [Dir::rename], combined with `|`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rename(u32);
+
+impl Rename {
+    pub const NONE: Self = Self(0);
+
+    /// Fail with EEXIST instead of replacing the target
+    pub const NOREPLACE: Self = Self(RENAME_NOREPLACE);
+
+    /// Swap the source and the target atomically, both have to exist
+    pub const EXCHANGE: Self = Self(RENAME_EXCHANGE);
+
+    /// Leave a whiteout in place of the source, for overlay filesystems
+    pub const WHITEOUT: Self = Self(RENAME_WHITEOUT);
+}
+
+impl BitOr for Rename {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+/// An open directory which the paths of its methods are relative to.
+///
+/// With [Resolve] flags the paths are resolved by openat2 under these restrictions: the whole
+/// path for the methods which follow symlinks (open, stat, permissions, times) and the parent
+/// directory for the methods which work on the last component itself (create, remove, rename,
+/// link). The checks happen in the kernel during the lookup, so a path can't be redirected
+/// between a check and its use.
+///
+/// ```ignore
+/// let root = Dir::open("/tmp/extract")?.with_resolve(Resolve::BENEATH | Resolve::NO_SYMLINKS);
+/// root.create_dir(c"usr", 0o755)?;
+/// let file = root.open_file(c"usr/../../etc/passwd", O_WRONLY | O_CREAT, 0o644); // EXDEV
+/// ```
+pub struct Dir {
+    fd: OwnedFd,
+    resolve: Resolve,
+}
+
+impl Dir {
+    /// Open the directory with O_PATH, no permission to read it is needed
+    pub fn open(path: &str) -> Result<Self> {
+        let fd = open(path, O_PATH | O_DIRECTORY | O_CLOEXEC, 0)?;
+        Ok(Self::from_fd(fd))
+    }
+
+    pub fn from_fd(fd: OwnedFd) -> Self {
+        Self { fd, resolve: Resolve::NONE }
+    }
+
+    /// Resolve all paths with these restrictions, relative to this directory
+    pub fn with_resolve(mut self, resolve: Resolve) -> Self {
+        self.resolve = resolve;
+        self
+    }
+
+    pub fn resolve(&self) -> Resolve {
+        self.resolve
+    }
+
+    pub fn fd(&self) -> u32 {
+        self.fd.as_raw_fd()
+    }
+
+    fn dirfd(&self) -> i32 {
+        self.fd.as_raw_fd() as i32
+    }
+
+    fn openat2(&self, path: &CStr, flags: i32, mode: mode_t) -> Result<OwnedFd> {
+        let how = open_how {
+            flags: (flags | O_CLOEXEC) as u64,
+            mode: mode as u64,
+            resolve: self.resolve.0,
+        };
+        openat2(self.dirfd(), path, &how)
+    }
+
+    /// The directory which contains the last component of `path` and the last component.
+    /// The parent is None when the last component can be looked up in this directory.
+    /// A last component `..` is rejected with EINVAL, it would be looked up relative to the
+    /// parent without the resolve flags and could leave the directory.
+    fn parent<'a>(&self, path: &'a CStr, buf: &'a mut [u8; PATH_MAX]) -> Result<(Option<OwnedFd>, &'a CStr)> {
+        if self.resolve == Resolve::NONE {
+            return Ok((None, path));
+        }
+
+        let mut bytes = path.to_bytes();
+        if bytes.len() >= PATH_MAX {
+            return Err(Error::ENAMETOOLONG);
+        }
+        while let [rest @ .., b'/'] = bytes {
+            bytes = rest;
+        }
+        let (parent, name) = match bytes.iter().rposition(|&b| b == b'/') {
+            None => (None, bytes),
+            Some(0) => (Some(&b"/"[..]), &bytes[1..]),
+            Some(idx) => (Some(&bytes[..idx]), &bytes[idx + 1..]),
+        };
+        if name.is_empty() {
+            return Err(Error::ENOENT);
+        }
+        if name == b".." {
+            return Err(Error::EINVAL);
+        }
+
+        let parent = match parent {
+            Some(parent) => {
+                buf[..parent.len()].copy_from_slice(parent);
+                buf[parent.len()] = 0;
+                let parent = unsafe { CStr::from_bytes_with_nul_unchecked(&buf[..parent.len() + 1]) };
+                Some(self.openat2(parent, O_PATH | O_DIRECTORY, 0)?)
+            }
+            None => None,
+        };
+
+        buf[..name.len()].copy_from_slice(name);
+        buf[name.len()] = 0;
+        Ok((parent, unsafe { CStr::from_bytes_with_nul_unchecked(&buf[..name.len() + 1]) }))
+    }
+
+    /// The descriptor the last component returned by parent() is relative to
+    fn at(&self, parent: &Option<OwnedFd>) -> i32 {
+        parent.as_ref().map_or(self.dirfd(), |fd| fd.as_raw_fd() as i32)
+    }
+
+    /// Open a file with the O_* flags, O_CLOEXEC is always added
+    pub fn open_file(&self, path: &CStr, flags: i32, mode: mode_t) -> Result<File> {
+        self.openat2(path, flags, mode).map(File::from)
+    }
+
+    /// Open a subdirectory with the same resolve flags, it becomes the root for IN_ROOT
+    pub fn open_dir(&self, path: &CStr) -> Result<Dir> {
+        let fd = self.openat2(path, O_PATH | O_DIRECTORY, 0)?;
+        Ok(Dir { fd, resolve: self.resolve })
+    }
+
+    /// List the entries of this directory
+    pub fn read_dir(&self) -> Result<ReadDir> {
+        ReadDir::openat(self.dirfd(), unsafe { CStr::from_bytes_with_nul_unchecked(b".\0") }, 0)
+    }
+
+    pub fn create_dir(&self, path: &CStr, mode: mode_t) -> Result<()> {
+        let mut buf = [0u8; PATH_MAX];
+        let (parent, name) = self.parent(path, &mut buf)?;
+        mkdirat(self.at(&parent), name, mode)
+    }
+
+    /// Remove a file or a symlink, not the file it points to
+    pub fn remove_file(&self, path: &CStr) -> Result<()> {
+        let mut buf = [0u8; PATH_MAX];
+        let (parent, name) = self.parent(path, &mut buf)?;
+        unlinkat(self.at(&parent), name, 0)
+    }
+
+    /// Remove an empty directory
+    pub fn remove_dir(&self, path: &CStr) -> Result<()> {
+        let mut buf = [0u8; PATH_MAX];
+        let (parent, name) = self.parent(path, &mut buf)?;
+        unlinkat(self.at(&parent), name, AT_REMOVEDIR)
+    }
+
+    /// Move `from` to `to` in `to_dir`, the resolve flags of each directory apply to its path
+    pub fn rename(&self, from: &CStr, to_dir: &Dir, to: &CStr, flags: Rename) -> Result<()> {
+        let mut old = [0u8; PATH_MAX];
+        let mut new = [0u8; PATH_MAX];
+        let (old_parent, old_name) = self.parent(from, &mut old)?;
+        let (new_parent, new_name) = to_dir.parent(to, &mut new)?;
+        renameat2(self.at(&old_parent), old_name, to_dir.at(&new_parent), new_name, flags.0)
+    }
+
+    /// Create `to` in `to_dir` as another name of `from`. A symlink is linked itself.
+    pub fn hard_link(&self, from: &CStr, to_dir: &Dir, to: &CStr) -> Result<()> {
+        let mut old = [0u8; PATH_MAX];
+        let mut new = [0u8; PATH_MAX];
+        let (old_parent, old_name) = self.parent(from, &mut old)?;
+        let (new_parent, new_name) = to_dir.parent(to, &mut new)?;
+        linkat(self.at(&old_parent), old_name, to_dir.at(&new_parent), new_name, 0)
+    }
+
+    /// Create the symlink `link` pointing to `target`. The target is stored as it is, the
+    /// resolve flags apply when the link is followed through this directory.
+    pub fn symlink(&self, target: &CStr, link: &CStr) -> Result<()> {
+        let mut buf = [0u8; PATH_MAX];
+        let (parent, name) = self.parent(link, &mut buf)?;
+        symlinkat(target, self.at(&parent), name)
+    }
+
+    /// The target of the symlink, ENAMETOOLONG when it doesn't fit into `buf`
+    pub fn read_link<'b>(&self, path: &CStr, buf: &'b mut [u8]) -> Result<&'b [u8]> {
+        let mut tmp = [0u8; PATH_MAX];
+        let (parent, name) = self.parent(path, &mut tmp)?;
+        let n = readlinkat(self.at(&parent), name, buf)?;
+        match n < buf.len() {
+            true => Ok(&buf[..n]),
+            false => Err(Error::ENAMETOOLONG),
+        }
+    }
+
+    /// The status of the file, following a symlink in the last component
+    pub fn metadata(&self, path: &CStr) -> Result<Metadata> {
+        let fd = self.openat2(path, O_PATH, 0)?;
+        fd_metadata(&fd)
+    }
+
+    /// The status of the last component itself if it is a symlink
+    pub fn symlink_metadata(&self, path: &CStr) -> Result<Metadata> {
+        let mut buf = [0u8; PATH_MAX];
+        let (parent, name) = self.parent(path, &mut buf)?;
+        statx(self.at(&parent), name, AT_SYMLINK_NOFOLLOW, STATX_ALL)
+    }
+
+    /// Check the R_OK, W_OK, X_OK permissions or only the existence with F_OK
+    pub fn access(&self, path: &CStr, mode: i32) -> Result<()> {
+        let fd = self.openat2(path, O_PATH, 0)?;
+        faccessat2(fd.as_raw_fd() as i32, empty(), mode, AT_EMPTY_PATH)
+    }
+
+    /// Change the mode, since Linux 6.6
+    pub fn set_permissions(&self, path: &CStr, mode: mode_t) -> Result<()> {
+        let fd = self.openat2(path, O_PATH, 0)?;
+        fchmodat2(fd.as_raw_fd() as i32, empty(), mode, AT_EMPTY_PATH)
+    }
+
+    /// Set the access and the modification time, None leaves the time unchanged
+    pub fn set_times(&self, path: &CStr, atime: Option<timespec>, mtime: Option<timespec>) -> Result<()> {
+        let omit = || timespec { tv_sec: 0, tv_nsec: UTIME_OMIT };
+        let times = [atime.unwrap_or_else(omit), mtime.unwrap_or_else(omit)];
+        let fd = self.openat2(path, O_PATH, 0)?;
+        utimensat(fd.as_raw_fd() as i32, Some(empty()), Some(&times), AT_EMPTY_PATH)
+    }
+}
+
+impl AsFd for Dir {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+pub(crate) fn empty() -> &'static CStr {
+    unsafe { CStr::from_bytes_with_nul_unchecked(b"\0") }
+}
@@ -1,5 +1,6 @@
 /* ANCHOR: stdio */
 use crate::error::{Error, Result};
+use crate::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd, RawFd};
 use core::fmt;
 
 pub trait Write {
@@ -35,23 +36,60 @@ pub trait Read {
     }
 }
 
+/// An open file which is closed when it is dropped
 pub struct File {
-    fd: u32,
+    fd: OwnedFd,
 }
 
 impl File {
-    pub fn new(fd: u32) -> Self {
+    pub fn fd(&self) -> u32 {
+        self.fd.as_raw_fd()
+    }
+}
+
+impl From<OwnedFd> for File {
+    fn from(fd: OwnedFd) -> Self {
         Self { fd }
     }
 }
 
+impl From<File> for OwnedFd {
+    fn from(file: File) -> Self {
+        file.fd
+    }
+}
+
+impl AsFd for File {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+impl AsRawFd for File {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+impl IntoRawFd for File {
+    fn into_raw_fd(self) -> RawFd {
+        self.fd.into_raw_fd()
+    }
+}
+
+impl FromRawFd for File {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Self { fd: OwnedFd::from_raw_fd(fd) }
+    }
+}
+
 impl Write for File {
     fn flush() -> Result<()> {
         Ok(())
     }
 
     fn write(&mut self, buf: &[u8]) -> Result<usize> {
-        crate::syscall::write(self.fd, buf)
+        crate::syscall::write(self.fd(), buf)
     }
 }
 
@@ -66,20 +104,96 @@ impl fmt::Write for File {
 
 impl Read for File {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
-        crate::syscall::read(self.fd, buf)
+        crate::syscall::read(self.fd(), buf)
+    }
+}
+
+/// One of the standard streams of the process, it stays open when it is dropped
+pub struct StdStream {
+    fd: BorrowedFd<'static>,
+}
+
+impl AsFd for StdStream {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd
+    }
+}
+
+impl AsRawFd for StdStream {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+impl Write for StdStream {
+    fn flush() -> Result<()> {
+        Ok(())
+    }
+
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        crate::syscall::write(self.fd.as_raw_fd(), buf)
+    }
+}
+
+impl fmt::Write for StdStream {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        match self.write(s.as_bytes()) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(fmt::Error),
+        }
+    }
+}
+
+impl Read for StdStream {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        crate::syscall::read(self.fd.as_raw_fd(), buf)
     }
 }
 
-pub fn stdin() -> File {
-    File::new(0)
+pub fn stdin() -> StdStream {
+    StdStream { fd: unsafe { BorrowedFd::borrow_raw(0) } }
 }
 
-pub fn stdout() -> File {
-    File::new(1)
+pub fn stdout() -> StdStream {
+    StdStream { fd: unsafe { BorrowedFd::borrow_raw(1) } }
 }
 
-pub fn stderr() -> File {
-    File::new(2)
+pub fn stderr() -> StdStream {
+    StdStream { fd: unsafe { BorrowedFd::borrow_raw(2) } }
+}
+
+/// A string in a fixed buffer for formatting without an allocator, writing more than N
+/// bytes fails
+#[derive(Clone, Copy)]
+pub struct Buf<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> Buf<N> {
+    pub fn new() -> Self {
+        Self { buf: [0; N], len: 0 }
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    pub fn as_str(&self) -> &str {
+        unsafe { core::str::from_utf8_unchecked(&self.buf[..self.len]) }
+    }
+}
+
+impl<const N: usize> fmt::Write for Buf<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        if end > N {
+            return Err(fmt::Error);
+        }
+        self.buf[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
 }
 /* ANCHOR_END: stdio */
 
@@ -111,6 +225,10 @@ macro_rules! eprint {
     ($fmt:literal $(,$($args:expr)*)?) => {{
         use core::fmt::Write;
         write!($crate::io::stderr(), $fmt, $($($args),*)?).unwrap();
+    }};
+    ($fmt:literal, $($args:expr),*) => {{
+        use core::fmt::Write;
+        write!($crate::io::stderr(), $fmt, $($args),*).unwrap();
     }}
 }
 
@@ -118,6 +236,9 @@ macro_rules! eprint {
 macro_rules! eprintln {
     ($fmt:literal $(,$($args:expr)*)?) => {{
         $crate::eprint!("{}\n", format_args!($fmt, $($($args),*)?))
+    }};
+    ($fmt:literal, $($args:expr),*) => {{
+        $crate::eprint!("{}\n", format_args!($fmt, $($args),*))
     }}
 }
 /* ANCHOR_END: print-macro */
@@ -0,0 +1,342 @@
+//! Unprivileged sandboxing with Landlock.
+//!
+//! A ruleset names the access rights it handles, which are denied unless a rule allows them
+//! for a file hierarchy or a TCP port. Rights the ruleset doesn't handle stay allowed. Once the
+//! ruleset is enforced with [RulesetCreated::restrict_self] it can't be lifted again, it
+//! applies to the calling thread and all its future children.
+//!
+//! Every kernel release adds rights, reported by the ABI version. [Ruleset] drops the rights
+//! the running kernel doesn't know, so a program runs on older kernels with a weaker sandbox
+//! instead of failing, and [Status] tells how much was enforced.
+//!
+//! ```ignore
+//! let status = Ruleset::new()
+//!     .handle_fs(AccessFs::from_abi(5))
+//!     .handle_net(AccessNet::BIND_TCP | AccessNet::CONNECT_TCP)
+//!     .create()?
+//!     .allow_path("/usr", AccessFs::READ)?
+//!     .allow_path("/tmp/out", AccessFs::READ | AccessFs::WRITE)?
+//!     .restrict_self()?;
+//! ```
+use core::ops::{BitAnd, BitOr};
+
+use crate::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd};
+use crate::types::*;
+use crate::syscall::*;
+use crate::constants::*;
+use crate::error::Result;
+
+/// The highest ABI version the kernel supports, 0 when Landlock isn't available
+pub fn abi_version() -> u32 {
+    match landlock_create_ruleset(None, LANDLOCK_CREATE_RULESET_VERSION) {
+        Ok(version) => version as u32,
+        Err(_) => 0,
+    }
+}
+
+macro_rules! access {
+    ($name:ident) => {
+        impl $name {
+            pub const NONE: Self = Self(0);
+
+            pub fn bits(&self) -> u64 {
+                self.0
+            }
+
+            pub fn contains(&self, other: Self) -> bool {
+                self.0 & other.0 == other.0
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.0 == 0
+            }
+        }
+
+        impl BitOr for $name {
+            type Output = Self;
+
+            fn bitor(self, other: Self) -> Self {
+                Self(self.0 | other.0)
+            }
+        }
+
+        impl BitAnd for $name {
+            type Output = Self;
+
+            fn bitand(self, other: Self) -> Self {
+                Self(self.0 & other.0)
+            }
+        }
+    };
+}
+
+/// Access rights to files and directories, combined with `|`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AccessFs(u64);
+
+access!(AccessFs);
+
+impl AccessFs {
+    pub const EXECUTE: Self = Self(LANDLOCK_ACCESS_FS_EXECUTE);
+    pub const WRITE_FILE: Self = Self(LANDLOCK_ACCESS_FS_WRITE_FILE);
+    pub const READ_FILE: Self = Self(LANDLOCK_ACCESS_FS_READ_FILE);
+    pub const READ_DIR: Self = Self(LANDLOCK_ACCESS_FS_READ_DIR);
+    pub const REMOVE_DIR: Self = Self(LANDLOCK_ACCESS_FS_REMOVE_DIR);
+    pub const REMOVE_FILE: Self = Self(LANDLOCK_ACCESS_FS_REMOVE_FILE);
+    pub const MAKE_CHAR: Self = Self(LANDLOCK_ACCESS_FS_MAKE_CHAR);
+    pub const MAKE_DIR: Self = Self(LANDLOCK_ACCESS_FS_MAKE_DIR);
+    pub const MAKE_REG: Self = Self(LANDLOCK_ACCESS_FS_MAKE_REG);
+    pub const MAKE_SOCK: Self = Self(LANDLOCK_ACCESS_FS_MAKE_SOCK);
+    pub const MAKE_FIFO: Self = Self(LANDLOCK_ACCESS_FS_MAKE_FIFO);
+    pub const MAKE_BLOCK: Self = Self(LANDLOCK_ACCESS_FS_MAKE_BLOCK);
+    pub const MAKE_SYM: Self = Self(LANDLOCK_ACCESS_FS_MAKE_SYM);
+
+    /// Link or rename a file into another directory, since ABI 2. Without it a ruleset denies
+    /// moving files between directories.
+    pub const REFER: Self = Self(LANDLOCK_ACCESS_FS_REFER);
+
+    /// Truncate a file, since ABI 3
+    pub const TRUNCATE: Self = Self(LANDLOCK_ACCESS_FS_TRUNCATE);
+
+    /// ioctl on a character or a block device, since ABI 5
+    pub const IOCTL_DEV: Self = Self(LANDLOCK_ACCESS_FS_IOCTL_DEV);
+
+    /// Reading files and listing directories
+    pub const READ: Self = Self(LANDLOCK_ACCESS_FS_READ_FILE | LANDLOCK_ACCESS_FS_READ_DIR);
+
+    /// Changing files and directories: writing, creating, removing, renaming and truncating
+    pub const WRITE: Self = Self(
+        LANDLOCK_ACCESS_FS_WRITE_FILE | LANDLOCK_ACCESS_FS_REMOVE_DIR | LANDLOCK_ACCESS_FS_REMOVE_FILE
+        | LANDLOCK_ACCESS_FS_MAKE_CHAR | LANDLOCK_ACCESS_FS_MAKE_DIR | LANDLOCK_ACCESS_FS_MAKE_REG
+        | LANDLOCK_ACCESS_FS_MAKE_SOCK | LANDLOCK_ACCESS_FS_MAKE_FIFO | LANDLOCK_ACCESS_FS_MAKE_BLOCK
+        | LANDLOCK_ACCESS_FS_MAKE_SYM | LANDLOCK_ACCESS_FS_REFER | LANDLOCK_ACCESS_FS_TRUNCATE
+    );
+
+    /// The rights which apply to a file, a rule for a file which isn't a directory may only
+    /// allow these
+    pub const FILE: Self = Self(
+        LANDLOCK_ACCESS_FS_EXECUTE | LANDLOCK_ACCESS_FS_WRITE_FILE | LANDLOCK_ACCESS_FS_READ_FILE
+        | LANDLOCK_ACCESS_FS_TRUNCATE | LANDLOCK_ACCESS_FS_IOCTL_DEV
+    );
+
+    /// All rights known by the ABI version
+    pub fn from_abi(abi: u32) -> Self {
+        match abi {
+            0 => Self::NONE,
+            1 => Self((1 << 13) - 1),
+            2 => Self((1 << 14) - 1),
+            3 | 4 => Self((1 << 15) - 1),
+            _ => Self((1 << 16) - 1),
+        }
+    }
+}
+
+/// Access rights to TCP ports, since ABI 4
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AccessNet(u64);
+
+access!(AccessNet);
+
+impl AccessNet {
+    pub const BIND_TCP: Self = Self(LANDLOCK_ACCESS_NET_BIND_TCP);
+    pub const CONNECT_TCP: Self = Self(LANDLOCK_ACCESS_NET_CONNECT_TCP);
+
+    pub fn from_abi(abi: u32) -> Self {
+        match abi {
+            0..=3 => Self::NONE,
+            _ => Self::BIND_TCP | Self::CONNECT_TCP,
+        }
+    }
+}
+
+/// IPC with processes outside of the sandbox which is denied, since ABI 6
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Scope(u64);
+
+access!(Scope);
+
+impl Scope {
+    /// Connecting to an abstract unix socket created outside
+    pub const ABSTRACT_UNIX_SOCKET: Self = Self(LANDLOCK_SCOPE_ABSTRACT_UNIX_SOCKET);
+
+    /// Sending a signal to a process outside
+    pub const SIGNAL: Self = Self(LANDLOCK_SCOPE_SIGNAL);
+
+    pub fn from_abi(abi: u32) -> Self {
+        match abi {
+            0..=5 => Self::NONE,
+            _ => Self::ABSTRACT_UNIX_SOCKET | Self::SIGNAL,
+        }
+    }
+}
+
+/// How much of the requested sandbox the kernel enforces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// All requested rights are handled
+    Enforced,
+
+    /// The kernel doesn't know some of the rights, they stay allowed
+    Partial,
+
+    /// Landlock isn't available or the kernel knows none of the rights, only no_new_privs
+    /// is set
+    NotEnforced,
+}
+
+/// The access rights a sandbox handles, see [Ruleset::create]
+#[derive(Debug, Clone, Copy)]
+pub struct Ruleset {
+    abi: u32,
+    fs: AccessFs,
+    net: AccessNet,
+    scope: Scope,
+}
+
+impl Ruleset {
+    /// A ruleset which handles nothing yet for the ABI version of the running kernel
+    pub fn new() -> Self {
+        Self::with_abi(abi_version())
+    }
+
+    /// A ruleset for a lower ABI version than the kernel supports, e.g. to get the same
+    /// behavior on all kernels
+    pub fn with_abi(abi: u32) -> Self {
+        Self { abi, fs: AccessFs::NONE, net: AccessNet::NONE, scope: Scope::NONE }
+    }
+
+    pub fn abi(&self) -> u32 {
+        self.abi
+    }
+
+    /// Deny these rights unless a path rule allows them
+    pub fn handle_fs(&mut self, access: AccessFs) -> &mut Self {
+        self.fs = self.fs | access;
+        self
+    }
+
+    /// Deny these rights unless a port rule allows them
+    pub fn handle_net(&mut self, access: AccessNet) -> &mut Self {
+        self.net = self.net | access;
+        self
+    }
+
+    /// Deny these kinds of IPC with processes outside of the sandbox
+    pub fn scope(&mut self, scope: Scope) -> &mut Self {
+        self.scope = self.scope | scope;
+        self
+    }
+
+    /// Create the ruleset with the rights the ABI knows. Without Landlock or without any
+    /// right the ABI knows no ruleset is created and the rules are ignored.
+    pub fn create(&self) -> Result<RulesetCreated> {
+        let fs = self.fs & AccessFs::from_abi(self.abi);
+        let net = self.net & AccessNet::from_abi(self.abi);
+        let scope = self.scope & Scope::from_abi(self.abi);
+        let nothing = fs == AccessFs::NONE && net == AccessNet::NONE && scope == Scope::NONE;
+        let status = match (fs == self.fs && net == self.net && scope == self.scope, self.abi) {
+            (_, 0) => Status::NotEnforced,
+            _ if nothing => Status::NotEnforced,
+            (true, _) => Status::Enforced,
+            (false, _) => Status::Partial,
+        };
+
+        let fd = match status {
+            Status::NotEnforced => None,
+            _ => {
+                let attr = landlock_ruleset_attr {
+                    handled_access_fs: fs.0,
+                    handled_access_net: net.0,
+                    scoped: scope.0,
+                };
+                let fd = landlock_create_ruleset(Some(&attr), 0)?;
+                Some(unsafe { OwnedFd::from_raw_fd(fd as u32) })
+            }
+        };
+
+        Ok(RulesetCreated { fd, fs, net, status })
+    }
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A ruleset which takes rules, see [Ruleset]
+#[derive(Debug)]
+pub struct RulesetCreated {
+    fd: Option<OwnedFd>,
+    fs: AccessFs,
+    net: AccessNet,
+    status: Status,
+}
+
+impl RulesetCreated {
+    /// Allow `access` to `path` and everything below it, the rights the ruleset doesn't handle
+    /// are dropped. For a file which isn't a directory only the [AccessFs::FILE] rights are
+    /// used.
+    pub fn allow_path(&mut self, path: &str, access: AccessFs) -> Result<&mut Self> {
+        let fd = open(path, O_PATH | O_CLOEXEC, 0)?;
+        self.allow_fd(fd.as_fd(), access)
+    }
+
+    /// Like [RulesetCreated::allow_path] for an open file, which may be opened with O_PATH
+    pub fn allow_fd(&mut self, fd: BorrowedFd<'_>, access: AccessFs) -> Result<&mut Self> {
+        let Some(ruleset) = &self.fd else {
+            return Ok(self);
+        };
+
+        let mut stat = stat64::default();
+        fstat(fd.as_raw_fd(), &mut stat)?;
+        let mut access = access & self.fs;
+        if stat.st_mode as mode_t & S_IFMT != S_IFDIR {
+            access = access & AccessFs::FILE;
+        }
+        if access.is_empty() {
+            return Ok(self);
+        }
+
+        let attr = landlock_path_beneath_attr {
+            allowed_access: access.0,
+            parent_fd: fd.as_raw_fd() as i32,
+        };
+        let attr = &attr as *const landlock_path_beneath_attr;
+        unsafe { landlock_add_rule(ruleset.as_raw_fd(), LANDLOCK_RULE_PATH_BENEATH, attr.cast(), 0)? };
+        Ok(self)
+    }
+
+    /// Allow `access` to the TCP port, the rights the ruleset doesn't handle are dropped
+    pub fn allow_port(&mut self, port: u16, access: AccessNet) -> Result<&mut Self> {
+        let access = access & self.net;
+        let Some(ruleset) = &self.fd else {
+            return Ok(self);
+        };
+        if access.is_empty() {
+            return Ok(self);
+        }
+
+        let attr = landlock_net_port_attr {
+            allowed_access: access.0,
+            port: port as u64,
+        };
+        let attr = &attr as *const landlock_net_port_attr;
+        unsafe { landlock_add_rule(ruleset.as_raw_fd(), LANDLOCK_RULE_NET_PORT, attr.cast(), 0)? };
+        Ok(self)
+    }
+
+    /// Set no_new_privs and enforce the ruleset on the calling thread and its future children.
+    /// Other threads which already exist aren't restricted.
+    pub fn restrict_self(&self) -> Result<Status> {
+        unsafe { prctl(PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0)? };
+        if let Some(ruleset) = &self.fd {
+            landlock_restrict_self(ruleset.as_raw_fd(), 0)?;
+        }
+        Ok(self.status)
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+}
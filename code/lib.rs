@@ -8,14 +8,32 @@ use core::fmt::Write;
 use core::sync::atomic::{AtomicPtr, Ordering};
 
 pub mod io;
+pub mod alloc;
 pub mod elf;
+pub mod fs;
+pub mod mount;
+pub mod namespace;
+pub mod landlock;
+pub mod seccomp;
+pub mod ptrace;
+pub mod demangle;
+pub mod backtrace;
 pub mod env;
+pub mod fd;
 pub mod ffi;
 pub mod vdso;
 pub mod ldso;
 pub mod error;
 pub mod types;
 pub mod limits;
+pub mod process;
+pub mod signal;
+pub mod net;
+pub mod reactor;
+pub mod uring;
+pub mod thread;
+pub mod sync;
+mod tls;
 pub mod syscall;
 #[rustfmt::skip]
 pub mod constants;
@@ -46,7 +64,7 @@ fn _start() -> ! {
             "mov rdi,rsp",
             "call __rust_main",
             "mov rdi,rax",
-            "mov rax,0x3c",
+            "mov rax,0xe7",     // exit_group to end the other threads too
             "syscall",
             options(nostack, noreturn),
         )
@@ -57,6 +75,7 @@ fn _start() -> ! {
 #[no_mangle]
 unsafe fn __rust_main(rsp: *const u8) -> u8 {
     parse_stack(rsp);
+    thread::init().expect("failed to set up thread-local storage");
     //let ldso = ldso::Ldso::new();
     //ldso.relocate_ldso();
     //ldso.relocate_exe();
@@ -85,12 +104,21 @@ unsafe fn parse_stack(rsp: *const u8) {
 #[panic_handler]
 fn panic_handler(info: &core::panic::PanicInfo) -> ! {
     let _ = write!(crate::io::stderr(), "{}\n", info);
-    syscall::exit(255);
+    let _ = backtrace::print(&mut crate::io::stderr(), backtrace::Style::from_env());
+    thread::panicked(info);
+    syscall::exit_group(255);
 }
 
 #[lang = "eh_personality"]
 fn rust_eh_personality() {}
 
+// The precompiled alloc crate is built with unwinding, so it references this symbol even
+// though with panic=abort it is never called.
+#[no_mangle]
+extern "C" fn _Unwind_Resume() -> ! {
+    syscall::exit(255);
+}
+
 #[no_mangle]
 fn cstr(src: &[u8], dst: &mut [u8]) -> Result<()> {
     if src.len() >= dst.len() {
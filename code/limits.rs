@@ -1 +1,5 @@
 pub const PATH_MAX: usize = 4096;
+pub const ARG_MAX: usize = 131072;
+pub const XATTR_NAME_MAX: usize = 255;
+pub const XATTR_SIZE_MAX: usize = 65536;
+pub const XATTR_LIST_MAX: usize = 65536;
@@ -0,0 +1,363 @@
+//! Mounting filesystems with the file descriptor based mount API.
+//!
+//! A filesystem is configured through an [FsContext] and turned into a detached [Mount] which
+//! isn't visible anywhere until it is attached with [Mount::mount_at]. A detached bind mount is
+//! a copy of an existing mount from [Mount::bind]. The attributes of a mount like MOUNT_ATTR_RDONLY
+//! are set on the mount itself, independent of the superblock.
+//!
+//! [ListMounts] and [MountInfo] enumerate the mounts by their unique ids with listmount and
+//! statmount, without parsing /proc/self/mountinfo.
+//!
+//! ```ignore
+//! let dir = Dir::open("/tmp")?;
+//! FsContext::new("tmpfs")?.set("size", "16M")?.create()?.mount_at(dir.as_fd(), "x")?;
+//!
+//! for id in ListMounts::<64>::new(LSMT_ROOT) {
+//!     let info: MountInfo = MountInfo::new(id?, STATMOUNT_MNT_POINT | STATMOUNT_FS_TYPE)?;
+//!     println!("{:?} {:?}", info.mnt_point(), info.fs_type());
+//! }
+//! ```
+use core::ffi::CStr;
+use core::mem::{size_of, MaybeUninit};
+
+use crate::fd::{AsFd, AsRawFd, BorrowedFd, OwnedFd};
+use crate::types::*;
+use crate::syscall::*;
+use crate::limits::PATH_MAX;
+use crate::fs::{cstr, empty};
+use crate::error::{Error, Result};
+
+/// A filesystem which is being configured, or a mounted one which is being reconfigured
+pub struct FsContext {
+    fd: OwnedFd,
+    attr: u64,
+}
+
+impl FsContext {
+    /// Configure a new superblock of the filesystem type, like "tmpfs" or "proc"
+    pub fn new(fstype: &str) -> Result<Self> {
+        let mut buf = [0u8; PATH_MAX];
+        let fd = fsopen(cstr(fstype, &mut buf)?, FSOPEN_CLOEXEC)?;
+        Ok(Self { fd, attr: 0 })
+    }
+
+    /// Reconfigure the superblock of the mount at `path`, the changes are applied with
+    /// [FsContext::reconfigure]
+    pub fn pick(path: &str) -> Result<Self> {
+        let mut buf = [0u8; PATH_MAX];
+        let fd = fspick(AT_FDCWD, cstr(path, &mut buf)?, FSPICK_CLOEXEC)?;
+        Ok(Self { fd, attr: 0 })
+    }
+
+    /// Set the parameter `key` to a string like the options of mount -o key=value
+    pub fn set(&mut self, key: &str, value: &str) -> Result<&mut Self> {
+        let mut k = [0u8; PATH_MAX];
+        let mut v = [0u8; PATH_MAX];
+        let value = cstr(value, &mut v)?;
+        unsafe { fsconfig(self.fd(), FSCONFIG_SET_STRING, Some(cstr(key, &mut k)?), value.as_ptr().cast(), 0)? };
+        Ok(self)
+    }
+
+    /// Set a parameter without a value like "ro"
+    pub fn set_flag(&mut self, key: &str) -> Result<&mut Self> {
+        let mut k = [0u8; PATH_MAX];
+        unsafe { fsconfig(self.fd(), FSCONFIG_SET_FLAG, Some(cstr(key, &mut k)?), core::ptr::null(), 0)? };
+        Ok(self)
+    }
+
+    /// Set a parameter which takes a file descriptor, like "source" of a block device
+    pub fn set_fd(&mut self, key: &str, fd: BorrowedFd<'_>) -> Result<&mut Self> {
+        let mut k = [0u8; PATH_MAX];
+        unsafe { fsconfig(self.fd(), FSCONFIG_SET_FD, Some(cstr(key, &mut k)?), core::ptr::null(), fd.as_raw_fd() as i32)? };
+        Ok(self)
+    }
+
+    /// The "source" parameter, the device or a name which shows up in the mount table
+    pub fn source(&mut self, source: &str) -> Result<&mut Self> {
+        self.set("source", source)
+    }
+
+    /// MOUNT_ATTR_* flags of the mount created by [FsContext::create]
+    pub fn attr(&mut self, attr: u64) -> &mut Self {
+        self.attr = attr;
+        self
+    }
+
+    /// Create the superblock and a detached mount of it
+    pub fn create(&mut self) -> Result<Mount> {
+        unsafe { fsconfig(self.fd(), FSCONFIG_CMD_CREATE, None, core::ptr::null(), 0)? };
+        let fd = fsmount(self.fd(), FSMOUNT_CLOEXEC, self.attr as u32)?;
+        Ok(Mount { fd })
+    }
+
+    /// Apply the parameters to the superblock opened with [FsContext::pick]
+    pub fn reconfigure(&mut self) -> Result<()> {
+        unsafe { fsconfig(self.fd(), FSCONFIG_CMD_RECONFIGURE, None, core::ptr::null(), 0) }
+    }
+
+    /// The next message the filesystem logged for the context, e.g. why a parameter was
+    /// rejected. The message starts with "e ", "w " or "i " for error, warning and info.
+    pub fn message<'b>(&self, buf: &'b mut [u8]) -> Option<&'b [u8]> {
+        match read(self.fd(), buf) {
+            Ok(n) => Some(&buf[..n]),
+            Err(_) => None,
+        }
+    }
+
+    pub fn fd(&self) -> u32 {
+        self.fd.as_raw_fd()
+    }
+}
+
+impl AsFd for FsContext {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+/// A mount referred to by a file descriptor, detached when it comes from [FsContext::create]
+/// or [Mount::bind]. A detached mount which was never attached is unmounted on drop.
+pub struct Mount {
+    fd: OwnedFd,
+}
+
+impl Mount {
+    /// A detached bind mount of `path`, with its submounts when `recursive` is set
+    pub fn bind(path: &str, recursive: bool) -> Result<Self> {
+        let mut buf = [0u8; PATH_MAX];
+        let flags = OPEN_TREE_CLONE | OPEN_TREE_CLOEXEC | if recursive { AT_RECURSIVE as u32 } else { 0 };
+        let fd = open_tree(AT_FDCWD, cstr(path, &mut buf)?, flags)?;
+        Ok(Self { fd })
+    }
+
+    /// The mount at `path` itself, to change its attributes or to move it
+    pub fn open(path: &str) -> Result<Self> {
+        let mut buf = [0u8; PATH_MAX];
+        let fd = open_tree(AT_FDCWD, cstr(path, &mut buf)?, OPEN_TREE_CLOEXEC)?;
+        Ok(Self { fd })
+    }
+
+    /// Attach the mount at `path` relative to `dir`, or move it there if it is attached
+    pub fn mount_at(&self, dir: BorrowedFd<'_>, path: &str) -> Result<()> {
+        self.move_to(dir.as_raw_fd() as i32, path)
+    }
+
+    /// Attach the mount at `path` relative to the working directory, or move it there
+    pub fn mount_at_cwd(&self, path: &str) -> Result<()> {
+        self.move_to(AT_FDCWD, path)
+    }
+
+    fn move_to(&self, dirfd: i32, path: &str) -> Result<()> {
+        let mut buf = [0u8; PATH_MAX];
+        move_mount(self.dirfd(), empty(), dirfd, cstr(path, &mut buf)?, MOVE_MOUNT_F_EMPTY_PATH)
+    }
+
+    /// Set and clear MOUNT_ATTR_* flags, of all submounts too when `recursive` is set
+    pub fn set_attr(&self, set: u64, clear: u64, recursive: bool) -> Result<()> {
+        let attr = mount_attr { attr_set: set, attr_clr: clear, ..Default::default() };
+        self.setattr(&attr, recursive)
+    }
+
+    /// Change the propagation to MS_SHARED, MS_SLAVE, MS_PRIVATE or MS_UNBINDABLE
+    pub fn set_propagation(&self, propagation: u64, recursive: bool) -> Result<()> {
+        let attr = mount_attr { propagation, ..Default::default() };
+        self.setattr(&attr, recursive)
+    }
+
+    fn setattr(&self, attr: &mount_attr, recursive: bool) -> Result<()> {
+        let flags = AT_EMPTY_PATH as u32 | if recursive { AT_RECURSIVE as u32 } else { 0 };
+        mount_setattr(self.dirfd(), empty(), flags, attr)
+    }
+
+    /// The unique id of the mount for [MountInfo] and [ListMounts]
+    pub fn id(&self) -> Result<u64> {
+        let mut stx = statx::default();
+        statx(self.dirfd(), empty(), AT_EMPTY_PATH, STATX_MNT_ID_UNIQUE, &mut stx)?;
+        match stx.stx_mask & STATX_MNT_ID_UNIQUE {
+            0 => Err(Error::EOPNOTSUPP),
+            _ => Ok(stx.stx_mnt_id),
+        }
+    }
+
+    pub fn fd(&self) -> u32 {
+        self.fd.as_raw_fd()
+    }
+
+    fn dirfd(&self) -> i32 {
+        self.fd.as_raw_fd() as i32
+    }
+}
+
+impl AsFd for Mount {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+/// Iterates over the unique ids of the mounts below a mount with listmount, N ids per call
+pub struct ListMounts<const N: usize = 64> {
+    req: mnt_id_req,
+    flags: u32,
+    ids: [u64; N],
+    pos: usize,
+    len: usize,
+    done: bool,
+}
+
+impl<const N: usize> ListMounts<N> {
+    /// The mounts below `mnt_id` in the mount namespace of the caller, all with LSMT_ROOT
+    pub fn new(mnt_id: u64) -> Self {
+        Self::with_flags(mnt_id, 0)
+    }
+
+    /// `flags` are LISTMOUNT_REVERSE to list the newest mounts first
+    pub fn with_flags(mnt_id: u64, flags: u32) -> Self {
+        let req = mnt_id_req {
+            size: size_of::<mnt_id_req>() as u32,
+            mnt_id,
+            ..Default::default()
+        };
+        Self { req, flags, ids: [0; N], pos: 0, len: 0, done: false }
+    }
+}
+
+impl<const N: usize> Iterator for ListMounts<N> {
+    type Item = Result<u64>;
+
+    fn next(&mut self) -> Option<Result<u64>> {
+        if self.pos == self.len {
+            if self.done {
+                return None;
+            }
+            match listmount(&self.req, &mut self.ids, self.flags) {
+                Ok(n) => {
+                    self.pos = 0;
+                    self.len = n;
+                    self.done = n < N;
+                }
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            }
+            if self.len == 0 {
+                return None;
+            }
+            // The next call continues after the last id
+            self.req.param = self.ids[self.len - 1];
+        }
+
+        self.pos += 1;
+        Some(Ok(self.ids[self.pos - 1]))
+    }
+}
+
+/// The result of statmount with N bytes for the strings
+#[repr(C)]
+pub struct MountInfo<const N: usize = 4096> {
+    sm: statmount,
+    strings: [u8; N],
+}
+
+impl<const N: usize> MountInfo<N> {
+    /// The STATMOUNT_* fields in `mask` of the mount with the unique id `mnt_id`. EOVERFLOW
+    /// when the strings don't fit into N bytes.
+    pub fn new(mnt_id: u64, mask: u64) -> Result<Self> {
+        let req = mnt_id_req {
+            size: size_of::<mnt_id_req>() as u32,
+            mnt_id,
+            param: mask,
+            ..Default::default()
+        };
+        let mut info = MaybeUninit::<Self>::zeroed();
+        unsafe {
+            statmount(&req, info.as_mut_ptr().cast(), size_of::<Self>(), 0)?;
+            Ok(info.assume_init())
+        }
+    }
+
+    pub fn raw(&self) -> &statmount {
+        &self.sm
+    }
+
+    /// The STATMOUNT_* fields which are set
+    pub fn mask(&self) -> u64 {
+        self.sm.mask
+    }
+
+    fn has(&self, mask: u64) -> bool {
+        self.sm.mask & mask != 0
+    }
+
+    fn string(&self, mask: u64, offset: u32) -> Option<&CStr> {
+        if !self.has(mask) {
+            return None;
+        }
+        CStr::from_bytes_until_nul(self.strings.get(offset as usize..)?).ok()
+    }
+
+    pub fn mnt_id(&self) -> u64 {
+        self.sm.mnt_id
+    }
+
+    pub fn parent_id(&self) -> u64 {
+        self.sm.mnt_parent_id
+    }
+
+    /// The id of the mount in /proc/self/mountinfo, which is reused after an unmount
+    pub fn mnt_id_old(&self) -> u32 {
+        self.sm.mnt_id_old
+    }
+
+    /// The MOUNT_ATTR_* flags, with STATMOUNT_MNT_BASIC
+    pub fn attr(&self) -> u64 {
+        self.sm.mnt_attr
+    }
+
+    /// One of MS_SHARED, MS_SLAVE, MS_PRIVATE and MS_UNBINDABLE, with STATMOUNT_MNT_BASIC
+    pub fn propagation(&self) -> u64 {
+        self.sm.mnt_propagation
+    }
+
+    /// The device of the superblock as (major, minor), with STATMOUNT_SB_BASIC
+    pub fn dev(&self) -> (u32, u32) {
+        (self.sm.sb_dev_major, self.sm.sb_dev_minor)
+    }
+
+    /// The ..._SUPER_MAGIC of the filesystem, with STATMOUNT_SB_BASIC
+    pub fn magic(&self) -> u64 {
+        self.sm.sb_magic
+    }
+
+    pub fn ns_id(&self) -> Option<u64> {
+        self.has(STATMOUNT_MNT_NS_ID).then_some(self.sm.mnt_ns_id)
+    }
+
+    pub fn fs_type(&self) -> Option<&CStr> {
+        self.string(STATMOUNT_FS_TYPE, self.sm.fs_type)
+    }
+
+    pub fn fs_subtype(&self) -> Option<&CStr> {
+        self.string(STATMOUNT_FS_SUBTYPE, self.sm.fs_subtype)
+    }
+
+    /// Where the mount is, relative to the root of the caller
+    pub fn mnt_point(&self) -> Option<&CStr> {
+        self.string(STATMOUNT_MNT_POINT, self.sm.mnt_point)
+    }
+
+    /// The directory of the filesystem which is mounted, "/" unless it is a bind mount
+    pub fn mnt_root(&self) -> Option<&CStr> {
+        self.string(STATMOUNT_MNT_ROOT, self.sm.mnt_root)
+    }
+
+    /// The options of the superblock separated by commas
+    pub fn mnt_opts(&self) -> Option<&CStr> {
+        self.string(STATMOUNT_MNT_OPTS, self.sm.mnt_opts)
+    }
+
+    /// The source like the device
+    pub fn sb_source(&self) -> Option<&CStr> {
+        self.string(STATMOUNT_SB_SOURCE, self.sm.sb_source)
+    }
+}
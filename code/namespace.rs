@@ -0,0 +1,209 @@
+//! Linux namespaces.
+//!
+//! A process is moved into new namespaces with [unshare] or joins the namespaces of another
+//! process through a [Namespace] handle. A new user namespace starts without any ids mapped,
+//! [map_user] maps root in the namespace to a user outside of it so the process gets the
+//! capabilities it needs to create the other namespaces and to mount.
+//!
+//! ```ignore
+//! let (uid, gid) = (getuid()? as u32, getgid()? as u32);
+//! unshare(CloneFlags::NEWUSER | CloneFlags::NEWNS | CloneFlags::NEWUTS)?;
+//! map_user(0, uid, gid)?;
+//! set_hostname("sandbox")?;
+//! ```
+use core::fmt::Write;
+use core::ops::BitOr;
+
+use crate::fd::{AsFd, AsRawFd, BorrowedFd, OwnedFd};
+use crate::io::Buf;
+use crate::mount::Mount;
+use crate::types::*;
+use crate::syscall::*;
+use crate::constants::*;
+use crate::error::{Error, Result};
+
+/// The types of namespaces, combined with `|`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CloneFlags(u64);
+
+impl CloneFlags {
+    pub const NONE: Self = Self(0);
+
+    /// Mount points
+    pub const NEWNS: Self = Self(CLONE_NEWNS);
+
+    /// The root of the cgroup hierarchy
+    pub const NEWCGROUP: Self = Self(CLONE_NEWCGROUP);
+
+    /// Hostname and NIS domain name
+    pub const NEWUTS: Self = Self(CLONE_NEWUTS);
+
+    /// System V IPC objects and POSIX message queues
+    pub const NEWIPC: Self = Self(CLONE_NEWIPC);
+
+    /// User and group ids and capabilities
+    pub const NEWUSER: Self = Self(CLONE_NEWUSER);
+
+    /// Process ids, only for the children of the caller
+    pub const NEWPID: Self = Self(CLONE_NEWPID);
+
+    /// Network devices, addresses, routes and firewall rules
+    pub const NEWNET: Self = Self(CLONE_NEWNET);
+
+    /// CLOCK_MONOTONIC and CLOCK_BOOTTIME offsets, only for the children of the caller
+    pub const NEWTIME: Self = Self(CLONE_NEWTIME);
+
+    pub fn bits(&self) -> u64 {
+        self.0
+    }
+
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// The name of the file in /proc/<pid>/ns of a single type
+    pub fn name(&self) -> Option<&'static str> {
+        match self.0 {
+            CLONE_NEWNS => Some("mnt"),
+            CLONE_NEWCGROUP => Some("cgroup"),
+            CLONE_NEWUTS => Some("uts"),
+            CLONE_NEWIPC => Some("ipc"),
+            CLONE_NEWUSER => Some("user"),
+            CLONE_NEWPID => Some("pid"),
+            CLONE_NEWNET => Some("net"),
+            CLONE_NEWTIME => Some("time"),
+            _ => None,
+        }
+    }
+}
+
+impl BitOr for CloneFlags {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+/// Move the calling process into new namespaces
+pub fn unshare(flags: CloneFlags) -> Result<()> {
+    crate::syscall::unshare(flags.0)
+}
+
+/// A namespace of a process, which stays alive as long as the handle is open
+pub struct Namespace {
+    fd: OwnedFd,
+    kind: CloneFlags,
+}
+
+impl Namespace {
+    /// The namespace of the type `kind` of the process `pid`, of the caller for pid 0
+    pub fn open(pid: pid_t, kind: CloneFlags) -> Result<Self> {
+        let name = kind.name().ok_or(Error::EINVAL)?;
+        let mut path = Buf::<64>::new();
+        proc_path(&mut path, pid, "ns/")?;
+        write!(path, "{}", name).map_err(|_| Error::ENAMETOOLONG)?;
+        let fd = open(path.as_str(), O_RDONLY | O_CLOEXEC, 0)?;
+        Ok(Self { fd, kind })
+    }
+
+    /// Move the calling thread into the namespace. Joining a user namespace requires a single
+    /// threaded process, joining a mount namespace CAP_SYS_ADMIN in the owning user namespace.
+    pub fn enter(&self) -> Result<()> {
+        setns(self.fd.as_raw_fd(), self.kind.0)
+    }
+
+    pub fn kind(&self) -> CloneFlags {
+        self.kind
+    }
+}
+
+impl AsFd for Namespace {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+/// A range of `count` ids starting at `inside` in the namespace which are `outside` in the
+/// parent namespace
+#[derive(Debug, Clone, Copy)]
+pub struct IdMap {
+    pub inside: u32,
+    pub outside: u32,
+    pub count: u32,
+}
+
+/// Write /proc/<pid>/uid_map, which can only be written once. Without CAP_SETUID in the parent
+/// namespace it may only map the effective uid of the process which created the namespace.
+pub fn write_uid_map(pid: pid_t, maps: &[IdMap]) -> Result<()> {
+    write_map(pid, "uid_map", maps)
+}
+
+/// Write /proc/<pid>/gid_map. Without CAP_SETGID in the parent namespace [deny_setgroups]
+/// has to be called before.
+pub fn write_gid_map(pid: pid_t, maps: &[IdMap]) -> Result<()> {
+    write_map(pid, "gid_map", maps)
+}
+
+/// Disable setgroups in the namespace of the process `pid`, it could otherwise drop a group
+/// which denies access to a file
+pub fn deny_setgroups(pid: pid_t) -> Result<()> {
+    write_file(pid, "setgroups", b"deny")
+}
+
+/// Map root in the user namespace of `pid` to `uid` and `gid` outside of it
+pub fn map_user(pid: pid_t, uid: u32, gid: u32) -> Result<()> {
+    deny_setgroups(pid)?;
+    write_uid_map(pid, &[IdMap { inside: 0, outside: uid, count: 1 }])?;
+    write_gid_map(pid, &[IdMap { inside: 0, outside: gid, count: 1 }])
+}
+
+fn write_map(pid: pid_t, file: &str, maps: &[IdMap]) -> Result<()> {
+    // The whole map has to be written with a single write
+    let mut text = Buf::<1024>::new();
+    for map in maps {
+        writeln!(text, "{} {} {}", map.inside, map.outside, map.count).map_err(|_| Error::E2BIG)?;
+    }
+    write_file(pid, file, text.as_str().as_bytes())
+}
+
+fn write_file(pid: pid_t, file: &str, data: &[u8]) -> Result<()> {
+    let mut path = Buf::<64>::new();
+    proc_path(&mut path, pid, file)?;
+    let fd = open(path.as_str(), O_WRONLY | O_CLOEXEC, 0)?;
+    match write(fd.as_raw_fd(), data)? == data.len() {
+        true => Ok(()),
+        false => Err(Error::EIO),
+    }
+}
+
+/// /proc/<pid>/<file> or /proc/self/<file> for pid 0
+fn proc_path<const N: usize>(path: &mut Buf<N>, pid: pid_t, file: &str) -> Result<()> {
+    let res = match pid {
+        0 => write!(path, "/proc/self/{}", file),
+        pid => write!(path, "/proc/{}/{}", pid, file),
+    };
+    res.map_err(|_| Error::ENAMETOOLONG)
+}
+
+/// Make the directory `new_root` the root of the mount namespace and detach the old root.
+/// The namespace shouldn't share mounts with the parent namespace, the propagation of "/" is
+/// changed to private first so the old root isn't unmounted in the parent too.
+pub fn pivot_root(new_root: &str) -> Result<()> {
+    Mount::open("/")?.set_propagation(MS_PRIVATE, true)?;
+
+    // The new root has to be a mount point, bind mounting it onto itself makes one
+    Mount::bind(new_root, true)?.mount_at_cwd(new_root)?;
+    chdir(new_root)?;
+
+    // Stack the old root on top of the new one and unmount it from there
+    crate::syscall::pivot_root(".", ".")?;
+    umount(unsafe { core::ffi::CStr::from_bytes_with_nul_unchecked(b".\0") }, MNT_DETACH)?;
+    chdir("/")
+}
+
+/// Set the hostname of the UTS namespace
+pub fn set_hostname(name: &str) -> Result<()> {
+    sethostname(name.as_bytes())
+}
+
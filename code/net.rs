@@ -0,0 +1,574 @@
+//! TCP, UDP and Unix domain sockets on top of the socket system calls.
+//!
+//! The internet addresses are the ones from `core::net`, the Unix socket addresses are
+//! represented by [UnixAddr] which can hold a path or a name in the abstract namespace.
+//! All sockets are created with SOCK_CLOEXEC and closed when they are dropped.
+use core::fmt;
+use core::mem::size_of;
+
+use crate::io::{Read, Write};
+use crate::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd, RawFd};
+use crate::types::*;
+use crate::syscall::*;
+use crate::constants::*;
+use crate::error::{Error, Result};
+
+pub use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+/// Which direction of a connection to shut down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shutdown {
+    Read,
+    Write,
+    Both,
+}
+
+/// The file descriptor shared by all socket types
+struct Socket {
+    fd: OwnedFd,
+}
+
+impl Socket {
+    fn new(domain: i32, ty: i32) -> Result<Self> {
+        socket(domain, ty | SOCK_CLOEXEC, 0).map(|fd| Self { fd })
+    }
+
+    fn raw(&self) -> u32 {
+        self.fd.as_raw_fd()
+    }
+
+    fn accept(&self, addr: &mut sockaddr_storage) -> Result<(Self, socklen_t)> {
+        accept4(self.raw(), addr, SOCK_CLOEXEC).map(|(fd, len)| (Self { fd }, len))
+    }
+
+    fn recv(&self, buf: &mut [u8], flags: i32) -> Result<usize> {
+        recvfrom(self.raw(), buf, flags, None).map(|(n, _)| n)
+    }
+
+    /// Writing to a closed connection returns EPIPE instead of raising SIGPIPE
+    fn send(&self, buf: &[u8]) -> Result<usize> {
+        sendto(self.raw(), buf, MSG_NOSIGNAL, None)
+    }
+
+    fn shutdown(&self, how: Shutdown) -> Result<()> {
+        let how = match how {
+            Shutdown::Read => SHUT_RD,
+            Shutdown::Write => SHUT_WR,
+            Shutdown::Both => SHUT_RDWR,
+        };
+        shutdown(self.raw(), how)
+    }
+
+    fn set_nonblocking(&self, nonblocking: bool) -> Result<()> {
+        let flags = fcntl(self.raw(), F_GETFL, 0)? as i32;
+        let flags = match nonblocking {
+            true => flags | O_NONBLOCK,
+            false => flags & !O_NONBLOCK,
+        };
+        fcntl(self.raw(), F_SETFL, flags as usize).map(|_| ())
+    }
+
+    fn set_option(&self, level: i32, name: i32, val: i32) -> Result<()> {
+        setsockopt(self.raw(), level, name, &val.to_ne_bytes())
+    }
+
+    fn option(&self, level: i32, name: i32) -> Result<i32> {
+        let mut val = [0u8; 4];
+        getsockopt(self.raw(), level, name, &mut val)?;
+        Ok(i32::from_ne_bytes(val))
+    }
+
+    /// Pending error of the socket, e.g. the result of a non-blocking connect
+    fn take_error(&self) -> Result<Option<Error>> {
+        match self.option(SOL_SOCKET, SO_ERROR)? {
+            0 => Ok(None),
+            errno => Ok(Some(Error::from(errno as isize))),
+        }
+    }
+}
+
+fn to_raw(addr: &SocketAddr) -> (sockaddr_storage, socklen_t) {
+    let mut storage = sockaddr_storage::default();
+    let len = match addr {
+        SocketAddr::V4(addr) => {
+            let raw = sockaddr_in {
+                sin_family: AF_INET as sa_family_t,
+                sin_port: addr.port().to_be(),
+                sin_addr: in_addr { s_addr: u32::from_ne_bytes(addr.ip().octets()) },
+                sin_zero: [0; 8],
+            };
+            unsafe { (&mut storage as *mut sockaddr_storage as *mut sockaddr_in).write(raw) };
+            size_of::<sockaddr_in>()
+        }
+        SocketAddr::V6(addr) => {
+            let raw = sockaddr_in6 {
+                sin6_family: AF_INET6 as sa_family_t,
+                sin6_port: addr.port().to_be(),
+                sin6_flowinfo: addr.flowinfo().to_be(),
+                sin6_addr: in6_addr { s6_addr: addr.ip().octets() },
+                sin6_scope_id: addr.scope_id(),
+            };
+            unsafe { (&mut storage as *mut sockaddr_storage as *mut sockaddr_in6).write(raw) };
+            size_of::<sockaddr_in6>()
+        }
+    };
+    (storage, len as socklen_t)
+}
+
+fn from_raw(storage: &sockaddr_storage, len: socklen_t) -> Result<SocketAddr> {
+    let ptr = storage as *const sockaddr_storage;
+    match storage.ss_family as i32 {
+        AF_INET if len as usize >= size_of::<sockaddr_in>() => {
+            let raw = unsafe { &*(ptr as *const sockaddr_in) };
+            let ip = Ipv4Addr::from(raw.sin_addr.s_addr.to_ne_bytes());
+            Ok(SocketAddr::V4(SocketAddrV4::new(ip, u16::from_be(raw.sin_port))))
+        }
+        AF_INET6 if len as usize >= size_of::<sockaddr_in6>() => {
+            let raw = unsafe { &*(ptr as *const sockaddr_in6) };
+            let ip = Ipv6Addr::from(raw.sin6_addr.s6_addr);
+            let port = u16::from_be(raw.sin6_port);
+            let flowinfo = u32::from_be(raw.sin6_flowinfo);
+            Ok(SocketAddr::V6(SocketAddrV6::new(ip, port, flowinfo, raw.sin6_scope_id)))
+        }
+        _ => Err(Error::EAFNOSUPPORT),
+    }
+}
+
+fn domain(addr: &SocketAddr) -> i32 {
+    match addr {
+        SocketAddr::V4(_) => AF_INET,
+        SocketAddr::V6(_) => AF_INET6,
+    }
+}
+
+fn local_addr(fd: u32) -> Result<SocketAddr> {
+    let mut storage = sockaddr_storage::default();
+    let len = getsockname(fd, &mut storage)?;
+    from_raw(&storage, len)
+}
+
+fn peer_addr(fd: u32) -> Result<SocketAddr> {
+    let mut storage = sockaddr_storage::default();
+    let len = getpeername(fd, &mut storage)?;
+    from_raw(&storage, len)
+}
+
+// ==============================================================================
+// TCP
+// ==============================================================================
+
+pub struct TcpListener {
+    socket: Socket,
+}
+
+impl TcpListener {
+    /// Listen on the address. SO_REUSEADDR is set so that a restarted server doesn't have
+    /// to wait for the connections of the previous one in TIME_WAIT. Bind to port 0 to let
+    /// the kernel pick a free port and read it back with local_addr().
+    pub fn bind(addr: SocketAddr) -> Result<Self> {
+        let socket = Socket::new(domain(&addr), SOCK_STREAM)?;
+        socket.set_option(SOL_SOCKET, SO_REUSEADDR, 1)?;
+
+        let (raw, len) = to_raw(&addr);
+        bind(socket.raw(), &raw, len)?;
+        listen(socket.raw(), 128)?;
+        Ok(Self { socket })
+    }
+
+    pub fn accept(&self) -> Result<(TcpStream, SocketAddr)> {
+        let mut raw = sockaddr_storage::default();
+        let (socket, len) = self.socket.accept(&mut raw)?;
+        Ok((TcpStream { socket }, from_raw(&raw, len)?))
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        local_addr(self.socket.raw())
+    }
+
+    /// accept() returns EAGAIN instead of blocking when there is no pending connection
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<()> {
+        self.socket.set_nonblocking(nonblocking)
+    }
+
+    pub fn take_error(&self) -> Result<Option<Error>> {
+        self.socket.take_error()
+    }
+
+    pub fn fd(&self) -> u32 {
+        self.socket.raw()
+    }
+}
+
+pub struct TcpStream {
+    socket: Socket,
+}
+
+impl TcpStream {
+    pub fn connect(addr: SocketAddr) -> Result<Self> {
+        let socket = Socket::new(domain(&addr), SOCK_STREAM)?;
+        let (raw, len) = to_raw(&addr);
+        connect(socket.raw(), &raw, len)?;
+        Ok(Self { socket })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        local_addr(self.socket.raw())
+    }
+
+    pub fn peer_addr(&self) -> Result<SocketAddr> {
+        peer_addr(self.socket.raw())
+    }
+
+    pub fn shutdown(&self, how: Shutdown) -> Result<()> {
+        self.socket.shutdown(how)
+    }
+
+    /// Read without removing the data from the receive queue
+    pub fn peek(&self, buf: &mut [u8]) -> Result<usize> {
+        self.socket.recv(buf, MSG_PEEK)
+    }
+
+    /// Disable Nagle's algorithm to send small segments immediately
+    pub fn set_nodelay(&self, nodelay: bool) -> Result<()> {
+        self.socket.set_option(IPPROTO_TCP, TCP_NODELAY, nodelay as i32)
+    }
+
+    pub fn nodelay(&self) -> Result<bool> {
+        self.socket.option(IPPROTO_TCP, TCP_NODELAY).map(|val| val != 0)
+    }
+
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<()> {
+        self.socket.set_nonblocking(nonblocking)
+    }
+
+    pub fn take_error(&self) -> Result<Option<Error>> {
+        self.socket.take_error()
+    }
+
+    pub fn fd(&self) -> u32 {
+        self.socket.raw()
+    }
+}
+
+impl Read for TcpStream {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.socket.recv(buf, 0)
+    }
+}
+
+impl Write for TcpStream {
+    fn flush() -> Result<()> {
+        Ok(())
+    }
+
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.socket.send(buf)
+    }
+}
+
+// ==============================================================================
+// UDP
+// ==============================================================================
+
+pub struct UdpSocket {
+    socket: Socket,
+}
+
+impl UdpSocket {
+    pub fn bind(addr: SocketAddr) -> Result<Self> {
+        let socket = Socket::new(domain(&addr), SOCK_DGRAM)?;
+        let (raw, len) = to_raw(&addr);
+        bind(socket.raw(), &raw, len)?;
+        Ok(Self { socket })
+    }
+
+    /// Set the default destination of send() and drop the datagrams from other addresses
+    pub fn connect(&self, addr: SocketAddr) -> Result<()> {
+        let (raw, len) = to_raw(&addr);
+        connect(self.socket.raw(), &raw, len)
+    }
+
+    pub fn send(&self, buf: &[u8]) -> Result<usize> {
+        self.socket.send(buf)
+    }
+
+    /// Receive a datagram. The rest of a datagram which doesn't fit into `buf` is discarded.
+    pub fn recv(&self, buf: &mut [u8]) -> Result<usize> {
+        self.socket.recv(buf, 0)
+    }
+
+    pub fn send_to(&self, buf: &[u8], addr: SocketAddr) -> Result<usize> {
+        let (raw, len) = to_raw(&addr);
+        sendto(self.socket.raw(), buf, MSG_NOSIGNAL, Some((&raw, len)))
+    }
+
+    pub fn recv_from(&self, buf: &mut [u8]) -> Result<(usize, SocketAddr)> {
+        let mut raw = sockaddr_storage::default();
+        let (n, len) = recvfrom(self.socket.raw(), buf, 0, Some(&mut raw))?;
+        Ok((n, from_raw(&raw, len)?))
+    }
+
+    pub fn peek_from(&self, buf: &mut [u8]) -> Result<(usize, SocketAddr)> {
+        let mut raw = sockaddr_storage::default();
+        let (n, len) = recvfrom(self.socket.raw(), buf, MSG_PEEK, Some(&mut raw))?;
+        Ok((n, from_raw(&raw, len)?))
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        local_addr(self.socket.raw())
+    }
+
+    pub fn peer_addr(&self) -> Result<SocketAddr> {
+        peer_addr(self.socket.raw())
+    }
+
+    pub fn set_broadcast(&self, broadcast: bool) -> Result<()> {
+        self.socket.set_option(SOL_SOCKET, SO_BROADCAST, broadcast as i32)
+    }
+
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<()> {
+        self.socket.set_nonblocking(nonblocking)
+    }
+
+    pub fn take_error(&self) -> Result<Option<Error>> {
+        self.socket.take_error()
+    }
+
+    pub fn fd(&self) -> u32 {
+        self.socket.raw()
+    }
+}
+
+// ==============================================================================
+// Unix domain sockets
+// ==============================================================================
+
+/// Offset of sun_path in sockaddr_un
+const SUN_PATH: usize = 2;
+
+/// Address of a Unix domain socket: a path in the file system, a name in the abstract
+/// namespace (not visible in the file system and gone when the socket is closed) or unnamed.
+#[derive(Clone, Copy)]
+pub struct UnixAddr {
+    raw: sockaddr_un,
+    len: socklen_t,
+}
+
+impl UnixAddr {
+    pub fn path(path: &str) -> Result<Self> {
+        let mut raw = sockaddr_un { sun_family: AF_UNIX as sa_family_t, ..Default::default() };
+        crate::cstr(path.as_bytes(), &mut raw.sun_path)?;
+        Ok(Self { raw, len: (SUN_PATH + path.len() + 1) as socklen_t })
+    }
+
+    /// The name is stored after a leading null byte, it may contain any bytes
+    pub fn abstract_name(name: &[u8]) -> Result<Self> {
+        let mut raw = sockaddr_un { sun_family: AF_UNIX as sa_family_t, ..Default::default() };
+        if name.len() >= raw.sun_path.len() {
+            return Err(Error::ENAMETOOLONG);
+        }
+        raw.sun_path[1..=name.len()].copy_from_slice(name);
+        Ok(Self { raw, len: (SUN_PATH + 1 + name.len()) as socklen_t })
+    }
+
+    fn from_raw(storage: &sockaddr_storage, len: socklen_t) -> Result<Self> {
+        if storage.ss_family as i32 != AF_UNIX {
+            return Err(Error::EAFNOSUPPORT);
+        }
+        let raw = unsafe { *(storage as *const sockaddr_storage as *const sockaddr_un) };
+        Ok(Self { raw, len: len.min(size_of::<sockaddr_un>() as socklen_t) })
+    }
+
+    fn to_raw(&self) -> (sockaddr_storage, socklen_t) {
+        let mut storage = sockaddr_storage::default();
+        unsafe { (&mut storage as *mut sockaddr_storage as *mut sockaddr_un).write(self.raw) };
+        (storage, self.len)
+    }
+
+    /// Bytes of sun_path which belong to the address
+    fn name(&self) -> &[u8] {
+        let len = (self.len as usize).saturating_sub(SUN_PATH);
+        &self.raw.sun_path[..len]
+    }
+
+    /// E.g. the address of a socket returned by socketpair or of an unbound client
+    pub fn is_unnamed(&self) -> bool {
+        self.name().is_empty()
+    }
+
+    pub fn as_path(&self) -> Option<&str> {
+        let name = self.name();
+        match name.first() {
+            Some(0) | None => None,
+            Some(_) => {
+                let end = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+                core::str::from_utf8(&name[..end]).ok()
+            }
+        }
+    }
+
+    pub fn as_abstract(&self) -> Option<&[u8]> {
+        match self.name().split_first() {
+            Some((0, name)) => Some(name),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Debug for UnixAddr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(path) = self.as_path() {
+            return write!(f, "{:?} (pathname)", path);
+        }
+        if let Some(name) = self.as_abstract() {
+            write!(f, "\"")?;
+            for &b in name {
+                write!(f, "{}", core::ascii::escape_default(b))?;
+            }
+            return write!(f, "\" (abstract)");
+        }
+        write!(f, "(unnamed)")
+    }
+}
+
+fn unix_local_addr(fd: u32) -> Result<UnixAddr> {
+    let mut storage = sockaddr_storage::default();
+    let len = getsockname(fd, &mut storage)?;
+    UnixAddr::from_raw(&storage, len)
+}
+
+fn unix_peer_addr(fd: u32) -> Result<UnixAddr> {
+    let mut storage = sockaddr_storage::default();
+    let len = getpeername(fd, &mut storage)?;
+    UnixAddr::from_raw(&storage, len)
+}
+
+pub struct UnixListener {
+    socket: Socket,
+}
+
+impl UnixListener {
+    /// Binding to a path creates the socket file which has to be removed when it is no
+    /// longer needed, binding fails with EADDRINUSE while it exists.
+    pub fn bind(addr: &UnixAddr) -> Result<Self> {
+        let socket = Socket::new(AF_UNIX, SOCK_STREAM)?;
+        let (raw, len) = addr.to_raw();
+        bind(socket.raw(), &raw, len)?;
+        listen(socket.raw(), 128)?;
+        Ok(Self { socket })
+    }
+
+    pub fn accept(&self) -> Result<(UnixStream, UnixAddr)> {
+        let mut raw = sockaddr_storage::default();
+        let (socket, len) = self.socket.accept(&mut raw)?;
+        Ok((UnixStream { socket }, UnixAddr::from_raw(&raw, len)?))
+    }
+
+    pub fn local_addr(&self) -> Result<UnixAddr> {
+        unix_local_addr(self.socket.raw())
+    }
+
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<()> {
+        self.socket.set_nonblocking(nonblocking)
+    }
+
+    pub fn take_error(&self) -> Result<Option<Error>> {
+        self.socket.take_error()
+    }
+
+    pub fn fd(&self) -> u32 {
+        self.socket.raw()
+    }
+}
+
+pub struct UnixStream {
+    socket: Socket,
+}
+
+impl UnixStream {
+    pub fn connect(addr: &UnixAddr) -> Result<Self> {
+        let socket = Socket::new(AF_UNIX, SOCK_STREAM)?;
+        let (raw, len) = addr.to_raw();
+        connect(socket.raw(), &raw, len)?;
+        Ok(Self { socket })
+    }
+
+    /// A pair of connected, unnamed sockets
+    pub fn pair() -> Result<(Self, Self)> {
+        let (a, b) = socketpair(AF_UNIX, SOCK_STREAM | SOCK_CLOEXEC, 0)?;
+        Ok((Self { socket: Socket { fd: a } }, Self { socket: Socket { fd: b } }))
+    }
+
+    pub fn local_addr(&self) -> Result<UnixAddr> {
+        unix_local_addr(self.socket.raw())
+    }
+
+    pub fn peer_addr(&self) -> Result<UnixAddr> {
+        unix_peer_addr(self.socket.raw())
+    }
+
+    pub fn shutdown(&self, how: Shutdown) -> Result<()> {
+        self.socket.shutdown(how)
+    }
+
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<()> {
+        self.socket.set_nonblocking(nonblocking)
+    }
+
+    pub fn take_error(&self) -> Result<Option<Error>> {
+        self.socket.take_error()
+    }
+
+    pub fn fd(&self) -> u32 {
+        self.socket.raw()
+    }
+}
+
+impl Read for UnixStream {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.socket.recv(buf, 0)
+    }
+}
+
+impl Write for UnixStream {
+    fn flush() -> Result<()> {
+        Ok(())
+    }
+
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.socket.send(buf)
+    }
+}
+
+// ==============================================================================
+// File descriptors
+// ==============================================================================
+macro_rules! impl_fd {
+    ($($ty:ident),*) => {$(
+        impl AsFd for $ty {
+            fn as_fd(&self) -> BorrowedFd<'_> {
+                self.socket.fd.as_fd()
+            }
+        }
+
+        impl AsRawFd for $ty {
+            fn as_raw_fd(&self) -> RawFd {
+                self.socket.raw()
+            }
+        }
+
+        impl IntoRawFd for $ty {
+            fn into_raw_fd(self) -> RawFd {
+                self.socket.fd.into_raw_fd()
+            }
+        }
+
+        impl FromRawFd for $ty {
+            unsafe fn from_raw_fd(fd: RawFd) -> Self {
+                Self { socket: Socket { fd: OwnedFd::from_raw_fd(fd) } }
+            }
+        }
+    )*};
+}
+
+impl_fd!(TcpListener, TcpStream, UdpSocket, UnixListener, UnixStream);
@@ -0,0 +1,472 @@
+use core::fmt;
+
+use crate::env;
+use crate::io::File;
+use crate::fd::{AsRawFd, OwnedFd};
+use crate::types::*;
+use crate::syscall::*;
+use crate::constants::*;
+use crate::error::{Error, Result};
+
+const MAX_ARGS: usize = 128;
+const MAX_ENVS: usize = 64;
+const MAX_ENVP: usize = 1024;
+
+/// Where the standard streams of the child are connected to
+#[derive(Debug, Clone, Copy)]
+pub enum Stdio {
+    /// Use the same file as the parent
+    Inherit,
+
+    /// Connect the stream to /dev/null
+    Null,
+
+    /// Create a pipe which is available through Child.stdin, Child.stdout or Child.stderr
+    Piped,
+
+    /// Use a file descriptor owned by the caller
+    Fd(u32),
+}
+
+// ==============================================================================
+// Command
+// ==============================================================================
+/// A process builder which doesn't allocate. The arguments and the environment are
+/// borrowed and they are turned into nul terminated strings only when spawning.
+pub struct Command<'a> {
+    program: &'a str,
+    args: [&'a str; MAX_ARGS],
+    argc: usize,
+    envs: [(&'a str, Option<&'a str>); MAX_ENVS],
+    envc: usize,
+    env_clear: bool,
+    cwd: Option<&'a str>,
+    stdin: Stdio,
+    stdout: Stdio,
+    stderr: Stdio,
+    pre_exec: Option<&'a dyn Fn() -> Result<()>>,
+    overflow: bool,
+}
+
+impl<'a> Command<'a> {
+    /// If the program doesn't contain a slash then it is searched in the PATH of the parent.
+    pub fn new(program: &'a str) -> Self {
+        Self {
+            program,
+            args: [""; MAX_ARGS],
+            argc: 0,
+            envs: [("", None); MAX_ENVS],
+            envc: 0,
+            env_clear: false,
+            cwd: None,
+            stdin: Stdio::Inherit,
+            stdout: Stdio::Inherit,
+            stderr: Stdio::Inherit,
+            pre_exec: None,
+            overflow: false,
+        }
+    }
+
+    pub fn arg(&mut self, arg: &'a str) -> &mut Self {
+        match self.argc < MAX_ARGS {
+            true => { self.args[self.argc] = arg; self.argc += 1; }
+            false => { self.overflow = true; }
+        }
+        self
+    }
+
+    /// Use `.args(env::args().skip(1))` to forward the arguments of the current process.
+    pub fn args<I: IntoIterator<Item = &'a str>>(&mut self, args: I) -> &mut Self {
+        for arg in args {
+            self.arg(arg);
+        }
+        self
+    }
+
+    pub fn env(&mut self, key: &'a str, val: &'a str) -> &mut Self {
+        self.set_env(key, Some(val))
+    }
+
+    /// Use `.env_clear().envs(env::vars())` to start from a copy of the current environment.
+    pub fn envs<I: IntoIterator<Item = (&'a str, &'a str)>>(&mut self, vars: I) -> &mut Self {
+        for (key, val) in vars {
+            self.set_env(key, Some(val));
+        }
+        self
+    }
+
+    pub fn env_remove(&mut self, key: &'a str) -> &mut Self {
+        self.set_env(key, None)
+    }
+
+    /// Don't inherit the environment of the parent, only pass the variables set by env()
+    pub fn env_clear(&mut self) -> &mut Self {
+        self.env_clear = true;
+        self.envc = 0;
+        self
+    }
+
+    pub fn current_dir(&mut self, dir: &'a str) -> &mut Self {
+        self.cwd = Some(dir);
+        self
+    }
+
+    pub fn stdin(&mut self, cfg: Stdio) -> &mut Self {
+        self.stdin = cfg;
+        self
+    }
+
+    pub fn stdout(&mut self, cfg: Stdio) -> &mut Self {
+        self.stdout = cfg;
+        self
+    }
+
+    pub fn stderr(&mut self, cfg: Stdio) -> &mut Self {
+        self.stderr = cfg;
+        self
+    }
+
+    /// Run `f` in the child right before the program is executed, an error is returned by
+    /// spawn() like a failed execve.
+    ///
+    /// # Safety
+    /// The child is a copy of the parent made by fork, only the calling thread exists in it.
+    /// `f` must not take locks or allocate, another thread may have held them at the time.
+    pub unsafe fn pre_exec(&mut self, f: &'a dyn Fn() -> Result<()>) -> &mut Self {
+        self.pre_exec = Some(f);
+        self
+    }
+
+    fn set_env(&mut self, key: &'a str, val: Option<&'a str>) -> &mut Self {
+        if let Some(idx) = self.envs[..self.envc].iter().position(|(k, _)| *k == key) {
+            self.envs[idx].1 = val;
+        } else if self.envc < MAX_ENVS {
+            self.envs[self.envc] = (key, val);
+            self.envc += 1;
+        } else {
+            self.overflow = true;
+        }
+        self
+    }
+
+    /// Fork and execute the program. The failure of execve is reported back to the parent
+    /// through a close-on-exec pipe, so a missing program is an error here and not a child
+    /// exiting with 127.
+    pub fn spawn(&mut self) -> Result<Child> {
+        if self.overflow {
+            return Err(Error::E2BIG);
+        }
+
+        let mut block = Block::new(crate::limits::ARG_MAX)?;
+
+        let mut argv = [core::ptr::null::<i8>(); MAX_ARGS + 2];
+        argv[0] = block.push(&[self.program.as_bytes()])?;
+        for idx in 0..self.argc {
+            argv[idx + 1] = block.push(&[self.args[idx].as_bytes()])?;
+        }
+
+        let mut envp = [core::ptr::null::<i8>(); MAX_ENVP + 1];
+        let mut envc = 0;
+        let overrides = &self.envs[..self.envc];
+        if !self.env_clear {
+            for var in env::envp() {
+                let key = var.split_once('=').map_or(var, |(k, _)| k);
+                if overrides.iter().any(|(k, _)| *k == key) {
+                    continue;
+                }
+                if envc == MAX_ENVP {
+                    return Err(Error::E2BIG);
+                }
+                // The strings of the environment are already nul terminated on the stack
+                envp[envc] = var.as_ptr() as *const i8;
+                envc += 1;
+            }
+        }
+        for (key, val) in overrides.iter() {
+            if let Some(val) = val {
+                if envc == MAX_ENVP {
+                    return Err(Error::E2BIG);
+                }
+                envp[envc] = block.push(&[key.as_bytes(), b"=", val.as_bytes()])?;
+                envc += 1;
+            }
+        }
+
+        let mut stdin = Redirect::new(self.stdin, true)?;
+        let mut stdout = Redirect::new(self.stdout, false)?;
+        let mut stderr = Redirect::new(self.stderr, false)?;
+        let (err_rx, err_tx) = pipe2(O_CLOEXEC)?;
+
+        let pid = fork()?;
+
+        if pid == 0 {
+            let error = self.exec([&stdin, &stdout, &stderr], &argv[..self.argc + 2], &envp[..envc + 1]);
+            let code = isize::from(error) as i32;
+            let _ = write(err_tx.as_raw_fd(), &code.to_ne_bytes());
+            exit(127);
+        }
+
+        drop(err_tx);
+        let mut code = [0u8; 4];
+        let n = loop {
+            match read(err_rx.as_raw_fd(), &mut code) {
+                Err(Error::EINTR) => { /* retry */ }
+                other => break other,
+            }
+        };
+
+        if let Ok(4) = n {
+            let mut status = 0;
+            let _ = wait4(pid, &mut status, 0, None);
+            return Err(Error::from(i32::from_ne_bytes(code) as isize));
+        }
+
+        Ok(Child {
+            pid,
+            status: None,
+            stdin: stdin.parent.take().map(File::from),
+            stdout: stdout.parent.take().map(File::from),
+            stderr: stderr.parent.take().map(File::from),
+        })
+    }
+
+    /// Spawn the program and wait for it to finish
+    pub fn status(&mut self) -> Result<ExitStatus> {
+        self.spawn()?.wait()
+    }
+
+    /// Runs in the forked child and returns only if the program couldn't be executed
+    fn exec(&self, redirects: [&Redirect; 3], argv: &[*const i8], envp: &[*const i8]) -> Error {
+        for (fd, r) in redirects.iter().enumerate() {
+            if let Some(child) = r.child {
+                if let Err(e) = dup2(child, fd as u32) {
+                    return e;
+                }
+            }
+        }
+
+        if let Some(dir) = self.cwd {
+            if let Err(e) = chdir(dir) {
+                return e;
+            }
+        }
+
+        if let Some(f) = self.pre_exec {
+            if let Err(e) = f() {
+                return e;
+            }
+        }
+
+        if self.program.contains('/') {
+            return unsafe { execve(self.program, argv, envp) };
+        }
+
+        let mut error = Error::ENOENT;
+        let mut buf = [0u8; crate::limits::PATH_MAX];
+        for dir in env::var("PATH").unwrap_or("/usr/local/bin:/usr/bin:/bin").split(':') {
+            let dir = if dir.is_empty() { "." } else { dir };
+            let len = dir.len() + 1 + self.program.len();
+            if len > buf.len() {
+                continue;
+            }
+
+            buf[..dir.len()].copy_from_slice(dir.as_bytes());
+            buf[dir.len()] = b'/';
+            buf[dir.len() + 1..len].copy_from_slice(self.program.as_bytes());
+            let path = unsafe { core::str::from_utf8_unchecked(&buf[..len]) };
+
+            match unsafe { execve(path, argv, envp) } {
+                Error::ENOENT | Error::ENOTDIR => { /* try the next one */ }
+                Error::EACCES => { error = Error::EACCES; }
+                other => return other,
+            }
+        }
+
+        error
+    }
+}
+
+/// Storage of the nul terminated argument and environment strings. It is mapped instead of
+/// living on the stack, only the pages which are written to are backed by memory.
+struct Block {
+    buf: &'static mut [u8],
+    len: usize,
+}
+
+impl Block {
+    fn new(size: usize) -> Result<Self> {
+        let prot = PROT_READ | PROT_WRITE;
+        let flags = MAP_PRIVATE | MAP_ANONYMOUS;
+        let p = unsafe { mmap(core::ptr::null_mut(), size, prot, flags, u32::MAX, 0)? };
+        Ok(Self { buf: unsafe { core::slice::from_raw_parts_mut(p, size) }, len: 0 })
+    }
+
+    fn push(&mut self, parts: &[&[u8]]) -> Result<*const i8> {
+        let start = self.len;
+        let size = parts.iter().map(|p| p.len()).sum::<usize>() + 1;
+        if start + size > self.buf.len() {
+            return Err(Error::E2BIG);
+        }
+
+        for part in parts {
+            self.buf[self.len..self.len + part.len()].copy_from_slice(part);
+            self.len += part.len();
+        }
+        self.buf[self.len] = 0;
+        self.len += 1;
+
+        Ok(self.buf[start..].as_ptr() as *const i8)
+    }
+}
+
+impl Drop for Block {
+    fn drop(&mut self) {
+        let _ = unsafe { munmap(self.buf.as_mut_ptr(), self.buf.len()) };
+    }
+}
+
+/// The file descriptors of one standard stream. The ends which are not handed over to
+/// the Child are closed when the spawning is done (or has failed).
+struct Redirect {
+    parent: Option<OwnedFd>,
+    child: Option<u32>,
+
+    /// Keeps the child end open until the child has been forked, unless it belongs to the caller
+    _owned: Option<OwnedFd>,
+}
+
+impl Redirect {
+    fn new(cfg: Stdio, readable: bool) -> Result<Self> {
+        match cfg {
+            Stdio::Inherit => Ok(Self { parent: None, child: None, _owned: None }),
+            Stdio::Fd(fd) => Ok(Self { parent: None, child: Some(fd), _owned: None }),
+            Stdio::Null => {
+                let flags = if readable { O_RDONLY } else { O_WRONLY };
+                let fd = open("/dev/null", flags | O_CLOEXEC, 0)?;
+                Ok(Self { parent: None, child: Some(fd.as_raw_fd()), _owned: Some(fd) })
+            }
+            Stdio::Piped => {
+                let (rx, tx) = pipe2(O_CLOEXEC)?;
+                let (parent, child) = if readable { (tx, rx) } else { (rx, tx) };
+                Ok(Self { parent: Some(parent), child: Some(child.as_raw_fd()), _owned: Some(child) })
+            }
+        }
+    }
+}
+
+// ==============================================================================
+// Child
+// ==============================================================================
+pub struct Child {
+    pid: pid_t,
+    status: Option<ExitStatus>,
+    pub stdin: Option<File>,
+    pub stdout: Option<File>,
+    pub stderr: Option<File>,
+}
+
+impl Child {
+    pub fn id(&self) -> pid_t {
+        self.pid
+    }
+
+    /// Send SIGKILL to the child unless it has already been waited for
+    pub fn kill(&mut self) -> Result<()> {
+        match self.status {
+            Some(_) => Err(Error::ESRCH),
+            None => kill(self.pid, crate::signal::SIGKILL),
+        }
+    }
+
+    /// Block until the child has exited. The stdin pipe is closed before waiting,
+    /// otherwise a child reading its input until EOF would never finish.
+    pub fn wait(&mut self) -> Result<ExitStatus> {
+        drop(self.stdin.take());
+
+        if let Some(status) = self.status {
+            return Ok(status);
+        }
+
+        let mut status = 0;
+        loop {
+            match wait4(self.pid, &mut status, 0, None) {
+                Ok(_) => break,
+                Err(Error::EINTR) => { /* retry */ }
+                Err(e) => return Err(e),
+            }
+        }
+
+        self.status = Some(ExitStatus(status));
+        Ok(ExitStatus(status))
+    }
+
+    /// Collect the exit status if the child has already exited, without blocking
+    pub fn try_wait(&mut self) -> Result<Option<ExitStatus>> {
+        if let Some(status) = self.status {
+            return Ok(Some(status));
+        }
+
+        let mut status = 0;
+        match wait4(self.pid, &mut status, WNOHANG, None)? {
+            0 => Ok(None),
+            _ => {
+                self.status = Some(ExitStatus(status));
+                Ok(Some(ExitStatus(status)))
+            }
+        }
+    }
+}
+
+// ==============================================================================
+// Exit status
+// ==============================================================================
+/// The raw status reported by wait4
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExitStatus(pub i32);
+
+impl ExitStatus {
+    /// The exit code if the child has terminated normally
+    pub fn code(&self) -> Option<i32> {
+        match self.0 & 0x7f {
+            0 => Some((self.0 >> 8) & 0xff),
+            _ => None,
+        }
+    }
+
+    /// The signal which has terminated the child
+    pub fn signal(&self) -> Option<i32> {
+        match self.0 & 0x7f {
+            0 | 0x7f => None,
+            sig => Some(sig),
+        }
+    }
+
+    /// The signal which has stopped the child, with WUNTRACED or for a tracer
+    pub fn stopped_signal(&self) -> Option<i32> {
+        match self.0 & 0xff {
+            0x7f => Some((self.0 >> 8) & 0xff),
+            _ => None,
+        }
+    }
+
+    pub fn core_dumped(&self) -> bool {
+        self.signal().is_some() && self.0 & 0x80 != 0
+    }
+
+    pub fn success(&self) -> bool {
+        self.code() == Some(0)
+    }
+}
+
+impl fmt::Display for ExitStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.code(), self.signal()) {
+            (Some(code), _) => write!(f, "exit status: {}", code),
+            (_, Some(sig)) if self.core_dumped() => {
+                write!(f, "signal: {} ({}) (core dumped)", sig, crate::signal::name(sig))
+            }
+            (_, Some(sig)) => write!(f, "signal: {} ({})", sig, crate::signal::name(sig)),
+            _ => write!(f, "unrecognised wait status: {:#x}", self.0),
+        }
+    }
+}
@@ -0,0 +1,190 @@
+//! Tracing processes with ptrace.
+//!
+//! A tracee stops on signals, on the events enabled by the options and with [syscall] at the
+//! entry and the exit of every system call. The tracer waits for the stops with [wait], looks
+//! at the registers and the memory of the tracee and resumes it.
+//!
+//! ```ignore
+//! let child = Command::new("ls").pre_exec(&|| ptrace::traceme()).spawn()?;
+//! let (pid, _) = ptrace::wait(child.id())?;           // stopped by SIGTRAP after execve
+//! ptrace::set_options(pid, PTRACE_O_TRACESYSGOOD | PTRACE_O_EXITKILL)?;
+//! ptrace::syscall(pid, 0)?;
+//! while let (pid, Stop::Syscall) = ptrace::wait(pid)? {
+//!     let regs = ptrace::get_regs(pid)?;
+//!     ptrace::syscall(pid, 0)?;
+//! }
+//! ```
+use crate::types::*;
+use crate::syscall::*;
+use crate::process::ExitStatus;
+use crate::error::{Error, Result};
+
+/// Why [wait] returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    /// The entry or the exit of a system call, which requires PTRACE_O_TRACESYSGOOD
+    Syscall,
+
+    /// A signal is about to be delivered, it is suppressed unless it is passed on resuming
+    Signal(i32),
+
+    /// A PTRACE_EVENT_*, PTRACE_EVENT_STOP for group-stops and interrupts of seized tracees
+    Event(i32),
+
+    /// The tracee has terminated with the exit code
+    Exited(i32),
+
+    /// The tracee has been killed by the signal
+    Killed(i32),
+}
+
+impl Stop {
+    pub fn from_status(status: ExitStatus) -> Self {
+        if let Some(code) = status.code() {
+            return Stop::Exited(code);
+        }
+        if let Some(sig) = status.signal() {
+            return Stop::Killed(sig);
+        }
+
+        let sig = status.stopped_signal().unwrap_or(0);
+        match status.0 >> 16 {
+            0 if sig == SIGTRAP | 0x80 => Stop::Syscall,
+            0 => Stop::Signal(sig),
+            event => Stop::Event(event),
+        }
+    }
+
+    /// Whether the tracee is gone
+    pub fn is_exit(&self) -> bool {
+        matches!(self, Stop::Exited(_) | Stop::Killed(_))
+    }
+}
+
+/// Let the parent trace the calling process, which stops at the next execve
+pub fn traceme() -> Result<()> {
+    unsafe { ptrace(PTRACE_TRACEME, 0, 0, 0)? };
+    Ok(())
+}
+
+/// Attach to `pid` without stopping it, see [interrupt]
+pub fn seize(pid: pid_t, options: u64) -> Result<()> {
+    unsafe { ptrace(PTRACE_SEIZE, pid, 0, options as usize)? };
+    Ok(())
+}
+
+/// Stop a seized tracee, it reports PTRACE_EVENT_STOP
+pub fn interrupt(pid: pid_t) -> Result<()> {
+    unsafe { ptrace(PTRACE_INTERRUPT, pid, 0, 0)? };
+    Ok(())
+}
+
+/// Set the PTRACE_O_* options
+pub fn set_options(pid: pid_t, options: u64) -> Result<()> {
+    unsafe { ptrace(PTRACE_SETOPTIONS, pid, 0, options as usize)? };
+    Ok(())
+}
+
+/// Resume a stopped tracee until the next entry or exit of a system call and deliver `sig`
+/// unless it is 0
+pub fn syscall(pid: pid_t, sig: i32) -> Result<()> {
+    unsafe { ptrace(PTRACE_SYSCALL, pid, 0, sig as usize)? };
+    Ok(())
+}
+
+/// Resume a stopped tracee and deliver `sig` unless it is 0
+pub fn cont(pid: pid_t, sig: i32) -> Result<()> {
+    unsafe { ptrace(PTRACE_CONT, pid, 0, sig as usize)? };
+    Ok(())
+}
+
+/// Kill a stopped tracee
+pub fn kill(pid: pid_t) -> Result<()> {
+    unsafe { ptrace(PTRACE_KILL, pid, 0, 0)? };
+    Ok(())
+}
+
+/// Stop tracing `pid` and resume it with `sig`
+pub fn detach(pid: pid_t, sig: i32) -> Result<()> {
+    unsafe { ptrace(PTRACE_DETACH, pid, 0, sig as usize)? };
+    Ok(())
+}
+
+pub fn get_regs(pid: pid_t) -> Result<user_regs_struct> {
+    let mut regs = user_regs_struct::default();
+    unsafe { ptrace(PTRACE_GETREGS, pid, 0, &mut regs as *mut _ as usize)? };
+    Ok(regs)
+}
+
+pub fn set_regs(pid: pid_t, regs: &user_regs_struct) -> Result<()> {
+    unsafe { ptrace(PTRACE_SETREGS, pid, 0, regs as *const _ as usize)? };
+    Ok(())
+}
+
+/// The word at `addr` in the tracee
+pub fn peek_data(pid: pid_t, addr: usize) -> Result<usize> {
+    let mut word = 0usize;
+    unsafe { ptrace(PTRACE_PEEKDATA, pid, addr, &mut word as *mut usize as usize)? };
+    Ok(word)
+}
+
+/// The message of the last event, the pid of the new child for PTRACE_EVENT_FORK
+pub fn event_msg(pid: pid_t) -> Result<u64> {
+    let mut msg = 0u64;
+    unsafe { ptrace(PTRACE_GETEVENTMSG, pid, 0, &mut msg as *mut u64 as usize)? };
+    Ok(msg)
+}
+
+/// Resume a stopped tracee for a single instruction, it stops with SIGTRAP after it
+pub fn single_step(pid: pid_t, sig: i32) -> Result<()> {
+    unsafe { ptrace(PTRACE_SINGLESTEP, pid, 0, sig as usize)? };
+    Ok(())
+}
+
+/// Write the word at `addr` in the tracee, also into read-only mappings like the code
+pub fn poke_data(pid: pid_t, addr: usize, word: usize) -> Result<()> {
+    unsafe { ptrace(PTRACE_POKEDATA, pid, addr, word)? };
+    Ok(())
+}
+
+/// Wait for the next stop of the tracee `pid`, of any tracee for -1
+pub fn wait(pid: pid_t) -> Result<(pid_t, Stop)> {
+    let mut status = 0;
+    let pid = loop {
+        match wait4(pid, &mut status, __WALL, None) {
+            Err(Error::EINTR) => { /* retry */ }
+            other => break other?,
+        }
+    };
+    Ok((pid, Stop::from_status(ExitStatus(status))))
+}
+
+/// Copy the memory at `addr` in the process `pid` into `buf`. It is read up to the first
+/// page which isn't mapped, the number of bytes read is returned.
+pub fn read_memory(pid: pid_t, addr: usize, buf: &mut [u8]) -> Result<usize> {
+    let local = [iovec { iov_base: buf.as_mut_ptr(), iov_len: buf.len() }];
+    let remote = [iovec { iov_base: addr as *mut u8, iov_len: buf.len() }];
+    unsafe { process_vm_readv(pid, &local, &remote, 0) }
+}
+
+/// Read the null-terminated string at `addr` in the process `pid`. The string is cut when it
+/// doesn't fit into `buf`, the null isn't included.
+pub fn read_str(pid: pid_t, addr: usize, buf: &mut [u8]) -> Result<&[u8]> {
+    const PAGE: usize = 4096;
+
+    // A page at a time, the string may end right before an unmapped page
+    let mut len = 0;
+    while len < buf.len() {
+        let start = addr + len;
+        let end = buf.len().min(len + PAGE - start % PAGE);
+        let n = read_memory(pid, start, &mut buf[len..end])?;
+        if let Some(nul) = buf[len..len + n].iter().position(|&c| c == 0) {
+            return Ok(&buf[..len + nul]);
+        }
+        if n < end - len {
+            return Err(Error::EFAULT);
+        }
+        len = end;
+    }
+    Ok(&buf[..len])
+}
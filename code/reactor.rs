@@ -0,0 +1,331 @@
+//! An epoll based event loop to multiplex sockets, pipes and timers on a single thread.
+//!
+//! File descriptors are registered with an [Interest] and a [Token] chosen by the caller.
+//! [Reactor::poll] fills an [Events] buffer with the tokens of the ready descriptors and of the
+//! expired timers, or [Reactor::run] dispatches them to a [Handler] until [Reactor::stop] is
+//! called. The descriptors should be non-blocking: with edge-triggered interest they have to be
+//! read and written until EAGAIN before the next event is reported.
+//!
+//! ```ignore
+//! struct Server { listener: TcpListener }
+//!
+//! impl Handler for Server {
+//!     fn ready(&mut self, reactor: &mut Reactor, event: Event) {
+//!         if event.token() == LISTENER {
+//!             while let Ok((stream, _)) = self.listener.accept() { /* register the stream */ }
+//!         }
+//!     }
+//! }
+//!
+//! let mut reactor: Reactor = Reactor::new()?;
+//! reactor.register(server.listener.fd(), Interest::READABLE, LISTENER)?;
+//! reactor.run(&mut server)?;
+//! ```
+use core::ops::BitOr;
+use core::time::Duration;
+
+use crate::fd::{AsFd, AsRawFd, BorrowedFd, OwnedFd};
+use crate::types::*;
+use crate::syscall::*;
+use crate::constants::*;
+use crate::error::{Error, Result};
+
+/// Identifies a registered descriptor or a timer in the events
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Token(pub usize);
+
+/// The events a descriptor is registered for, combined with `|`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interest(u32);
+
+impl Interest {
+    pub const READABLE: Self = Self(EPOLLIN | EPOLLRDHUP);
+    pub const WRITABLE: Self = Self(EPOLLOUT);
+
+    /// Report an event only when the state changes instead of as long as it lasts
+    pub const EDGE: Self = Self(EPOLLET);
+
+    /// Disable the descriptor after the first event until it is rearmed with modify()
+    pub const ONESHOT: Self = Self(EPOLLONESHOT);
+
+    pub fn is_readable(&self) -> bool {
+        self.0 & EPOLLIN != 0
+    }
+
+    pub fn is_writable(&self) -> bool {
+        self.0 & EPOLLOUT != 0
+    }
+}
+
+impl BitOr for Interest {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+/// A ready descriptor or an expired timer
+#[derive(Debug, Clone, Copy)]
+pub struct Event {
+    token: Token,
+    events: u32,
+    timer: bool,
+}
+
+impl Event {
+    pub fn token(&self) -> Token {
+        self.token
+    }
+
+    pub fn is_readable(&self) -> bool {
+        self.events & (EPOLLIN | EPOLLPRI) != 0
+    }
+
+    pub fn is_writable(&self) -> bool {
+        self.events & EPOLLOUT != 0
+    }
+
+    /// The peer closed its writing side, reading returns the remaining data and then EOF
+    pub fn is_read_closed(&self) -> bool {
+        self.events & (EPOLLHUP | EPOLLRDHUP) != 0
+    }
+
+    /// Both directions are closed
+    pub fn is_hangup(&self) -> bool {
+        self.events & EPOLLHUP != 0
+    }
+
+    /// The descriptor has a pending error, e.g. a failed non-blocking connect
+    pub fn is_error(&self) -> bool {
+        self.events & EPOLLERR != 0
+    }
+
+    /// The event comes from a timer set with set_timeout()
+    pub fn is_timeout(&self) -> bool {
+        self.timer
+    }
+}
+
+/// Buffer for the events returned by one call to poll()
+pub struct Events<const N: usize = 64> {
+    raw: [epoll_event; N],
+    timers: [Token; N],
+    fds: usize,
+    expired: usize,
+}
+
+impl<const N: usize> Events<N> {
+    pub fn new() -> Self {
+        Self {
+            raw: [epoll_event::default(); N],
+            timers: [Token(0); N],
+            fds: 0,
+            expired: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.fds + self.expired
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, idx: usize) -> Option<Event> {
+        if idx < self.fds {
+            let raw = self.raw[idx];
+            return Some(Event { token: Token(raw.data as usize), events: raw.events, timer: false });
+        }
+        match idx - self.fds < self.expired {
+            true => Some(Event { token: self.timers[idx - self.fds], events: 0, timer: true }),
+            false => None,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Event> + '_ {
+        (0..self.len()).filter_map(move |idx| self.get(idx))
+    }
+}
+
+impl<const N: usize> Default for Events<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Identifies a pending timer to cancel it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimerId {
+    slot: usize,
+    generation: u64,
+}
+
+#[derive(Clone, Copy)]
+struct Timer {
+    deadline: u64,
+    token: Token,
+    generation: u64,
+}
+
+/// Callbacks of the event loop, they get the reactor to change the registrations and timers
+pub trait Handler<const T: usize = 64> {
+    fn ready(&mut self, reactor: &mut Reactor<T>, event: Event);
+
+    fn timeout(&mut self, reactor: &mut Reactor<T>, token: Token) {
+        let _ = (reactor, token);
+    }
+}
+
+/// The epoll instance and up to T pending timers
+pub struct Reactor<const T: usize = 64> {
+    epfd: OwnedFd,
+    timers: [Option<Timer>; T],
+    generation: u64,
+    stopped: bool,
+}
+
+/// Nanoseconds on the monotonic clock
+fn now() -> u64 {
+    let mut ts = timespec::default();
+    let _ = clock_gettime(CLOCK_MONOTONIC, &mut ts);
+    ts.tv_sec as u64 * 1_000_000_000 + ts.tv_nsec as u64
+}
+
+impl<const T: usize> Reactor<T> {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            epfd: epoll_create1(EPOLL_CLOEXEC)?,
+            timers: [None; T],
+            generation: 0,
+            stopped: false,
+        })
+    }
+
+    /// The epoll descriptor, it can itself be registered with another reactor
+    pub fn fd(&self) -> u32 {
+        self.epfd.as_raw_fd()
+    }
+
+    pub fn register(&self, fd: u32, interest: Interest, token: Token) -> Result<()> {
+        let event = epoll_event { events: interest.0, data: token.0 as u64 };
+        epoll_ctl(self.fd(), EPOLL_CTL_ADD, fd, Some(&event))
+    }
+
+    /// Change the interest or the token of a registered descriptor, this also rearms a
+    /// descriptor registered with ONESHOT
+    pub fn modify(&self, fd: u32, interest: Interest, token: Token) -> Result<()> {
+        let event = epoll_event { events: interest.0, data: token.0 as u64 };
+        epoll_ctl(self.fd(), EPOLL_CTL_MOD, fd, Some(&event))
+    }
+
+    /// Closing a descriptor deregisters it only when no duplicate of it is left open,
+    /// so deregister before closing.
+    pub fn deregister(&self, fd: u32) -> Result<()> {
+        epoll_ctl(self.fd(), EPOLL_CTL_DEL, fd, None)
+    }
+
+    /// Report the token once after `after` has elapsed. Fails with ENOSPC when all
+    /// T timers are pending.
+    pub fn set_timeout(&mut self, after: Duration, token: Token) -> Result<TimerId> {
+        let slot = self.timers.iter().position(|t| t.is_none()).ok_or(Error::ENOSPC)?;
+        let deadline = now().saturating_add(after.as_nanos().min(u64::MAX as u128) as u64);
+
+        self.generation += 1;
+        self.timers[slot] = Some(Timer { deadline, token, generation: self.generation });
+        Ok(TimerId { slot, generation: self.generation })
+    }
+
+    /// Returns false when the timer has already expired or was cancelled
+    pub fn cancel_timeout(&mut self, id: TimerId) -> bool {
+        match self.timers[id.slot] {
+            Some(timer) if timer.generation == id.generation => {
+                self.timers[id.slot] = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Make run() return after the current events have been dispatched
+    pub fn stop(&mut self) {
+        self.stopped = true;
+    }
+
+    /// Wait until a descriptor is ready, a timer expires or `timeout` has elapsed (None
+    /// waits forever). Interrupted waits return with no events.
+    pub fn poll<const N: usize>(&mut self, events: &mut Events<N>, timeout: Option<Duration>) -> Result<()> {
+        events.fds = 0;
+        events.expired = 0;
+
+        let mut wait = timeout.map(|t| t.as_nanos().min(u64::MAX as u128) as u64);
+        if let Some(deadline) = self.timers.iter().flatten().map(|t| t.deadline).min() {
+            let left = deadline.saturating_sub(now());
+            wait = Some(wait.map_or(left, |w| w.min(left)));
+        }
+
+        events.fds = match self.wait(&mut events.raw, wait) {
+            Ok(n) => n,
+            Err(Error::EINTR) => 0,
+            Err(err) => return Err(err),
+        };
+
+        // Expired timers which don't fit into the buffer are reported by the next call
+        let now = now();
+        for slot in self.timers.iter_mut() {
+            if events.expired == N {
+                break;
+            }
+            if let Some(timer) = slot {
+                if timer.deadline <= now {
+                    events.timers[events.expired] = timer.token;
+                    events.expired += 1;
+                    *slot = None;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// epoll_pwait2 for the nanosecond timeout with a fallback to epoll_wait before Linux 5.11
+    fn wait(&self, raw: &mut [epoll_event], timeout: Option<u64>) -> Result<usize> {
+        let ts = timeout.map(|ns| timespec {
+            tv_sec: (ns / 1_000_000_000) as i64,
+            tv_nsec: (ns % 1_000_000_000) as i64,
+        });
+        match epoll_pwait2(self.fd(), raw, ts.as_ref(), None) {
+            Err(Error::ENOSYS) => {
+                let ms = match timeout {
+                    Some(ns) => ns.div_ceil(1_000_000).min(i32::MAX as u64) as i32,
+                    None => -1,
+                };
+                epoll_wait(self.fd(), raw, ms)
+            }
+            other => other,
+        }
+    }
+
+    /// Dispatch the events to the handler until stop() is called
+    pub fn run<H: Handler<T>>(&mut self, handler: &mut H) -> Result<()> {
+        let mut events = Events::<64>::new();
+        self.stopped = false;
+
+        while !self.stopped {
+            self.poll(&mut events, None)?;
+            for event in events.iter() {
+                match event.is_timeout() {
+                    true => handler.timeout(self, event.token()),
+                    false => handler.ready(self, event),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<const T: usize> AsFd for Reactor<T> {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.epfd.as_fd()
+    }
+}
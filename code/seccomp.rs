@@ -0,0 +1,397 @@
+//! System call filtering with seccomp.
+//!
+//! A [Filter] maps system calls, optionally restricted by their arguments, to an [Action] and
+//! takes a default action for all other calls. It is compiled into a classic BPF program which
+//! the kernel runs on every system call of the thread once it is loaded. A filter can't be
+//! removed again, it is inherited by the children and kept across execve.
+//!
+//! The program first checks the architecture, a 32 bit or x32 system call has different
+//! numbers and would get past the rules, so it kills the process.
+//!
+//! ```ignore
+//! let mut filter = Filter::<16>::new(Action::Allow);
+//! filter
+//!     .errno(SYS_MKDIR, Error::EACCES)?
+//!     .rule(SYS_SOCKET, Action::Errno(Error::EAFNOSUPPORT), &[ArgCmp::new(0, Cmp::Ne, AF_UNIX as u64)])?
+//!     .deny(SYS_PTRACE)?;
+//! filter.load()?;
+//! ```
+//!
+//! With [Action::Notify] the calls wait for a supervisor instead, which reads them from the
+//! [Notifier] returned by [Filter::load_with_listener] and decides the result.
+use crate::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd};
+use crate::types::*;
+use crate::syscall::*;
+use crate::error::{Error, Result};
+
+/// What happens to a system call
+#[derive(Debug)]
+pub enum Action {
+    Allow,
+
+    /// Kill all threads of the process
+    KillProcess,
+
+    /// Kill the calling thread
+    KillThread,
+
+    /// Send SIGSYS to the thread, the call isn't executed
+    Trap,
+
+    /// Fail with the error without executing the call
+    Errno(Error),
+
+    /// Stop in a ptrace tracer with PTRACE_EVENT_SECCOMP and the value as event message,
+    /// fails with ENOSYS when the process isn't traced
+    Trace(u16),
+
+    /// Allow the call and log it in the audit log
+    Log,
+
+    /// Wait until the supervisor answers through the [Notifier]
+    Notify,
+}
+
+impl Action {
+    /// The return value of the filter program
+    pub fn ret(self) -> u32 {
+        match self {
+            Action::Allow => SECCOMP_RET_ALLOW,
+            Action::KillProcess => SECCOMP_RET_KILL_PROCESS,
+            Action::KillThread => SECCOMP_RET_KILL_THREAD,
+            Action::Trap => SECCOMP_RET_TRAP,
+            Action::Errno(err) => SECCOMP_RET_ERRNO | (isize::from(err) as u32 & SECCOMP_RET_DATA),
+            Action::Trace(data) => SECCOMP_RET_TRACE | data as u32,
+            Action::Log => SECCOMP_RET_LOG,
+            Action::Notify => SECCOMP_RET_USER_NOTIF,
+        }
+    }
+
+    /// Whether the kernel supports the action
+    pub fn is_available(self) -> bool {
+        let action = self.ret() & SECCOMP_RET_ACTION_FULL;
+        unsafe { seccomp(SECCOMP_GET_ACTION_AVAIL, 0, &action as *const u32 as *const u8).is_ok() }
+    }
+}
+
+/// How an argument is compared, as unsigned 64 bit numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cmp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+
+    /// The argument and the mask equal the value
+    MaskedEq(u64),
+}
+
+/// A comparison of the argument with the index `arg` with `value`
+#[derive(Debug, Clone, Copy)]
+pub struct ArgCmp {
+    pub arg: u8,
+    pub op: Cmp,
+    pub value: u64,
+}
+
+impl ArgCmp {
+    pub fn new(arg: u8, op: Cmp, value: u64) -> Self {
+        Self { arg, op, value }
+    }
+
+    /// The number of instructions of the comparison
+    fn len(&self) -> usize {
+        match self.op {
+            Cmp::Eq | Cmp::Ne => 4,
+            Cmp::Lt | Cmp::Le | Cmp::Gt | Cmp::Ge => 5,
+            Cmp::MaskedEq(_) => 6,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Rule {
+    nr: u32,
+    ret: u32,
+    args: [ArgCmp; 6],
+    nargs: usize,
+}
+
+impl Rule {
+    /// The number of instructions of the rule, loading and checking the number, comparing
+    /// the arguments and returning the action
+    fn len(&self) -> usize {
+        3 + self.args[..self.nargs].iter().map(ArgCmp::len).sum::<usize>()
+    }
+}
+
+/// A seccomp filter of up to N rules, checked in the order they were added
+pub struct Filter<const N: usize = 64> {
+    default: u32,
+    flags: u32,
+    rules: [Rule; N],
+    len: usize,
+}
+
+impl<const N: usize> Filter<N> {
+    /// A filter which takes `default` for the calls no rule matches
+    pub fn new(default: Action) -> Self {
+        let rule = Rule {
+            nr: 0,
+            ret: 0,
+            args: [ArgCmp::new(0, Cmp::Eq, 0); 6],
+            nargs: 0,
+        };
+        Self { default: default.ret(), flags: 0, rules: [rule; N], len: 0 }
+    }
+
+    /// Add SECCOMP_FILTER_FLAG_* flags for loading the filter, like TSYNC to apply it to all
+    /// threads of the process
+    pub fn flags(&mut self, flags: u32) -> &mut Self {
+        self.flags |= flags;
+        self
+    }
+
+    /// Take `action` for the system call `nr` when all comparisons of its arguments are true
+    pub fn rule(&mut self, nr: isize, action: Action, args: &[ArgCmp]) -> Result<&mut Self> {
+        if self.len == N {
+            return Err(Error::ENOSPC);
+        }
+        if nr < 0 || args.len() > 6 || args.iter().any(|cmp| cmp.arg > 5) {
+            return Err(Error::EINVAL);
+        }
+
+        let rule = &mut self.rules[self.len];
+        rule.nr = nr as u32;
+        rule.ret = action.ret();
+        rule.args[..args.len()].copy_from_slice(args);
+        rule.nargs = args.len();
+        self.len += 1;
+        Ok(self)
+    }
+
+    pub fn allow(&mut self, nr: isize) -> Result<&mut Self> {
+        self.rule(nr, Action::Allow, &[])
+    }
+
+    /// Kill the process
+    pub fn deny(&mut self, nr: isize) -> Result<&mut Self> {
+        self.rule(nr, Action::KillProcess, &[])
+    }
+
+    pub fn errno(&mut self, nr: isize, err: Error) -> Result<&mut Self> {
+        self.rule(nr, Action::Errno(err), &[])
+    }
+
+    pub fn trap(&mut self, nr: isize) -> Result<&mut Self> {
+        self.rule(nr, Action::Trap, &[])
+    }
+
+    pub fn log(&mut self, nr: isize) -> Result<&mut Self> {
+        self.rule(nr, Action::Log, &[])
+    }
+
+    pub fn notify(&mut self, nr: isize) -> Result<&mut Self> {
+        self.rule(nr, Action::Notify, &[])
+    }
+
+    /// The number of instructions of the program
+    pub fn program_len(&self) -> usize {
+        7 + self.rules[..self.len].iter().map(Rule::len).sum::<usize>()
+    }
+
+    /// Write the BPF program into `prog` and return the number of instructions, fails with
+    /// E2BIG when it doesn't fit
+    pub fn compile(&self, prog: &mut [sock_filter]) -> Result<usize> {
+        let len = self.program_len();
+        if len > prog.len() || len > BPF_MAXINSNS {
+            return Err(Error::E2BIG);
+        }
+
+        let mut asm = Asm { prog, pc: 0 };
+
+        // Kill anything which isn't a x86-64 system call
+        asm.stmt(BPF_LD | BPF_W | BPF_ABS, ARCH);
+        asm.jump(BPF_JMP | BPF_JEQ | BPF_K, AUDIT_ARCH_X86_64, 1, 0);
+        asm.stmt(BPF_RET | BPF_K, SECCOMP_RET_KILL_PROCESS);
+        asm.stmt(BPF_LD | BPF_W | BPF_ABS, NR);
+        asm.jump(BPF_JMP | BPF_JGE | BPF_K, X32_SYSCALL_BIT, 0, 1);
+        asm.stmt(BPF_RET | BPF_K, SECCOMP_RET_KILL_PROCESS);
+
+        for rule in &self.rules[..self.len] {
+            // Every failed check jumps to the next rule
+            let end = asm.pc + rule.len();
+            asm.stmt(BPF_LD | BPF_W | BPF_ABS, NR);
+            asm.jump(BPF_JMP | BPF_JEQ | BPF_K, rule.nr, 0, (end - asm.pc - 1) as u8);
+            for cmp in &rule.args[..rule.nargs] {
+                asm.cmp(cmp, end);
+            }
+            asm.stmt(BPF_RET | BPF_K, rule.ret);
+        }
+
+        asm.stmt(BPF_RET | BPF_K, self.default);
+        Ok(asm.pc)
+    }
+
+    /// Install the filter on the calling thread. This sets PR_SET_NO_NEW_PRIVS, which an
+    /// unprivileged process needs to load a filter.
+    pub fn load(&self) -> Result<()> {
+        self.install(self.flags)?;
+        Ok(())
+    }
+
+    /// Install the filter and return the listener which receives the calls of [Action::Notify].
+    /// Only one filter of a process may have a listener.
+    pub fn load_with_listener(&self) -> Result<Notifier> {
+        let fd = self.install(self.flags | SECCOMP_FILTER_FLAG_NEW_LISTENER)?;
+        Ok(Notifier { fd: unsafe { OwnedFd::from_raw_fd(fd as u32) } })
+    }
+
+    fn install(&self, flags: u32) -> Result<usize> {
+        let mut prog = [sock_filter::default(); BPF_MAXINSNS];
+        let len = self.compile(&mut prog)?;
+        let fprog = sock_fprog { len: len as u16, filter: prog.as_ptr() };
+
+        unsafe {
+            prctl(PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0)?;
+            seccomp(SECCOMP_SET_MODE_FILTER, flags, &fprog as *const sock_fprog as *const u8)
+        }
+    }
+}
+
+// The offsets of the fields of seccomp_data, the low word of an argument comes first
+const NR: u32 = 0;
+const ARCH: u32 = 4;
+
+fn arg_lo(arg: u8) -> u32 {
+    16 + 8 * arg as u32
+}
+
+fn arg_hi(arg: u8) -> u32 {
+    arg_lo(arg) + 4
+}
+
+/// Writes the instructions of a program
+struct Asm<'a> {
+    prog: &'a mut [sock_filter],
+    pc: usize,
+}
+
+impl<'a> Asm<'a> {
+    fn stmt(&mut self, code: u16, k: u32) {
+        self.jump(code, k, 0, 0);
+    }
+
+    /// A conditional jump skips `jt` instructions if true and `jf` if false
+    fn jump(&mut self, code: u16, k: u32, jt: u8, jf: u8) {
+        self.prog[self.pc] = sock_filter { code, jt, jf, k };
+        self.pc += 1;
+    }
+
+    /// Compare an argument, jumping to `fail` when the comparison is false
+    fn cmp(&mut self, cmp: &ArgCmp, fail: usize) {
+        let (hi, lo) = ((cmp.value >> 32) as u32, cmp.value as u32);
+        let ld = BPF_LD | BPF_W | BPF_ABS;
+        let jmp = BPF_JMP | BPF_K;
+
+        // The distance to `fail` from the jump written next
+        let to = |pc: usize| (fail - pc - 1) as u8;
+
+        match cmp.op {
+            Cmp::Eq => {
+                self.stmt(ld, arg_hi(cmp.arg));
+                self.jump(jmp | BPF_JEQ, hi, 0, to(self.pc));
+                self.stmt(ld, arg_lo(cmp.arg));
+                self.jump(jmp | BPF_JEQ, lo, 0, to(self.pc));
+            }
+            Cmp::Ne => {
+                // True as soon as the high words differ
+                self.stmt(ld, arg_hi(cmp.arg));
+                self.jump(jmp | BPF_JEQ, hi, 0, 2);
+                self.stmt(ld, arg_lo(cmp.arg));
+                self.jump(jmp | BPF_JEQ, lo, to(self.pc), 0);
+            }
+            Cmp::MaskedEq(mask) => {
+                let (mask_hi, mask_lo) = ((mask >> 32) as u32, mask as u32);
+                self.stmt(ld, arg_hi(cmp.arg));
+                self.stmt(BPF_ALU | BPF_AND | BPF_K, mask_hi);
+                self.jump(jmp | BPF_JEQ, hi & mask_hi, 0, to(self.pc));
+                self.stmt(ld, arg_lo(cmp.arg));
+                self.stmt(BPF_ALU | BPF_AND | BPF_K, mask_lo);
+                self.jump(jmp | BPF_JEQ, lo & mask_lo, 0, to(self.pc));
+            }
+            Cmp::Gt | Cmp::Ge => {
+                // Decided by the high words unless they are equal
+                self.stmt(ld, arg_hi(cmp.arg));
+                self.jump(jmp | BPF_JGT, hi, 3, 0);
+                self.jump(jmp | BPF_JEQ, hi, 0, to(self.pc));
+                self.stmt(ld, arg_lo(cmp.arg));
+                let op = if cmp.op == Cmp::Gt { BPF_JGT } else { BPF_JGE };
+                self.jump(jmp | op, lo, 0, to(self.pc));
+            }
+            Cmp::Lt | Cmp::Le => {
+                self.stmt(ld, arg_hi(cmp.arg));
+                self.jump(jmp | BPF_JGT, hi, to(self.pc), 0);
+                self.jump(jmp | BPF_JEQ, hi, 0, 2);
+                self.stmt(ld, arg_lo(cmp.arg));
+                let op = if cmp.op == Cmp::Lt { BPF_JGE } else { BPF_JGT };
+                self.jump(jmp | op, lo, to(self.pc), 0);
+            }
+        }
+    }
+}
+
+/// The listener of a filter, which answers the system calls of [Action::Notify]
+pub struct Notifier {
+    fd: OwnedFd,
+}
+
+impl Notifier {
+    /// A listener received from another process, e.g. over a unix socket
+    pub fn from_fd(fd: OwnedFd) -> Self {
+        Self { fd }
+    }
+
+    /// Wait for the next system call
+    pub fn recv(&self) -> Result<seccomp_notif> {
+        // The kernel requires the struct to be zeroed
+        let mut notif = seccomp_notif::default();
+        unsafe { ioctl(self.fd.as_raw_fd(), SECCOMP_IOCTL_NOTIF_RECV, &mut notif as *mut _ as usize)? };
+        Ok(notif)
+    }
+
+    /// Whether the call `id` is still waiting, the process may have been killed meanwhile. A
+    /// supervisor checks this after reading the memory of the process through its pid.
+    pub fn id_valid(&self, id: u64) -> bool {
+        unsafe { ioctl(self.fd.as_raw_fd(), SECCOMP_IOCTL_NOTIF_ID_VALID, &id as *const u64 as usize).is_ok() }
+    }
+
+    pub fn respond(&self, resp: &seccomp_notif_resp) -> Result<()> {
+        unsafe { ioctl(self.fd.as_raw_fd(), SECCOMP_IOCTL_NOTIF_SEND, resp as *const _ as usize)? };
+        Ok(())
+    }
+
+    /// Return `val` from the call without executing it
+    pub fn reply(&self, id: u64, val: i64) -> Result<()> {
+        self.respond(&seccomp_notif_resp { id, val, error: 0, flags: 0 })
+    }
+
+    /// Fail the call with `err` without executing it
+    pub fn error(&self, id: u64, err: Error) -> Result<()> {
+        self.respond(&seccomp_notif_resp { id, val: 0, error: -(isize::from(err) as i32), flags: 0 })
+    }
+
+    /// Let the kernel execute the call. The arguments may have changed since the supervisor
+    /// looked at them, so this can't be used to allow calls depending on pointed to memory.
+    pub fn allow(&self, id: u64) -> Result<()> {
+        self.respond(&seccomp_notif_resp { id, val: 0, error: 0, flags: SECCOMP_USER_NOTIF_FLAG_CONTINUE })
+    }
+}
+
+impl AsFd for Notifier {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
@@ -0,0 +1,157 @@
+//! Signal handlers and signal masks on top of rt_sigaction and rt_sigprocmask.
+//!
+//! The handlers run asynchronously, interrupting the program at any instruction, so they
+//! should only touch atomics and call async-signal-safe system calls. A typical graceful
+//! shutdown sets a static `AtomicBool` in the handler and checks it in the main loop.
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::types::*;
+use crate::syscall::*;
+use crate::error::{Error, Result};
+
+pub use crate::syscall::sig::{
+    SIGHUP, SIGINT, SIGQUIT, SIGILL, SIGTRAP, SIGABRT, SIGBUS, SIGFPE, SIGKILL, SIGUSR1,
+    SIGSEGV, SIGUSR2, SIGPIPE, SIGALRM, SIGTERM, SIGSTKFLT, SIGCHLD, SIGCONT, SIGSTOP,
+    SIGTSTP, SIGTTIN, SIGTTOU, SIGURG, SIGXCPU, SIGXFSZ, SIGVTALRM, SIGPROF, SIGWINCH,
+    SIGIO, SIGPWR, SIGSYS, SIGRTMIN, SIGRTMAX,
+};
+
+/// Number of signals including the unused 0
+const NSIG: usize = SIGRTMAX as usize + 1;
+
+pub type Handler = fn(sig: i32, info: &siginfo_t);
+
+static HANDLERS: [AtomicUsize; NSIG] = [const { AtomicUsize::new(0) }; NSIG];
+
+/// Every registered signal is delivered here and forwarded to the handler of the signal
+extern "C" fn dispatch(sig: i32, info: *const siginfo_t, _ucontext: *mut u8) {
+    let handler = HANDLERS[sig as usize].load(Ordering::Acquire);
+    if handler != 0 {
+        let handler: Handler = unsafe { core::mem::transmute(handler) };
+        handler(sig, unsafe { &*info });
+    }
+}
+
+fn check(sig: i32) -> Result<()> {
+    match sig {
+        SIGKILL | SIGSTOP => Err(Error::EINVAL),
+        1..=SIGRTMAX => Ok(()),
+        _ => Err(Error::EINVAL),
+    }
+}
+
+/// Install a handler for the signal. Interrupted system calls are restarted (SA_RESTART)
+/// and the signal is blocked while its own handler is running.
+pub fn register(sig: i32, handler: Handler) -> Result<()> {
+    check(sig)?;
+    let act = sigaction {
+        sa_handler: dispatch as *const () as usize,
+        sa_flags: SA_SIGINFO | SA_RESTART,
+        sa_restorer: 0,
+        sa_mask: sigset_t::empty(),
+    };
+    // Stored first, a signal arriving right after rt_sigaction must find its handler
+    let prev = HANDLERS[sig as usize].swap(handler as usize, Ordering::AcqRel);
+    rt_sigaction(sig, Some(&act), None).map_err(|err| {
+        HANDLERS[sig as usize].store(prev, Ordering::Release);
+        err
+    })?;
+    Ok(())
+}
+
+/// Discard the signal
+pub fn ignore(sig: i32) -> Result<()> {
+    disposition(sig, SIG_IGN)
+}
+
+/// Restore the default action of the signal
+pub fn reset(sig: i32) -> Result<()> {
+    disposition(sig, SIG_DFL)
+}
+
+fn disposition(sig: i32, handler: usize) -> Result<()> {
+    check(sig)?;
+    let act = sigaction { sa_handler: handler, ..Default::default() };
+    rt_sigaction(sig, Some(&act), None)?;
+    HANDLERS[sig as usize].store(0, Ordering::Release);
+    Ok(())
+}
+
+/// Change the signal mask of the calling thread and return the previous one
+pub fn sigprocmask(how: i32, set: &sigset_t) -> Result<sigset_t> {
+    let mut old = sigset_t::empty();
+    rt_sigprocmask(how, Some(set), Some(&mut old))?;
+    Ok(old)
+}
+
+pub fn block(set: &sigset_t) -> Result<sigset_t> {
+    sigprocmask(SIG_BLOCK, set)
+}
+
+pub fn unblock(set: &sigset_t) -> Result<sigset_t> {
+    sigprocmask(SIG_UNBLOCK, set)
+}
+
+/// The current signal mask of the calling thread
+pub fn mask() -> Result<sigset_t> {
+    let mut old = sigset_t::empty();
+    rt_sigprocmask(SIG_BLOCK, None, Some(&mut old))?;
+    Ok(old)
+}
+
+/// The blocked signals which are waiting for delivery
+pub fn sigpending() -> Result<sigset_t> {
+    let mut set = sigset_t::empty();
+    rt_sigpending(&mut set)?;
+    Ok(set)
+}
+
+/// Replace the signal mask with `mask` and sleep until a handler has been executed.
+/// Blocking a signal, checking a flag and then calling this function is the race-free way
+/// to wait for a signal.
+pub fn sigsuspend(mask: &sigset_t) {
+    rt_sigsuspend(mask);
+}
+
+/// Send a signal to the calling process
+pub fn raise(sig: i32) -> Result<()> {
+    kill(getpid(), sig)
+}
+
+pub fn name(sig: i32) -> &'static str {
+    match sig {
+        SIGHUP    => "SIGHUP",
+        SIGINT    => "SIGINT",
+        SIGQUIT   => "SIGQUIT",
+        SIGILL    => "SIGILL",
+        SIGTRAP   => "SIGTRAP",
+        SIGABRT   => "SIGABRT",
+        SIGBUS    => "SIGBUS",
+        SIGFPE    => "SIGFPE",
+        SIGKILL   => "SIGKILL",
+        SIGUSR1   => "SIGUSR1",
+        SIGSEGV   => "SIGSEGV",
+        SIGUSR2   => "SIGUSR2",
+        SIGPIPE   => "SIGPIPE",
+        SIGALRM   => "SIGALRM",
+        SIGTERM   => "SIGTERM",
+        SIGSTKFLT => "SIGSTKFLT",
+        SIGCHLD   => "SIGCHLD",
+        SIGCONT   => "SIGCONT",
+        SIGSTOP   => "SIGSTOP",
+        SIGTSTP   => "SIGTSTP",
+        SIGTTIN   => "SIGTTIN",
+        SIGTTOU   => "SIGTTOU",
+        SIGURG    => "SIGURG",
+        SIGXCPU   => "SIGXCPU",
+        SIGXFSZ   => "SIGXFSZ",
+        SIGVTALRM => "SIGVTALRM",
+        SIGPROF   => "SIGPROF",
+        SIGWINCH  => "SIGWINCH",
+        SIGIO     => "SIGIO",
+        SIGPWR    => "SIGPWR",
+        SIGSYS    => "SIGSYS",
+        SIGRTMIN..=SIGRTMAX => "SIGRT",
+        _ => "UNKNOWN",
+    }
+}
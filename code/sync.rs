@@ -0,0 +1,776 @@
+//! Synchronization primitives built on futexes.
+//!
+//! Every primitive has a `new()` constructor for threads of the same process and a
+//! `new_shared()` constructor for processes which place it in MAP_SHARED memory. The private
+//! futexes are cheaper because the kernel doesn't have to look up the backing page.
+//!
+//! There is no unwinding, so there is no poisoning either: a thread which panics while holding
+//! a lock exits without releasing it.
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ops::{Deref, DerefMut};
+use core::ptr::null;
+use core::sync::atomic::{AtomicPtr, AtomicU32, Ordering};
+use core::time::Duration;
+
+use crate::types::*;
+use crate::syscall::*;
+use crate::error::Error;
+
+/// Sleep while `futex` contains `val`. Returns false if the timeout has elapsed, spurious
+/// wakeups return true so the callers have to check their condition again.
+fn wait(futex: &AtomicU32, val: u32, flag: i32, timeout: Option<&timespec>) -> bool {
+    let timeout = timeout.map_or(0, |ts| ts as *const timespec as usize);
+    match unsafe { futex_raw(futex, FUTEX_WAIT | flag, val, timeout, null(), 0) } {
+        Err(Error::ETIMEDOUT) => false,
+        _ => true,
+    }
+}
+
+/// Wake up to `count` waiters and return whether any was woken
+fn wake(futex: &AtomicU32, count: u32, flag: i32) -> bool {
+    matches!(unsafe { futex_raw(futex, FUTEX_WAKE | flag, count, 0, null(), 0) }, Ok(n) if n > 0)
+}
+
+unsafe fn futex_raw(futex: &AtomicU32, op: i32, val: u32, timeout: usize, uaddr2: *const u32, val3: u32) -> crate::Result<usize> {
+    crate::syscall::futex(futex.as_ptr(), op, val, timeout, uaddr2, val3)
+}
+
+fn to_timespec(duration: Duration) -> timespec {
+    timespec {
+        tv_sec: duration.as_secs().min(i64::MAX as u64) as i64,
+        tv_nsec: duration.subsec_nanos() as i64,
+    }
+}
+
+/// Spin for a short time before sleeping, most locks are released quickly
+fn spin(futex: &AtomicU32, until: impl Fn(u32) -> bool) -> u32 {
+    let mut spins = 100;
+    loop {
+        let val = futex.load(Ordering::Relaxed);
+        if until(val) || spins == 0 {
+            return val;
+        }
+        core::hint::spin_loop();
+        spins -= 1;
+    }
+}
+
+// ==============================================================================
+// Mutex
+// ==============================================================================
+
+const UNLOCKED: u32 = 0;
+const LOCKED: u32 = 1;
+const CONTENDED: u32 = 2;
+
+/// The three states of "Futexes are tricky" (Drepper): unlocked, locked and locked with
+/// possible waiters. The unlock only enters the kernel in the last state.
+pub struct Mutex<T: ?Sized> {
+    state: AtomicU32,
+    flag: i32,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Send for Mutex<T> {}
+unsafe impl<T: ?Sized + Send> Sync for Mutex<T> {}
+
+impl<T> Mutex<T> {
+    pub const fn new(data: T) -> Self {
+        Self { state: AtomicU32::new(UNLOCKED), flag: FUTEX_PRIVATE_FLAG, data: UnsafeCell::new(data) }
+    }
+
+    pub const fn new_shared(data: T) -> Self {
+        Self { state: AtomicU32::new(UNLOCKED), flag: 0, data: UnsafeCell::new(data) }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T: ?Sized> Mutex<T> {
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        if self.state.compare_exchange(UNLOCKED, LOCKED, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            self.lock_contended();
+        }
+        MutexGuard { mutex: self }
+    }
+
+    pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+        self.state
+            .compare_exchange(UNLOCKED, LOCKED, Ordering::Acquire, Ordering::Relaxed)
+            .ok()
+            .map(|_| MutexGuard { mutex: self })
+    }
+
+    fn lock_contended(&self) {
+        let state = spin(&self.state, |s| s != LOCKED);
+        if state == UNLOCKED
+            && self.state.compare_exchange(UNLOCKED, LOCKED, Ordering::Acquire, Ordering::Relaxed).is_ok()
+        {
+            return;
+        }
+        self.lock_slow();
+    }
+
+    /// Take the lock in the contended state. Also used by the threads woken by Condvar which
+    /// have to pass the wakeup on because other waiters may have been requeued behind them.
+    fn lock_slow(&self) {
+        while self.state.swap(CONTENDED, Ordering::Acquire) != UNLOCKED {
+            wait(&self.state, CONTENDED, self.flag, None);
+        }
+    }
+
+    fn unlock(&self) {
+        if self.state.swap(UNLOCKED, Ordering::Release) == CONTENDED {
+            wake(&self.state, 1, self.flag);
+        }
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+}
+
+impl<T: Default> Default for Mutex<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Mutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.try_lock() {
+            Some(guard) => f.debug_struct("Mutex").field("data", &&*guard).finish(),
+            None => f.debug_struct("Mutex").field("data", &"<locked>").finish(),
+        }
+    }
+}
+
+pub struct MutexGuard<'a, T: ?Sized> {
+    mutex: &'a Mutex<T>,
+}
+
+unsafe impl<T: ?Sized + Sync> Sync for MutexGuard<'_, T> {}
+
+impl<T: ?Sized> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for MutexGuard<'_, T> {
+    fn drop(&mut self) {
+        self.mutex.unlock();
+    }
+}
+
+// ==============================================================================
+// RwLock
+// ==============================================================================
+
+const READ_LOCKED: u32 = 1;
+const MASK: u32 = (1 << 30) - 1;
+const WRITE_LOCKED: u32 = MASK;
+const MAX_READERS: u32 = MASK - 1;
+const READERS_WAITING: u32 = 1 << 30;
+const WRITERS_WAITING: u32 = 1 << 31;
+
+fn is_unlocked(state: u32) -> bool {
+    state & MASK == 0
+}
+
+fn is_write_locked(state: u32) -> bool {
+    state & MASK == WRITE_LOCKED
+}
+
+/// New readers queue up behind a waiting writer so writers don't starve
+fn is_read_lockable(state: u32) -> bool {
+    state & MASK < MAX_READERS && state & (READERS_WAITING | WRITERS_WAITING) == 0
+}
+
+/// The state holds the number of readers (or WRITE_LOCKED) and whether readers or writers are
+/// waiting. Readers wait on the state, writers on a separate counter so that an unlock can
+/// wake a single writer.
+pub struct RwLock<T: ?Sized> {
+    state: AtomicU32,
+    writer_notify: AtomicU32,
+    flag: i32,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Send for RwLock<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for RwLock<T> {}
+
+impl<T> RwLock<T> {
+    pub const fn new(data: T) -> Self {
+        Self {
+            state: AtomicU32::new(0),
+            writer_notify: AtomicU32::new(0),
+            flag: FUTEX_PRIVATE_FLAG,
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    pub const fn new_shared(data: T) -> Self {
+        Self {
+            state: AtomicU32::new(0),
+            writer_notify: AtomicU32::new(0),
+            flag: 0,
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T: ?Sized> RwLock<T> {
+    pub fn read(&self) -> RwLockReadGuard<'_, T> {
+        let state = self.state.load(Ordering::Relaxed);
+        if !is_read_lockable(state)
+            || self.state.compare_exchange_weak(state, state + READ_LOCKED, Ordering::Acquire, Ordering::Relaxed).is_err()
+        {
+            self.read_contended();
+        }
+        RwLockReadGuard { lock: self }
+    }
+
+    pub fn try_read(&self) -> Option<RwLockReadGuard<'_, T>> {
+        self.state
+            .fetch_update(Ordering::Acquire, Ordering::Relaxed, |s| match is_read_lockable(s) {
+                true => Some(s + READ_LOCKED),
+                false => None,
+            })
+            .ok()
+            .map(|_| RwLockReadGuard { lock: self })
+    }
+
+    fn read_contended(&self) {
+        let mut state = spin(&self.state, |s| !is_write_locked(s) || s & (READERS_WAITING | WRITERS_WAITING) != 0);
+        loop {
+            if is_read_lockable(state) {
+                match self.state.compare_exchange_weak(state, state + READ_LOCKED, Ordering::Acquire, Ordering::Relaxed) {
+                    Ok(_) => return,
+                    Err(s) => {
+                        state = s;
+                        continue;
+                    }
+                }
+            }
+
+            if state & MASK == MAX_READERS {
+                panic!("too many readers of a RwLock");
+            }
+
+            // Tell the unlocking thread that readers are waiting
+            if state & READERS_WAITING == 0 {
+                if let Err(s) = self.state.compare_exchange(state, state | READERS_WAITING, Ordering::Relaxed, Ordering::Relaxed) {
+                    state = s;
+                    continue;
+                }
+            }
+
+            wait(&self.state, state | READERS_WAITING, self.flag, None);
+            state = spin(&self.state, |s| !is_write_locked(s) || s & (READERS_WAITING | WRITERS_WAITING) != 0);
+        }
+    }
+
+    fn read_unlock(&self) {
+        let state = self.state.fetch_sub(READ_LOCKED, Ordering::Release) - READ_LOCKED;
+
+        // Readers only wait while there is a writer (or a waiting one), so the last reader
+        // only has to look for waiting writers
+        if is_unlocked(state) && state & WRITERS_WAITING != 0 {
+            self.wake_writer_or_readers(state);
+        }
+    }
+
+    pub fn write(&self) -> RwLockWriteGuard<'_, T> {
+        if self.state.compare_exchange(0, WRITE_LOCKED, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            self.write_contended();
+        }
+        RwLockWriteGuard { lock: self }
+    }
+
+    pub fn try_write(&self) -> Option<RwLockWriteGuard<'_, T>> {
+        self.state
+            .fetch_update(Ordering::Acquire, Ordering::Relaxed, |s| match is_unlocked(s) {
+                true => Some(s + WRITE_LOCKED),
+                false => None,
+            })
+            .ok()
+            .map(|_| RwLockWriteGuard { lock: self })
+    }
+
+    fn write_contended(&self) {
+        let mut state = spin(&self.state, |s| is_unlocked(s) || s & WRITERS_WAITING != 0);
+
+        // Once this writer has waited it can't know whether other writers are still waiting,
+        // so it keeps the flag set when it takes the lock
+        let mut other_writers_waiting = 0;
+
+        loop {
+            if is_unlocked(state) {
+                match self.state.compare_exchange_weak(
+                    state,
+                    state | WRITE_LOCKED | other_writers_waiting,
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => return,
+                    Err(s) => {
+                        state = s;
+                        continue;
+                    }
+                }
+            }
+
+            if state & WRITERS_WAITING == 0 {
+                if let Err(s) = self.state.compare_exchange(state, state | WRITERS_WAITING, Ordering::Relaxed, Ordering::Relaxed) {
+                    state = s;
+                    continue;
+                }
+            }
+            other_writers_waiting = WRITERS_WAITING;
+
+            // Check the state again after reading the counter to not miss a wakeup
+            let seq = self.writer_notify.load(Ordering::Acquire);
+            state = self.state.load(Ordering::Relaxed);
+            if is_unlocked(state) || state & WRITERS_WAITING == 0 {
+                continue;
+            }
+
+            wait(&self.writer_notify, seq, self.flag, None);
+            state = spin(&self.state, |s| is_unlocked(s) || s & WRITERS_WAITING != 0);
+        }
+    }
+
+    fn write_unlock(&self) {
+        let state = self.state.fetch_sub(WRITE_LOCKED, Ordering::Release) - WRITE_LOCKED;
+        if state & (READERS_WAITING | WRITERS_WAITING) != 0 {
+            self.wake_writer_or_readers(state);
+        }
+    }
+
+    /// Called with the lock unlocked and waiters flagged. A writer is preferred over the readers.
+    fn wake_writer_or_readers(&self, mut state: u32) {
+        if state == WRITERS_WAITING {
+            match self.state.compare_exchange(state, 0, Ordering::Relaxed, Ordering::Relaxed) {
+                Ok(_) => {
+                    self.wake_writer();
+                    return;
+                }
+                Err(s) => state = s,
+            }
+        }
+
+        if state == READERS_WAITING | WRITERS_WAITING {
+            if self.state.compare_exchange(state, READERS_WAITING, Ordering::Relaxed, Ordering::Relaxed).is_err() {
+                // Somebody else took the lock and is now responsible for the waiters
+                return;
+            }
+            if self.wake_writer() {
+                return;
+            }
+            // No writer was actually sleeping, so the readers must not wait for one
+            state = READERS_WAITING;
+        }
+
+        if state == READERS_WAITING
+            && self.state.compare_exchange(state, 0, Ordering::Relaxed, Ordering::Relaxed).is_ok()
+        {
+            wake(&self.state, i32::MAX as u32, self.flag);
+        }
+    }
+
+    fn wake_writer(&self) -> bool {
+        self.writer_notify.fetch_add(1, Ordering::Release);
+        wake(&self.writer_notify, 1, self.flag)
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+}
+
+impl<T: Default> Default for RwLock<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+pub struct RwLockReadGuard<'a, T: ?Sized> {
+    lock: &'a RwLock<T>,
+}
+
+impl<T: ?Sized> Deref for RwLockReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for RwLockReadGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.read_unlock();
+    }
+}
+
+pub struct RwLockWriteGuard<'a, T: ?Sized> {
+    lock: &'a RwLock<T>,
+}
+
+impl<T: ?Sized> Deref for RwLockWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for RwLockWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for RwLockWriteGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.write_unlock();
+    }
+}
+
+// ==============================================================================
+// Condvar
+// ==============================================================================
+
+/// The waiters sleep on a sequence number which is incremented by every notification.
+/// notify_all() wakes one waiter and requeues the others onto the futex of the mutex, so they
+/// are woken one by one as the mutex is unlocked instead of all racing for it at once.
+pub struct Condvar {
+    seq: AtomicU32,
+    flag: i32,
+
+    /// The state of the mutex used with the condvar, the target of the requeue
+    mutex: AtomicPtr<AtomicU32>,
+}
+
+unsafe impl Send for Condvar {}
+unsafe impl Sync for Condvar {}
+
+impl Condvar {
+    pub const fn new() -> Self {
+        Self { seq: AtomicU32::new(0), flag: FUTEX_PRIVATE_FLAG, mutex: AtomicPtr::new(core::ptr::null_mut()) }
+    }
+
+    /// Has to be used with mutexes created with Mutex::new_shared()
+    pub const fn new_shared() -> Self {
+        Self { seq: AtomicU32::new(0), flag: 0, mutex: AtomicPtr::new(core::ptr::null_mut()) }
+    }
+
+    /// Unlock the mutex, sleep until notified and lock it again. Spurious wakeups are
+    /// possible, so wait in a loop which checks the condition.
+    pub fn wait<'a, T: ?Sized>(&self, guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+        self.wait_inner(guard, None).0
+    }
+
+    /// Returns the guard and false if the timeout has elapsed
+    pub fn wait_timeout<'a, T: ?Sized>(&self, guard: MutexGuard<'a, T>, timeout: Duration) -> (MutexGuard<'a, T>, bool) {
+        self.wait_inner(guard, Some(to_timespec(timeout)))
+    }
+
+    /// Wait until the condition returns false
+    pub fn wait_while<'a, T: ?Sized>(&self, mut guard: MutexGuard<'a, T>, mut condition: impl FnMut(&mut T) -> bool) -> MutexGuard<'a, T> {
+        while condition(&mut *guard) {
+            guard = self.wait(guard);
+        }
+        guard
+    }
+
+    fn wait_inner<'a, T: ?Sized>(&self, guard: MutexGuard<'a, T>, timeout: Option<timespec>) -> (MutexGuard<'a, T>, bool) {
+        let mutex = guard.mutex;
+        self.mutex.store(&mutex.state as *const AtomicU32 as *mut AtomicU32, Ordering::Relaxed);
+
+        // Read the sequence before unlocking to not miss a notification in between
+        let seq = self.seq.load(Ordering::Relaxed);
+        core::mem::forget(guard);
+        mutex.unlock();
+
+        let notified = wait(&self.seq, seq, self.flag, timeout.as_ref());
+
+        // A requeued thread sleeps on the mutex, so it has to take it in the contended state
+        // to pass the wakeup on to the others
+        mutex.lock_slow();
+        (MutexGuard { mutex }, notified)
+    }
+
+    pub fn notify_one(&self) {
+        self.seq.fetch_add(1, Ordering::Relaxed);
+        wake(&self.seq, 1, self.flag);
+    }
+
+    pub fn notify_all(&self) {
+        let mutex = self.mutex.load(Ordering::Relaxed);
+        let mut seq = self.seq.fetch_add(1, Ordering::Relaxed).wrapping_add(1);
+        if mutex.is_null() {
+            return;
+        }
+
+        // Fails with EAGAIN when another notification has changed the sequence in the
+        // meantime, the waiters would stay asleep then. Requeue with the new sequence.
+        loop {
+            match unsafe {
+                futex_raw(&self.seq, FUTEX_CMP_REQUEUE | self.flag, 1, i32::MAX as usize, (*mutex).as_ptr(), seq)
+            } {
+                Err(Error::EAGAIN) => seq = self.seq.load(Ordering::Relaxed),
+                _ => break,
+            }
+        }
+    }
+}
+
+impl Default for Condvar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ==============================================================================
+// Once and OnceLock
+// ==============================================================================
+
+const INCOMPLETE: u32 = 0;
+const RUNNING: u32 = 1;
+const QUEUED: u32 = 2;
+const COMPLETE: u32 = 3;
+
+/// Runs an initialization exactly once, concurrent callers wait until it has completed
+pub struct Once {
+    state: AtomicU32,
+    flag: i32,
+}
+
+impl Once {
+    pub const fn new() -> Self {
+        Self { state: AtomicU32::new(INCOMPLETE), flag: FUTEX_PRIVATE_FLAG }
+    }
+
+    pub const fn new_shared() -> Self {
+        Self { state: AtomicU32::new(INCOMPLETE), flag: 0 }
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.state.load(Ordering::Acquire) == COMPLETE
+    }
+
+    pub fn call_once(&self, f: impl FnOnce()) {
+        if self.is_completed() {
+            return;
+        }
+
+        let mut state = self.state.load(Ordering::Acquire);
+        loop {
+            match state {
+                COMPLETE => return,
+                INCOMPLETE => {
+                    if let Err(s) = self.state.compare_exchange(INCOMPLETE, RUNNING, Ordering::Acquire, Ordering::Acquire) {
+                        state = s;
+                        continue;
+                    }
+                    f();
+                    if self.state.swap(COMPLETE, Ordering::Release) == QUEUED {
+                        wake(&self.state, i32::MAX as u32, self.flag);
+                    }
+                    return;
+                }
+                RUNNING => {
+                    if let Err(s) = self.state.compare_exchange(RUNNING, QUEUED, Ordering::Relaxed, Ordering::Acquire) {
+                        state = s;
+                        continue;
+                    }
+                    state = QUEUED;
+                }
+                _ => {
+                    wait(&self.state, QUEUED, self.flag, None);
+                    state = self.state.load(Ordering::Acquire);
+                }
+            }
+        }
+    }
+}
+
+impl Default for Once {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A value which is initialized once, e.g. a lazily initialized static
+pub struct OnceLock<T> {
+    once: Once,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+unsafe impl<T: Send> Send for OnceLock<T> {}
+unsafe impl<T: Send + Sync> Sync for OnceLock<T> {}
+
+impl<T> OnceLock<T> {
+    pub const fn new() -> Self {
+        Self { once: Once::new(), value: UnsafeCell::new(MaybeUninit::uninit()) }
+    }
+
+    pub fn get(&self) -> Option<&T> {
+        match self.once.is_completed() {
+            true => Some(unsafe { (*self.value.get()).assume_init_ref() }),
+            false => None,
+        }
+    }
+
+    pub fn get_or_init(&self, f: impl FnOnce() -> T) -> &T {
+        self.once.call_once(|| unsafe {
+            (*self.value.get()).write(f());
+        });
+        unsafe { (*self.value.get()).assume_init_ref() }
+    }
+
+    /// Returns the value back if the lock was already initialized
+    pub fn set(&self, value: T) -> core::result::Result<(), T> {
+        let mut value = Some(value);
+        self.get_or_init(|| value.take().unwrap());
+        match value {
+            Some(value) => Err(value),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T> Default for OnceLock<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for OnceLock<T> {
+    fn drop(&mut self) {
+        if self.once.is_completed() {
+            unsafe { (*self.value.get()).assume_init_drop() };
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for OnceLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("OnceLock").field(&self.get()).finish()
+    }
+}
+
+// ==============================================================================
+// Barrier
+// ==============================================================================
+
+/// Blocks until `n` threads have called wait(). It can be reused for the next round.
+pub struct Barrier {
+    n: u32,
+    count: AtomicU32,
+    generation: AtomicU32,
+    flag: i32,
+}
+
+impl Barrier {
+    pub const fn new(n: u32) -> Self {
+        Self { n, count: AtomicU32::new(0), generation: AtomicU32::new(0), flag: FUTEX_PRIVATE_FLAG }
+    }
+
+    pub const fn new_shared(n: u32) -> Self {
+        Self { n, count: AtomicU32::new(0), generation: AtomicU32::new(0), flag: 0 }
+    }
+
+    /// Returns true for exactly one of the threads of a round, the last one which arrived
+    pub fn wait(&self) -> bool {
+        let generation = self.generation.load(Ordering::Acquire);
+        if self.count.fetch_add(1, Ordering::AcqRel) + 1 >= self.n {
+            self.count.store(0, Ordering::Relaxed);
+            self.generation.fetch_add(1, Ordering::Release);
+            wake(&self.generation, i32::MAX as u32, self.flag);
+            return true;
+        }
+
+        while self.generation.load(Ordering::Acquire) == generation {
+            wait(&self.generation, generation, self.flag, None);
+        }
+        false
+    }
+}
+
+// ==============================================================================
+// Semaphore
+// ==============================================================================
+
+/// A counting semaphore, acquire() waits while the count is 0
+pub struct Semaphore {
+    count: AtomicU32,
+    waiters: AtomicU32,
+    flag: i32,
+}
+
+impl Semaphore {
+    pub const fn new(count: u32) -> Self {
+        Self { count: AtomicU32::new(count), waiters: AtomicU32::new(0), flag: FUTEX_PRIVATE_FLAG }
+    }
+
+    pub const fn new_shared(count: u32) -> Self {
+        Self { count: AtomicU32::new(count), waiters: AtomicU32::new(0), flag: 0 }
+    }
+
+    pub fn try_acquire(&self) -> bool {
+        self.count
+            .fetch_update(Ordering::Acquire, Ordering::Relaxed, |c| c.checked_sub(1))
+            .is_ok()
+    }
+
+    pub fn acquire(&self) {
+        while !self.try_acquire() {
+            self.waiters.fetch_add(1, Ordering::SeqCst);
+            wait(&self.count, 0, self.flag, None);
+            self.waiters.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns false if the timeout has elapsed without acquiring the semaphore
+    pub fn acquire_timeout(&self, timeout: Duration) -> bool {
+        let ts = to_timespec(timeout);
+        while !self.try_acquire() {
+            self.waiters.fetch_add(1, Ordering::SeqCst);
+            let woken = wait(&self.count, 0, self.flag, Some(&ts));
+            self.waiters.fetch_sub(1, Ordering::Relaxed);
+            if !woken {
+                return self.try_acquire();
+            }
+        }
+        true
+    }
+
+    pub fn release(&self) {
+        self.count.fetch_add(1, Ordering::SeqCst);
+        if self.waiters.load(Ordering::SeqCst) > 0 {
+            wake(&self.count, 1, self.flag);
+        }
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count.load(Ordering::Relaxed)
+    }
+}
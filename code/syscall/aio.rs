@@ -1,6 +1,113 @@
 use crate::types::*;
+use crate::fd::{FromRawFd, OwnedFd};
 use crate::error::{Error, Result, result};
 
+// ==============================================================================
+// io_uring setup flags (include/uapi/linux/io_uring.h)
+// ==============================================================================
+pub const IORING_SETUP_IOPOLL:        u32 = 1 << 0;
+pub const IORING_SETUP_SQPOLL:        u32 = 1 << 1;
+pub const IORING_SETUP_SQ_AFF:        u32 = 1 << 2;
+pub const IORING_SETUP_CQSIZE:        u32 = 1 << 3;
+pub const IORING_SETUP_CLAMP:         u32 = 1 << 4;
+pub const IORING_SETUP_ATTACH_WQ:     u32 = 1 << 5;
+pub const IORING_SETUP_R_DISABLED:    u32 = 1 << 6;
+pub const IORING_SETUP_SUBMIT_ALL:    u32 = 1 << 7;
+pub const IORING_SETUP_COOP_TASKRUN:  u32 = 1 << 8;
+pub const IORING_SETUP_SINGLE_ISSUER: u32 = 1 << 12;
+
+// ==============================================================================
+// io_uring features (include/uapi/linux/io_uring.h)
+// ==============================================================================
+pub const IORING_FEAT_SINGLE_MMAP:     u32 = 1 << 0;
+pub const IORING_FEAT_NODROP:          u32 = 1 << 1;
+pub const IORING_FEAT_SUBMIT_STABLE:   u32 = 1 << 2;
+pub const IORING_FEAT_RW_CUR_POS:      u32 = 1 << 3;
+pub const IORING_FEAT_CUR_PERSONALITY: u32 = 1 << 4;
+pub const IORING_FEAT_FAST_POLL:       u32 = 1 << 5;
+pub const IORING_FEAT_EXT_ARG:         u32 = 1 << 8;
+
+// ==============================================================================
+// io_uring mmap offsets (include/uapi/linux/io_uring.h)
+// ==============================================================================
+pub const IORING_OFF_SQ_RING: usize = 0;
+pub const IORING_OFF_CQ_RING: usize = 0x8000000;
+pub const IORING_OFF_SQES:    usize = 0x10000000;
+
+// ==============================================================================
+// io_uring ring flags (include/uapi/linux/io_uring.h)
+// ==============================================================================
+pub const IORING_SQ_NEED_WAKEUP: u32 = 1 << 0;
+pub const IORING_SQ_CQ_OVERFLOW: u32 = 1 << 1;
+pub const IORING_SQ_TASKRUN:     u32 = 1 << 2;
+
+// ==============================================================================
+// io_uring_enter flags (include/uapi/linux/io_uring.h)
+// ==============================================================================
+pub const IORING_ENTER_GETEVENTS: u32 = 1 << 0;
+pub const IORING_ENTER_SQ_WAKEUP: u32 = 1 << 1;
+pub const IORING_ENTER_SQ_WAIT:   u32 = 1 << 2;
+pub const IORING_ENTER_EXT_ARG:   u32 = 1 << 3;
+
+// ==============================================================================
+// io_uring_register opcodes (include/uapi/linux/io_uring.h)
+// ==============================================================================
+pub const IORING_REGISTER_BUFFERS:      u32 = 0;
+pub const IORING_UNREGISTER_BUFFERS:    u32 = 1;
+pub const IORING_REGISTER_FILES:        u32 = 2;
+pub const IORING_UNREGISTER_FILES:      u32 = 3;
+pub const IORING_REGISTER_EVENTFD:      u32 = 4;
+pub const IORING_UNREGISTER_EVENTFD:    u32 = 5;
+pub const IORING_REGISTER_FILES_UPDATE: u32 = 6;
+pub const IORING_REGISTER_PROBE:        u32 = 8;
+
+// ==============================================================================
+// io_uring opcodes (include/uapi/linux/io_uring.h)
+// ==============================================================================
+pub const IORING_OP_NOP:             u8 = 0;
+pub const IORING_OP_READV:           u8 = 1;
+pub const IORING_OP_WRITEV:          u8 = 2;
+pub const IORING_OP_FSYNC:           u8 = 3;
+pub const IORING_OP_READ_FIXED:      u8 = 4;
+pub const IORING_OP_WRITE_FIXED:     u8 = 5;
+pub const IORING_OP_POLL_ADD:        u8 = 6;
+pub const IORING_OP_POLL_REMOVE:     u8 = 7;
+pub const IORING_OP_SYNC_FILE_RANGE: u8 = 8;
+pub const IORING_OP_SENDMSG:         u8 = 9;
+pub const IORING_OP_RECVMSG:         u8 = 10;
+pub const IORING_OP_TIMEOUT:         u8 = 11;
+pub const IORING_OP_TIMEOUT_REMOVE:  u8 = 12;
+pub const IORING_OP_ACCEPT:          u8 = 13;
+pub const IORING_OP_ASYNC_CANCEL:    u8 = 14;
+pub const IORING_OP_LINK_TIMEOUT:    u8 = 15;
+pub const IORING_OP_CONNECT:         u8 = 16;
+pub const IORING_OP_FALLOCATE:       u8 = 17;
+pub const IORING_OP_OPENAT:          u8 = 18;
+pub const IORING_OP_CLOSE:           u8 = 19;
+pub const IORING_OP_FILES_UPDATE:    u8 = 20;
+pub const IORING_OP_STATX:           u8 = 21;
+pub const IORING_OP_READ:            u8 = 22;
+pub const IORING_OP_WRITE:           u8 = 23;
+pub const IORING_OP_FADVISE:         u8 = 24;
+pub const IORING_OP_MADVISE:         u8 = 25;
+pub const IORING_OP_SEND:            u8 = 26;
+pub const IORING_OP_RECV:            u8 = 27;
+pub const IORING_OP_OPENAT2:         u8 = 28;
+
+// ==============================================================================
+// io_uring sqe flags (include/uapi/linux/io_uring.h)
+// ==============================================================================
+pub const IOSQE_FIXED_FILE:       u8 = 1 << 0;
+pub const IOSQE_IO_DRAIN:         u8 = 1 << 1;
+pub const IOSQE_IO_LINK:          u8 = 1 << 2;
+pub const IOSQE_IO_HARDLINK:      u8 = 1 << 3;
+pub const IOSQE_ASYNC:            u8 = 1 << 4;
+pub const IOSQE_BUFFER_SELECT:    u8 = 1 << 5;
+pub const IOSQE_CQE_SKIP_SUCCESS: u8 = 1 << 6;
+
+pub const IORING_FSYNC_DATASYNC: u32 = 1 << 0;
+pub const IORING_TIMEOUT_ABS:    u32 = 1 << 0;
+
 #[no_mangle]
 pub fn iopl() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_IOPL) })
@@ -48,16 +155,25 @@ pub fn io_pgetevents() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_IO_PGETEVENTS) })
 }
 #[no_mangle]
-pub fn io_uring_setup() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_IO_URING_SETUP) })
+pub fn io_uring_setup(entries: u32, params: &mut io_uring_params) -> Result<OwnedFd> {
+    result(unsafe { syscall!(super::SYS_IO_URING_SETUP, entries, params as *mut io_uring_params) })
+        .map(|fd| unsafe { OwnedFd::from_raw_fd(fd as u32) })
 }
 
+/// Submit `to_submit` entries and wait for `min_complete` completions with IORING_ENTER_GETEVENTS.
+/// Unsafe because the kernel accesses the memory the submitted entries point to.
 #[no_mangle]
-pub fn io_uring_enter() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_IO_URING_ENTER) })
+pub unsafe fn io_uring_enter(fd: u32, to_submit: u32, min_complete: u32, flags: u32, sig: Option<&sigset_t>) -> Result<usize> {
+    let sig = match sig {
+        Some(set) => set as *const sigset_t,
+        None => core::ptr::null(),
+    };
+    result(syscall!(super::SYS_IO_URING_ENTER, fd, to_submit, min_complete, flags, sig, core::mem::size_of::<sigset_t>()))
 }
 
+/// The type of `arg` depends on the opcode, e.g. an array of `nr_args` iovecs for
+/// IORING_REGISTER_BUFFERS or of file descriptors for IORING_REGISTER_FILES
 #[no_mangle]
-pub fn io_uring_register() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_IO_URING_REGISTER) })
+pub unsafe fn io_uring_register(fd: u32, opcode: u32, arg: *const u8, nr_args: u32) -> Result<usize> {
+    result(syscall!(super::SYS_IO_URING_REGISTER, fd, opcode, arg, nr_args))
 }
@@ -1,9 +1,60 @@
 use crate::types::*;
+use crate::fd::{FromRawFd, OwnedFd};
 use crate::error::{Error, Result, result};
 
+// ==============================================================================
+// Poll events (include/uapi/asm-generic/poll.h)
+// ==============================================================================
+pub const POLLIN:     i16 = 0x0001;
+pub const POLLPRI:    i16 = 0x0002;
+pub const POLLOUT:    i16 = 0x0004;
+pub const POLLERR:    i16 = 0x0008;
+pub const POLLHUP:    i16 = 0x0010;
+pub const POLLNVAL:   i16 = 0x0020;
+pub const POLLRDHUP:  i16 = 0x2000;
+
+// ==============================================================================
+// Epoll (include/uapi/linux/eventpoll.h)
+// ==============================================================================
+pub const EPOLL_CLOEXEC:  i32 = 0o2000000;
+
+pub const EPOLL_CTL_ADD:  i32 = 1;
+pub const EPOLL_CTL_DEL:  i32 = 2;
+pub const EPOLL_CTL_MOD:  i32 = 3;
+
+pub const EPOLLIN:        u32 = 0x00000001;
+pub const EPOLLPRI:       u32 = 0x00000002;
+pub const EPOLLOUT:       u32 = 0x00000004;
+pub const EPOLLERR:       u32 = 0x00000008;
+pub const EPOLLHUP:       u32 = 0x00000010;
+pub const EPOLLRDNORM:    u32 = 0x00000040;
+pub const EPOLLRDBAND:    u32 = 0x00000080;
+pub const EPOLLWRNORM:    u32 = 0x00000100;
+pub const EPOLLWRBAND:    u32 = 0x00000200;
+pub const EPOLLMSG:       u32 = 0x00000400;
+pub const EPOLLRDHUP:     u32 = 0x00002000;
+pub const EPOLLEXCLUSIVE: u32 = 1 << 28;
+pub const EPOLLWAKEUP:    u32 = 1 << 29;
+pub const EPOLLONESHOT:   u32 = 1 << 30;
+pub const EPOLLET:        u32 = 1 << 31;
+
+// ==============================================================================
+// Eventfd flags (include/uapi/linux/eventfd.h)
+// ==============================================================================
+pub const EFD_SEMAPHORE: i32 = 0o0000001;
+pub const EFD_NONBLOCK:  i32 = 0o0004000;
+pub const EFD_CLOEXEC:   i32 = 0o2000000;
+
+// ==============================================================================
+// Inotify flags (include/uapi/linux/inotify.h)
+// ==============================================================================
+pub const IN_NONBLOCK: i32 = 0o0004000;
+pub const IN_CLOEXEC:  i32 = 0o2000000;
+
+/// Returns the number of descriptors with events in revents, 0 on timeout
 #[no_mangle]
-pub fn poll(fds: &[pollfd], nfds: nfds_t, timeout: i32) -> Result<()> {
-    result(unsafe { syscall!(super::SYS_POLL, nfds, timeout) }).map(|_| ())
+pub fn poll(fds: &mut [pollfd], timeout: i32) -> Result<usize> {
+    result(unsafe { syscall!(super::SYS_POLL, fds.as_mut_ptr(), fds.len() as nfds_t, timeout) })
 }
 #[no_mangle]
 pub fn select() -> Result<usize> {
@@ -14,19 +65,25 @@ pub fn select() -> Result<usize> {
 pub fn epoll_create() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_EPOLL_CREATE) })
 }
+/// Returns the number of events written into `events`, 0 on timeout. A timeout of -1 blocks.
 #[no_mangle]
-pub fn epoll_wait() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_EPOLL_WAIT) })
+pub fn epoll_wait(epfd: u32, events: &mut [epoll_event], timeout: i32) -> Result<usize> {
+    result(unsafe { syscall!(super::SYS_EPOLL_WAIT, epfd, events.as_mut_ptr(), events.len() as i32, timeout) })
 }
 
+/// The event is ignored by EPOLL_CTL_DEL
 #[no_mangle]
-pub fn epoll_ctl() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_EPOLL_CTL) })
+pub fn epoll_ctl(epfd: u32, op: i32, fd: u32, event: Option<&epoll_event>) -> Result<()> {
+    let event = match event {
+        Some(event) => event as *const epoll_event,
+        None => core::ptr::null(),
+    };
+    result(unsafe { syscall!(super::SYS_EPOLL_CTL, epfd, op, fd, event) }).map(|_| ())
 }
 
 #[no_mangle]
-pub fn inotify_init() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_INOTIFY_INIT) })
+pub fn inotify_init() -> Result<OwnedFd> {
+    result(unsafe { syscall!(super::SYS_INOTIFY_INIT) }).map(|fd| unsafe { OwnedFd::from_raw_fd(fd as u32) })
 }
 
 #[no_mangle]
@@ -39,8 +96,8 @@ pub fn inotify_rm_watch() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_INOTIFY_RM_WATCH) })
 }
 #[no_mangle]
-pub fn inotify_init1() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_INOTIFY_INIT1) })
+pub fn inotify_init1(flags: i32) -> Result<OwnedFd> {
+    result(unsafe { syscall!(super::SYS_INOTIFY_INIT1, flags) }).map(|fd| unsafe { OwnedFd::from_raw_fd(fd as u32) })
 }
 
 #[no_mangle]
@@ -53,22 +110,30 @@ pub fn ppoll() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_PPOLL) })
 }
 
+/// epoll_wait with the signal mask replaced by `sigmask` while waiting
 #[no_mangle]
-pub fn epoll_pwait() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_EPOLL_PWAIT) })
+pub fn epoll_pwait(epfd: u32, events: &mut [epoll_event], timeout: i32, sigmask: Option<&sigset_t>) -> Result<usize> {
+    let sigmask = match sigmask {
+        Some(set) => set as *const sigset_t,
+        None => core::ptr::null(),
+    };
+    result(unsafe {
+        syscall!(super::SYS_EPOLL_PWAIT, epfd, events.as_mut_ptr(), events.len() as i32, timeout, sigmask, core::mem::size_of::<sigset_t>())
+    })
 }
 
 #[no_mangle]
-pub fn eventfd() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_EVENTFD) })
+pub fn eventfd(initval: u32) -> Result<OwnedFd> {
+    result(unsafe { syscall!(super::SYS_EVENTFD, initval) }).map(|fd| unsafe { OwnedFd::from_raw_fd(fd as u32) })
 }
+/// A counter which is read and written as a native endian u64, `flags` are EFD_*
 #[no_mangle]
-pub fn eventfd2() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_EVENTFD2) })
+pub fn eventfd2(initval: u32, flags: i32) -> Result<OwnedFd> {
+    result(unsafe { syscall!(super::SYS_EVENTFD2, initval, flags) }).map(|fd| unsafe { OwnedFd::from_raw_fd(fd as u32) })
 }
 #[no_mangle]
-pub fn epoll_create1() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_EPOLL_CREATE1) })
+pub fn epoll_create1(flags: i32) -> Result<OwnedFd> {
+    result(unsafe { syscall!(super::SYS_EPOLL_CREATE1, flags) }).map(|fd| unsafe { OwnedFd::from_raw_fd(fd as u32) })
 }
 #[no_mangle]
 pub fn fanotify_init() -> Result<usize> {
@@ -80,8 +145,19 @@ pub fn fanotify_mark() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_FANOTIFY_MARK) })
 }
 
-#[no_mangle]
-pub fn epoll_pwait2() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_EPOLL_PWAIT2) })
+/// epoll_pwait with a timeout in nanoseconds (Linux 5.11), None blocks
+#[no_mangle]
+pub fn epoll_pwait2(epfd: u32, events: &mut [epoll_event], timeout: Option<&timespec>, sigmask: Option<&sigset_t>) -> Result<usize> {
+    let timeout = match timeout {
+        Some(ts) => ts as *const timespec,
+        None => core::ptr::null(),
+    };
+    let sigmask = match sigmask {
+        Some(set) => set as *const sigset_t,
+        None => core::ptr::null(),
+    };
+    result(unsafe {
+        syscall!(super::SYS_EPOLL_PWAIT2, epfd, events.as_mut_ptr(), events.len() as i32, timeout, sigmask, core::mem::size_of::<sigset_t>())
+    })
 }
 
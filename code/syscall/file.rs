@@ -1,13 +1,42 @@
+use core::ffi::CStr;
+
 use crate::cstr;
+use crate::fd::{FromRawFd, OwnedFd};
 use crate::types::*;
 use crate::error::{Error, Result, result};
 
+// ==============================================================================
+// Special values for the *at() syscalls (include/uapi/linux/fcntl.h)
+// ==============================================================================
+pub const AT_FDCWD:            i32 = -100;
+pub const AT_SYMLINK_NOFOLLOW: i32 = 0x100;
+pub const AT_EACCESS:          i32 = 0x200;
+pub const AT_REMOVEDIR:        i32 = 0x200;
+pub const AT_SYMLINK_FOLLOW:   i32 = 0x400;
+pub const AT_NO_AUTOMOUNT:     i32 = 0x800;
+pub const AT_EMPTY_PATH:       i32 = 0x1000;
+pub const AT_RECURSIVE:        i32 = 0x8000;
+
+pub const AT_STATX_SYNC_AS_STAT: i32 = 0x0000;
+pub const AT_STATX_FORCE_SYNC:   i32 = 0x2000;
+pub const AT_STATX_DONT_SYNC:    i32 = 0x4000;
+
+// ==============================================================================
+// Resolve flags of open_how (include/uapi/linux/openat2.h)
+// ==============================================================================
+pub const RESOLVE_NO_XDEV:       u64 = 0x01;
+pub const RESOLVE_NO_MAGICLINKS: u64 = 0x02;
+pub const RESOLVE_NO_SYMLINKS:   u64 = 0x04;
+pub const RESOLVE_BENEATH:       u64 = 0x08;
+pub const RESOLVE_IN_ROOT:       u64 = 0x10;
+pub const RESOLVE_CACHED:        u64 = 0x20;
+
 #[no_mangle]
 #[doc = include_str!(".file/open.md")]
-pub fn open(path: &str, flags: i32, mode: mode_t) -> Result<u32> {
+pub fn open(path: &str, flags: i32, mode: mode_t) -> Result<OwnedFd> {
     let mut dst = [0u8; crate::limits::PATH_MAX];
     cstr(path.as_bytes(), &mut dst)?;
-    result(unsafe { syscall!(super::SYS_OPEN, dst.as_ptr(), flags, mode) }).map(|n| n as u32)
+    result(unsafe { syscall!(super::SYS_OPEN, dst.as_ptr(), flags, mode) }).map(|fd| unsafe { OwnedFd::from_raw_fd(fd as u32) })
 }
 
 #[no_mangle]
@@ -38,17 +67,24 @@ pub fn lseek(fd: u32, offset: off_t, whence: i32) -> Result<off_t> {
     result(unsafe { syscall!(super::SYS_LSEEK, fd, offset, whence) })
 }
 #[no_mangle]
-pub fn dup() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_DUP) })
+pub fn dup(fd: u32) -> Result<OwnedFd> {
+    result(unsafe { syscall!(super::SYS_DUP, fd) }).map(|fd| unsafe { OwnedFd::from_raw_fd(fd as u32) })
 }
 
+/// Returns `newfd` as a raw number because it usually belongs to someone else, e.g. stdout
 #[no_mangle]
-pub fn dup2() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_DUP2) })
+pub fn dup2(oldfd: u32, newfd: u32) -> Result<u32> {
+    result(unsafe { syscall!(super::SYS_DUP2, oldfd, newfd) }).map(|n| n as u32)
 }
+/// SAFETY: For most requests `arg` is a pointer to the struct the kernel reads or writes.
+#[no_mangle]
+pub unsafe fn ioctl(fd: u32, request: u64, arg: usize) -> Result<usize> {
+    result(syscall!(super::SYS_IOCTL, fd, request, arg))
+}
+
 #[no_mangle]
-pub fn fcntl() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_FCNTL) })
+pub fn fcntl(fd: u32, cmd: i32, arg: usize) -> Result<usize> {
+    result(unsafe { syscall!(super::SYS_FCNTL, fd, cmd, arg) })
 }
 
 #[no_mangle]
@@ -86,17 +122,20 @@ pub fn fadvise64() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_FADVISE64) })
 }
 
+/// Like open() but a relative `path` is resolved from the directory `dirfd` or from the
+/// working directory with AT_FDCWD
 #[no_mangle]
-pub fn openat() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_OPENAT) })
+pub fn openat(dirfd: i32, path: &CStr, flags: i32, mode: mode_t) -> Result<OwnedFd> {
+    result(unsafe { syscall!(super::SYS_OPENAT, dirfd, path.as_ptr(), flags, mode) })
+        .map(|fd| unsafe { OwnedFd::from_raw_fd(fd as u32) })
 }
 #[no_mangle]
 pub fn fallocate(fd: u32, mode: u32, offset: off_t, len: off_t) -> Result<()> {
     result(unsafe { syscall!(super::SYS_FALLOCATE, fd, mode, offset, len) }).map(|_| ())
 }
 #[no_mangle]
-pub fn dup3() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_DUP3) })
+pub fn dup3(oldfd: u32, newfd: u32, flags: i32) -> Result<u32> {
+    result(unsafe { syscall!(super::SYS_DUP3, oldfd, newfd, flags) }).map(|n| n as u32)
 }
 
 #[no_mangle]
@@ -113,7 +152,10 @@ pub fn close_range() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_CLOSE_RANGE) })
 }
 
+/// openat with RESOLVE_* restrictions for the path. Unlike openat it fails with EINVAL
+/// on unknown flags and on a mode without O_CREAT or O_TMPFILE.
 #[no_mangle]
-pub fn openat2() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_OPENAT2) })
+pub fn openat2(dirfd: i32, path: &CStr, how: &open_how) -> Result<OwnedFd> {
+    result(unsafe { syscall!(super::SYS_OPENAT2, dirfd, path.as_ptr(), how as *const open_how, core::mem::size_of::<open_how>()) })
+        .map(|fd| unsafe { OwnedFd::from_raw_fd(fd as u32) })
 }
@@ -1,7 +1,179 @@
+use core::ffi::CStr;
+
 use crate::cstr;
+use crate::fd::{FromRawFd, OwnedFd};
 use crate::types::*;
 use crate::error::{Error, Result, result};
 
+// ==============================================================================
+// Directory entry types of linux_dirent64.d_type (include/linux/fs_types.h)
+// ==============================================================================
+pub const DT_UNKNOWN: u8 = 0;
+pub const DT_FIFO:    u8 = 1;
+pub const DT_CHR:     u8 = 2;
+pub const DT_DIR:     u8 = 4;
+pub const DT_BLK:     u8 = 6;
+pub const DT_REG:     u8 = 8;
+pub const DT_LNK:     u8 = 10;
+pub const DT_SOCK:    u8 = 12;
+pub const DT_WHT:     u8 = 14;
+
+// ==============================================================================
+// Flags of renameat2 (include/uapi/linux/fs.h)
+// ==============================================================================
+pub const RENAME_NOREPLACE: u32 = 1 << 0;
+pub const RENAME_EXCHANGE:  u32 = 1 << 1;
+pub const RENAME_WHITEOUT:  u32 = 1 << 2;
+
+// ==============================================================================
+// Flags of the legacy mount (include/uapi/linux/mount.h)
+// ==============================================================================
+pub const MS_RDONLY:      u64 = 1 << 0;
+pub const MS_NOSUID:      u64 = 1 << 1;
+pub const MS_NODEV:       u64 = 1 << 2;
+pub const MS_NOEXEC:      u64 = 1 << 3;
+pub const MS_SYNCHRONOUS: u64 = 1 << 4;
+pub const MS_REMOUNT:     u64 = 1 << 5;
+pub const MS_MANDLOCK:    u64 = 1 << 6;
+pub const MS_DIRSYNC:     u64 = 1 << 7;
+pub const MS_NOSYMFOLLOW: u64 = 1 << 8;
+pub const MS_NOATIME:     u64 = 1 << 10;
+pub const MS_NODIRATIME:  u64 = 1 << 11;
+pub const MS_BIND:        u64 = 1 << 12;
+pub const MS_MOVE:        u64 = 1 << 13;
+pub const MS_REC:         u64 = 1 << 14;
+pub const MS_SILENT:      u64 = 1 << 15;
+pub const MS_POSIXACL:    u64 = 1 << 16;
+pub const MS_UNBINDABLE:  u64 = 1 << 17;
+pub const MS_PRIVATE:     u64 = 1 << 18;
+pub const MS_SLAVE:       u64 = 1 << 19;
+pub const MS_SHARED:      u64 = 1 << 20;
+pub const MS_RELATIME:    u64 = 1 << 21;
+pub const MS_STRICTATIME: u64 = 1 << 24;
+pub const MS_LAZYTIME:    u64 = 1 << 25;
+
+// Flags of umount
+pub const MNT_FORCE:       i32 = 1;
+pub const MNT_DETACH:      i32 = 2;
+pub const MNT_EXPIRE:      i32 = 4;
+pub const UMOUNT_NOFOLLOW: i32 = 8;
+
+// ==============================================================================
+// Flags of the mount API (include/uapi/linux/mount.h)
+// ==============================================================================
+pub const OPEN_TREE_CLONE:   u32 = 1;
+pub const OPEN_TREE_CLOEXEC: u32 = 0o2000000;
+
+pub const MOVE_MOUNT_F_SYMLINKS:   u32 = 0x00000001;
+pub const MOVE_MOUNT_F_AUTOMOUNTS: u32 = 0x00000002;
+pub const MOVE_MOUNT_F_EMPTY_PATH: u32 = 0x00000004;
+pub const MOVE_MOUNT_T_SYMLINKS:   u32 = 0x00000010;
+pub const MOVE_MOUNT_T_AUTOMOUNTS: u32 = 0x00000020;
+pub const MOVE_MOUNT_T_EMPTY_PATH: u32 = 0x00000040;
+pub const MOVE_MOUNT_SET_GROUP:    u32 = 0x00000100;
+pub const MOVE_MOUNT_BENEATH:      u32 = 0x00000200;
+
+pub const FSOPEN_CLOEXEC: u32 = 0x00000001;
+
+pub const FSPICK_CLOEXEC:          u32 = 0x00000001;
+pub const FSPICK_SYMLINK_NOFOLLOW: u32 = 0x00000002;
+pub const FSPICK_NO_AUTOMOUNT:     u32 = 0x00000004;
+pub const FSPICK_EMPTY_PATH:       u32 = 0x00000008;
+
+// Commands of fsconfig
+pub const FSCONFIG_SET_FLAG:        u32 = 0;
+pub const FSCONFIG_SET_STRING:      u32 = 1;
+pub const FSCONFIG_SET_BINARY:      u32 = 2;
+pub const FSCONFIG_SET_PATH:        u32 = 3;
+pub const FSCONFIG_SET_PATH_EMPTY:  u32 = 4;
+pub const FSCONFIG_SET_FD:          u32 = 5;
+pub const FSCONFIG_CMD_CREATE:      u32 = 6;
+pub const FSCONFIG_CMD_RECONFIGURE: u32 = 7;
+pub const FSCONFIG_CMD_CREATE_EXCL: u32 = 8;
+
+pub const FSMOUNT_CLOEXEC: u32 = 0x00000001;
+
+// Attributes of fsmount and mount_setattr
+pub const MOUNT_ATTR_RDONLY:      u64 = 0x00000001;
+pub const MOUNT_ATTR_NOSUID:      u64 = 0x00000002;
+pub const MOUNT_ATTR_NODEV:       u64 = 0x00000004;
+pub const MOUNT_ATTR_NOEXEC:      u64 = 0x00000008;
+pub const MOUNT_ATTR__ATIME:      u64 = 0x00000070;
+pub const MOUNT_ATTR_RELATIME:    u64 = 0x00000000;
+pub const MOUNT_ATTR_NOATIME:     u64 = 0x00000010;
+pub const MOUNT_ATTR_STRICTATIME: u64 = 0x00000020;
+pub const MOUNT_ATTR_NODIRATIME:  u64 = 0x00000080;
+pub const MOUNT_ATTR_IDMAP:       u64 = 0x00100000;
+pub const MOUNT_ATTR_NOSYMFOLLOW: u64 = 0x00200000;
+
+// Fields of statmount
+pub const STATMOUNT_SB_BASIC:       u64 = 0x00000001;
+pub const STATMOUNT_MNT_BASIC:      u64 = 0x00000002;
+pub const STATMOUNT_PROPAGATE_FROM: u64 = 0x00000004;
+pub const STATMOUNT_MNT_ROOT:       u64 = 0x00000008;
+pub const STATMOUNT_MNT_POINT:      u64 = 0x00000010;
+pub const STATMOUNT_FS_TYPE:        u64 = 0x00000020;
+pub const STATMOUNT_MNT_NS_ID:      u64 = 0x00000040;
+pub const STATMOUNT_MNT_OPTS:       u64 = 0x00000080;
+pub const STATMOUNT_FS_SUBTYPE:     u64 = 0x00000100;
+pub const STATMOUNT_SB_SOURCE:      u64 = 0x00000200;
+pub const STATMOUNT_OPT_ARRAY:      u64 = 0x00000400;
+pub const STATMOUNT_OPT_SEC_ARRAY:  u64 = 0x00000800;
+pub const STATMOUNT_SUPPORTED_MASK: u64 = 0x00001000;
+pub const STATMOUNT_MNT_UIDMAP:     u64 = 0x00002000;
+pub const STATMOUNT_MNT_GIDMAP:     u64 = 0x00004000;
+
+/// The mnt_id of listmount for the mounts below the root
+pub const LSMT_ROOT: u64 = 0xffffffffffffffff;
+pub const LISTMOUNT_REVERSE: u32 = 1 << 0;
+
+// ==============================================================================
+// Flags of setxattr (include/uapi/linux/xattr.h)
+// ==============================================================================
+pub const XATTR_CREATE:  i32 = 0x1;
+pub const XATTR_REPLACE: i32 = 0x2;
+
+// ==============================================================================
+// Special values of timespec.tv_nsec for utimensat (include/uapi/linux/stat.h)
+// ==============================================================================
+pub const UTIME_NOW:  i64 = (1 << 30) - 1;
+pub const UTIME_OMIT: i64 = (1 << 30) - 2;
+
+// ==============================================================================
+// Fields of statx (include/uapi/linux/stat.h)
+// ==============================================================================
+pub const STATX_TYPE:           u32 = 0x00000001;
+pub const STATX_MODE:           u32 = 0x00000002;
+pub const STATX_NLINK:          u32 = 0x00000004;
+pub const STATX_UID:            u32 = 0x00000008;
+pub const STATX_GID:            u32 = 0x00000010;
+pub const STATX_ATIME:          u32 = 0x00000020;
+pub const STATX_MTIME:          u32 = 0x00000040;
+pub const STATX_CTIME:          u32 = 0x00000080;
+pub const STATX_INO:            u32 = 0x00000100;
+pub const STATX_SIZE:           u32 = 0x00000200;
+pub const STATX_BLOCKS:         u32 = 0x00000400;
+pub const STATX_BASIC_STATS:    u32 = 0x000007ff;
+pub const STATX_BTIME:          u32 = 0x00000800;
+pub const STATX_MNT_ID:         u32 = 0x00001000;
+pub const STATX_DIOALIGN:       u32 = 0x00002000;
+pub const STATX_MNT_ID_UNIQUE:  u32 = 0x00004000;
+pub const STATX_SUBVOL:         u32 = 0x00008000;
+pub const STATX_WRITE_ATOMIC:   u32 = 0x00010000;
+pub const STATX_DIO_READ_ALIGN: u32 = 0x00020000;
+
+// Attributes of statx.stx_attributes
+pub const STATX_ATTR_COMPRESSED:   u64 = 0x00000004;
+pub const STATX_ATTR_IMMUTABLE:    u64 = 0x00000010;
+pub const STATX_ATTR_APPEND:       u64 = 0x00000020;
+pub const STATX_ATTR_NODUMP:       u64 = 0x00000040;
+pub const STATX_ATTR_ENCRYPTED:    u64 = 0x00000800;
+pub const STATX_ATTR_AUTOMOUNT:    u64 = 0x00001000;
+pub const STATX_ATTR_MOUNT_ROOT:   u64 = 0x00002000;
+pub const STATX_ATTR_VERITY:       u64 = 0x00100000;
+pub const STATX_ATTR_DAX:          u64 = 0x00200000;
+pub const STATX_ATTR_WRITE_ATOMIC: u64 = 0x00400000;
+
 #[no_mangle]
 pub fn access(path: &str, mode: i32) -> Result<()> {
     let mut dst = [0u8; crate::limits::PATH_MAX];
@@ -112,81 +284,98 @@ pub fn sysfs() -> Result<usize> {
 pub fn sync() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_SYNC) })
 }
+/// The legacy interface, `source` and `data` depend on the filesystem and the MS_* flags
 #[no_mangle]
-pub fn mount() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_MOUNT) })
+pub fn mount(source: Option<&CStr>, target: &CStr, fstype: Option<&CStr>, flags: u64, data: Option<&CStr>) -> Result<()> {
+    let ptr = |s: Option<&CStr>| s.map_or(core::ptr::null(), |s| s.as_ptr());
+    result(unsafe { syscall!(super::SYS_MOUNT, ptr(source), target.as_ptr(), ptr(fstype), flags, ptr(data)) }).map(|_| ())
 }
 
+/// umount2, `flags` are MNT_* and UMOUNT_NOFOLLOW
 #[no_mangle]
-pub fn umount() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_UMOUNT) })
+pub fn umount(target: &CStr, flags: i32) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_UMOUNT, target.as_ptr(), flags) }).map(|_| ())
 }
+/// Set the attribute `name` of the file, XATTR_CREATE or XATTR_REPLACE fail if it does or
+/// doesn't exist already
 #[no_mangle]
-pub fn setxattr() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_SETXATTR) })
+pub fn setxattr(path: &CStr, name: &CStr, value: &[u8], flags: i32) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_SETXATTR, path.as_ptr(), name.as_ptr(), value.as_ptr(), value.len(), flags) }).map(|_| ())
 }
 
+/// Like setxattr but on a symlink itself
 #[no_mangle]
-pub fn lsetxattr() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_LSETXATTR) })
+pub fn lsetxattr(path: &CStr, name: &CStr, value: &[u8], flags: i32) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_LSETXATTR, path.as_ptr(), name.as_ptr(), value.as_ptr(), value.len(), flags) }).map(|_| ())
 }
 
 #[no_mangle]
-pub fn fsetxattr() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_FSETXATTR) })
+pub fn fsetxattr(fd: u32, name: &CStr, value: &[u8], flags: i32) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_FSETXATTR, fd, name.as_ptr(), value.as_ptr(), value.len(), flags) }).map(|_| ())
 }
 
+/// Copy the value of the attribute into `value`, returns its length. An empty `value` returns
+/// the length without copying, a value which doesn't fit fails with ERANGE.
 #[no_mangle]
-pub fn getxattr() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_GETXATTR) })
+pub fn getxattr(path: &CStr, name: &CStr, value: &mut [u8]) -> Result<usize> {
+    result(unsafe { syscall!(super::SYS_GETXATTR, path.as_ptr(), name.as_ptr(), value.as_mut_ptr(), value.len()) })
 }
 
+/// Like getxattr but on a symlink itself
 #[no_mangle]
-pub fn lgetxattr() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_LGETXATTR) })
+pub fn lgetxattr(path: &CStr, name: &CStr, value: &mut [u8]) -> Result<usize> {
+    result(unsafe { syscall!(super::SYS_LGETXATTR, path.as_ptr(), name.as_ptr(), value.as_mut_ptr(), value.len()) })
 }
 
 #[no_mangle]
-pub fn fgetxattr() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_FGETXATTR) })
+pub fn fgetxattr(fd: u32, name: &CStr, value: &mut [u8]) -> Result<usize> {
+    result(unsafe { syscall!(super::SYS_FGETXATTR, fd, name.as_ptr(), value.as_mut_ptr(), value.len()) })
 }
 
+/// Copy the nul terminated names of the attributes into `list`, returns the number of bytes.
+/// An empty `list` returns the size needed, a list which doesn't fit fails with ERANGE.
 #[no_mangle]
-pub fn listxattr() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_LISTXATTR) })
+pub fn listxattr(path: &CStr, list: &mut [u8]) -> Result<usize> {
+    result(unsafe { syscall!(super::SYS_LISTXATTR, path.as_ptr(), list.as_mut_ptr(), list.len()) })
 }
 
+/// Like listxattr but on a symlink itself
 #[no_mangle]
-pub fn llistxattr() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_LLISTXATTR) })
+pub fn llistxattr(path: &CStr, list: &mut [u8]) -> Result<usize> {
+    result(unsafe { syscall!(super::SYS_LLISTXATTR, path.as_ptr(), list.as_mut_ptr(), list.len()) })
 }
 
 #[no_mangle]
-pub fn flistxattr() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_FLISTXATTR) })
+pub fn flistxattr(fd: u32, list: &mut [u8]) -> Result<usize> {
+    result(unsafe { syscall!(super::SYS_FLISTXATTR, fd, list.as_mut_ptr(), list.len()) })
 }
 
 #[no_mangle]
-pub fn removexattr() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_REMOVEXATTR) })
+pub fn removexattr(path: &CStr, name: &CStr) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_REMOVEXATTR, path.as_ptr(), name.as_ptr()) }).map(|_| ())
 }
 
+/// Like removexattr but on a symlink itself
 #[no_mangle]
-pub fn lremovexattr() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_LREMOVEXATTR) })
+pub fn lremovexattr(path: &CStr, name: &CStr) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_LREMOVEXATTR, path.as_ptr(), name.as_ptr()) }).map(|_| ())
 }
 
 #[no_mangle]
-pub fn fremovexattr() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_FREMOVEXATTR) })
+pub fn fremovexattr(fd: u32, name: &CStr) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_FREMOVEXATTR, fd, name.as_ptr()) }).map(|_| ())
 }
+
+/// Fill `buf` with linux_dirent64 records of the directory, returns the number of bytes
+/// written or 0 at the end of the directory. Fails with EINVAL when the buffer is too small
+/// for the next record.
 #[no_mangle]
-pub fn getdents64() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_GETDENTS64) })
+pub fn getdents64(fd: u32, buf: &mut [u8]) -> Result<usize> {
+    result(unsafe { syscall!(super::SYS_GETDENTS64, fd, buf.as_mut_ptr(), buf.len()) })
 }
 #[no_mangle]
-pub fn mkdirat() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_MKDIRAT) })
+pub fn mkdirat(dirfd: i32, path: &CStr, mode: mode_t) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_MKDIRAT, dirfd, path.as_ptr(), mode) }).map(|_| ())
 }
 
 #[no_mangle]
@@ -204,110 +393,154 @@ pub fn futimesat() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_FUTIMESAT) })
 }
 
+/// `flags` are AT_SYMLINK_NOFOLLOW, AT_NO_AUTOMOUNT and AT_EMPTY_PATH to stat `dirfd` itself
 #[no_mangle]
-pub fn newfstatat() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_NEWFSTATAT) })
+pub fn newfstatat(dirfd: i32, path: &CStr, stat: &mut stat64, flags: i32) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_NEWFSTATAT, dirfd, path.as_ptr(), stat as *mut stat64, flags) }).map(|_| ())
 }
 
+/// Removes a directory instead of a file with AT_REMOVEDIR
 #[no_mangle]
-pub fn unlinkat() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_UNLINKAT) })
+pub fn unlinkat(dirfd: i32, path: &CStr, flags: i32) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_UNLINKAT, dirfd, path.as_ptr(), flags) }).map(|_| ())
 }
 
 #[no_mangle]
-pub fn renameat() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_RENAMEAT) })
+pub fn renameat(olddirfd: i32, oldpath: &CStr, newdirfd: i32, newpath: &CStr) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_RENAMEAT, olddirfd, oldpath.as_ptr(), newdirfd, newpath.as_ptr()) }).map(|_| ())
 }
 
+/// A symlink in `oldpath` is linked itself unless AT_SYMLINK_FOLLOW is set
 #[no_mangle]
-pub fn linkat() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_LINKAT) })
+pub fn linkat(olddirfd: i32, oldpath: &CStr, newdirfd: i32, newpath: &CStr, flags: i32) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_LINKAT, olddirfd, oldpath.as_ptr(), newdirfd, newpath.as_ptr(), flags) }).map(|_| ())
 }
 
+/// Create `linkpath` pointing to `target`, the target is not resolved
 #[no_mangle]
-pub fn symlinkat() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_SYMLINKAT) })
+pub fn symlinkat(target: &CStr, newdirfd: i32, linkpath: &CStr) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_SYMLINKAT, target.as_ptr(), newdirfd, linkpath.as_ptr()) }).map(|_| ())
 }
 
+/// Returns the length of the target written into `buf`, it is not nul terminated and it
+/// is truncated when the buffer is too small
 #[no_mangle]
-pub fn readlinkat() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_READLINKAT) })
+pub fn readlinkat(dirfd: i32, path: &CStr, buf: &mut [u8]) -> Result<usize> {
+    result(unsafe { syscall!(super::SYS_READLINKAT, dirfd, path.as_ptr(), buf.as_mut_ptr(), buf.len()) })
 }
 
+/// Always follows a symlink in `path`, see fchmodat2 for the flags
 #[no_mangle]
-pub fn fchmodat() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_FCHMODAT) })
+pub fn fchmodat(dirfd: i32, path: &CStr, mode: mode_t) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_FCHMODAT, dirfd, path.as_ptr(), mode) }).map(|_| ())
 }
 
+/// Checks with the real ids of the process, see faccessat2 for the flags
 #[no_mangle]
-pub fn faccessat() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_FACCESSAT) })
+pub fn faccessat(dirfd: i32, path: &CStr, mode: i32) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_FACCESSAT, dirfd, path.as_ptr(), mode) }).map(|_| ())
 }
 
+/// Set the access and modification times in `times`, to the current time if it is None.
+/// A path of None changes `dirfd` itself.
 #[no_mangle]
-pub fn utimensat() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_UTIMENSAT) })
+pub fn utimensat(dirfd: i32, path: Option<&CStr>, times: Option<&[timespec; 2]>, flags: i32) -> Result<()> {
+    let path = path.map_or(core::ptr::null(), |p| p.as_ptr());
+    let times = times.map_or(core::ptr::null(), |t| t.as_ptr());
+    result(unsafe { syscall!(super::SYS_UTIMENSAT, dirfd, path, times, flags) }).map(|_| ())
 }
 #[no_mangle]
 pub fn syncfs() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_SYNCFS) })
 }
+/// renameat with RENAME_* flags
 #[no_mangle]
-pub fn renameat2() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_RENAMEAT2) })
+pub fn renameat2(olddirfd: i32, oldpath: &CStr, newdirfd: i32, newpath: &CStr, flags: u32) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_RENAMEAT2, olddirfd, oldpath.as_ptr(), newdirfd, newpath.as_ptr(), flags) }).map(|_| ())
 }
+/// Fill `buf` with the STATX_* fields in `mask`, stx_mask tells which ones the filesystem
+/// provides. `flags` are the ones of newfstatat and AT_STATX_* for network filesystems.
 #[no_mangle]
-pub fn statx() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_STATX) })
+pub fn statx(dirfd: i32, path: &CStr, flags: i32, mask: u32, buf: &mut statx) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_STATX, dirfd, path.as_ptr(), flags, mask, buf as *mut statx) }).map(|_| ())
 }
+/// Open the mount at `path`, with OPEN_TREE_CLONE a detached copy of it which is a bind
+/// mount once it is attached with move_mount. AT_RECURSIVE copies the submounts too.
 #[no_mangle]
-pub fn open_tree() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_OPEN_TREE) })
+pub fn open_tree(dirfd: i32, path: &CStr, flags: u32) -> Result<OwnedFd> {
+    result(unsafe { syscall!(super::SYS_OPEN_TREE, dirfd, path.as_ptr(), flags) })
+        .map(|fd| unsafe { OwnedFd::from_raw_fd(fd as u32) })
 }
 
+/// Attach or move a mount, `flags` are MOVE_MOUNT_*
 #[no_mangle]
-pub fn move_mount() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_MOVE_MOUNT) })
+pub fn move_mount(from_dirfd: i32, from_path: &CStr, to_dirfd: i32, to_path: &CStr, flags: u32) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_MOVE_MOUNT, from_dirfd, from_path.as_ptr(), to_dirfd, to_path.as_ptr(), flags) }).map(|_| ())
 }
 
+/// Create a filesystem context which is configured with fsconfig
 #[no_mangle]
-pub fn fsopen() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_FSOPEN) })
+pub fn fsopen(fstype: &CStr, flags: u32) -> Result<OwnedFd> {
+    result(unsafe { syscall!(super::SYS_FSOPEN, fstype.as_ptr(), flags) })
+        .map(|fd| unsafe { OwnedFd::from_raw_fd(fd as u32) })
 }
 
+/// Set a parameter of the context or run a FSCONFIG_CMD_*. What `value` and `aux` are depends
+/// on `cmd`: a string, a buffer of `aux` bytes, a path relative to the descriptor `aux` or
+/// nothing and the descriptor `aux`.
+///
+/// # Safety
+/// `value` has to be what `cmd` expects
 #[no_mangle]
-pub fn fsconfig() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_FSCONFIG) })
+pub unsafe fn fsconfig(fd: u32, cmd: u32, key: Option<&CStr>, value: *const u8, aux: i32) -> Result<()> {
+    let key = key.map_or(core::ptr::null(), |key| key.as_ptr());
+    result(syscall!(super::SYS_FSCONFIG, fd, cmd, key, value, aux)).map(|_| ())
 }
 
+/// Create a detached mount of the superblock of the context, `attr` are MOUNT_ATTR_*
 #[no_mangle]
-pub fn fsmount() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_FSMOUNT) })
+pub fn fsmount(fd: u32, flags: u32, attr: u32) -> Result<OwnedFd> {
+    result(unsafe { syscall!(super::SYS_FSMOUNT, fd, flags, attr) })
+        .map(|fd| unsafe { OwnedFd::from_raw_fd(fd as u32) })
 }
 
+/// Create a context to reconfigure the superblock of the mount at `path`
 #[no_mangle]
-pub fn fspick() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_FSPICK) })
+pub fn fspick(dirfd: i32, path: &CStr, flags: u32) -> Result<OwnedFd> {
+    result(unsafe { syscall!(super::SYS_FSPICK, dirfd, path.as_ptr(), flags) })
+        .map(|fd| unsafe { OwnedFd::from_raw_fd(fd as u32) })
 }
+/// `flags` are AT_EACCESS to check with the effective ids, AT_SYMLINK_NOFOLLOW and
+/// AT_EMPTY_PATH to check `dirfd` itself
 #[no_mangle]
-pub fn faccessat2() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_FACCESSAT2) })
+pub fn faccessat2(dirfd: i32, path: &CStr, mode: i32, flags: i32) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_FACCESSAT2, dirfd, path.as_ptr(), mode, flags) }).map(|_| ())
 }
 
+/// Change the attributes of the mount at `path`, of all submounts too with AT_RECURSIVE
 #[no_mangle]
-pub fn mount_setattr() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_MOUNT_SETATTR) })
+pub fn mount_setattr(dirfd: i32, path: &CStr, flags: u32, attr: &mount_attr) -> Result<()> {
+    let size = core::mem::size_of::<mount_attr>();
+    result(unsafe { syscall!(super::SYS_MOUNT_SETATTR, dirfd, path.as_ptr(), flags, attr as *const mount_attr, size) }).map(|_| ())
 }
+/// `flags` are AT_SYMLINK_NOFOLLOW and AT_EMPTY_PATH to change `dirfd` itself, since Linux 6.6
 #[no_mangle]
-pub fn fchmodat2() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_FCHMODAT2) })
+pub fn fchmodat2(dirfd: i32, path: &CStr, mode: mode_t, flags: i32) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_FCHMODAT2, dirfd, path.as_ptr(), mode, flags) }).map(|_| ())
 }
+/// Fill `buf` with the STATMOUNT_* fields in `req.param` of the mount `req.mnt_id`. The
+/// strings follow the struct, EOVERFLOW when they don't fit into `size` bytes.
+///
+/// # Safety
+/// `buf` has to be valid for `size` bytes
 #[no_mangle]
-pub fn statmount() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_STATMOUNT) })
+pub unsafe fn statmount(req: &mnt_id_req, buf: *mut statmount, size: usize, flags: u32) -> Result<()> {
+    result(syscall!(super::SYS_STATMOUNT, req as *const mnt_id_req, buf, size, flags)).map(|_| ())
 }
 
+/// Fill `ids` with the unique ids of the mounts below `req.mnt_id` which come after the id
+/// `req.param`, returns the number of ids
 #[no_mangle]
-pub fn listmount() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_LISTMOUNT) })
+pub fn listmount(req: &mnt_id_req, ids: &mut [u64], flags: u32) -> Result<usize> {
+    result(unsafe { syscall!(super::SYS_LISTMOUNT, req as *const mnt_id_req, ids.as_mut_ptr(), ids.len(), flags) })
 }
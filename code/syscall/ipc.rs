@@ -1,10 +1,12 @@
 use crate::types::*;
+use crate::fd::{FromRawFd, OwnedFd};
 use crate::error::{Error, Result, result};
 
 #[no_mangle]
-pub fn pipe() -> Result<(u32, u32)> {
-    let fds = [0u32; 2];
-    result(unsafe { syscall!(super::SYS_PIPE, fds.as_ptr()) }).map(|_| (fds[0], fds[1]))
+pub fn pipe() -> Result<(OwnedFd, OwnedFd)> {
+    let mut fds = [0u32; 2];
+    result(unsafe { syscall!(super::SYS_PIPE, fds.as_mut_ptr()) })
+        .map(|_| unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) })
 }
 #[no_mangle]
 pub fn shmget() -> Result<usize> {
@@ -88,6 +90,8 @@ pub fn vmsplice() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_VMSPLICE) })
 }
 #[no_mangle]
-pub fn pipe2() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_PIPE2) })
+pub fn pipe2(flags: i32) -> Result<(OwnedFd, OwnedFd)> {
+    let mut fds = [0u32; 2];
+    result(unsafe { syscall!(super::SYS_PIPE2, fds.as_mut_ptr(), flags) })
+        .map(|_| unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) })
 }
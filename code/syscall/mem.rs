@@ -2,6 +2,7 @@
 
 use crate::cstr;
 use crate::types::*;
+use crate::fd::{FromRawFd, OwnedFd};
 use crate::constants::*; // For docs links
 use crate::error::{Error, Result, result};
 use crate::error::Error::*;
@@ -88,6 +89,41 @@ pub const MREMAP_FIXED:     i32 = 2;
 pub const MREMAP_DONTUNMAP: i32 = 4;
 
 
+// ==============================================================================
+// Memory advice (include/uapi/asm-generic/mman-common.h)
+// ==============================================================================
+pub const MADV_NORMAL:         i32 = 0;
+pub const MADV_RANDOM:         i32 = 1;
+pub const MADV_SEQUENTIAL:     i32 = 2;
+pub const MADV_WILLNEED:       i32 = 3;
+pub const MADV_DONTNEED:       i32 = 4;
+pub const MADV_FREE:           i32 = 8;
+pub const MADV_REMOVE:         i32 = 9;
+pub const MADV_DONTFORK:       i32 = 10;
+pub const MADV_DOFORK:         i32 = 11;
+pub const MADV_MERGEABLE:      i32 = 12;
+pub const MADV_UNMERGEABLE:    i32 = 13;
+pub const MADV_HUGEPAGE:       i32 = 14;
+pub const MADV_NOHUGEPAGE:     i32 = 15;
+pub const MADV_DONTDUMP:       i32 = 16;
+pub const MADV_DODUMP:         i32 = 17;
+pub const MADV_WIPEONFORK:     i32 = 18;
+pub const MADV_KEEPONFORK:     i32 = 19;
+pub const MADV_COLD:           i32 = 20;
+pub const MADV_PAGEOUT:        i32 = 21;
+pub const MADV_POPULATE_READ:  i32 = 22;
+pub const MADV_POPULATE_WRITE: i32 = 23;
+
+// ==============================================================================
+// Memfd flags (include/uapi/linux/memfd.h)
+// ==============================================================================
+pub const MFD_CLOEXEC:       u32 = 0x0001;
+pub const MFD_ALLOW_SEALING: u32 = 0x0002;
+pub const MFD_HUGETLB:       u32 = 0x0004;
+pub const MFD_NOEXEC_SEAL:   u32 = 0x0008;
+pub const MFD_EXEC:          u32 = 0x0010;
+
+
 
 #[no_mangle]
 #[doc = include_str!(".mem/mmap.md")]
@@ -130,8 +166,8 @@ pub fn mincore() -> Result<usize> {
 }
 
 #[no_mangle]
-pub fn madvise() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_MADVISE) })
+pub unsafe fn madvise(addr: *mut u8, len: usize, advice: i32) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_MADVISE, addr, len, advice) }).map(|_| ())
 }
 
 #[no_mangle]
@@ -194,18 +230,28 @@ pub fn move_pages() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_MOVE_PAGES) })
 }
 
+/// Copy from the buffers `remote` in the process `pid` into the buffers `local` of the
+/// caller. It stops at the first remote address which isn't mapped and returns the number
+/// of bytes copied so far.
+///
+/// # Safety
+/// The `local` buffers have to be valid for writes
 #[no_mangle]
-pub fn process_vm_readv() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_PROCESS_VM_READV) })
+pub unsafe fn process_vm_readv(pid: pid_t, local: &[iovec], remote: &[iovec], flags: u64) -> Result<usize> {
+    result(syscall!(super::SYS_PROCESS_VM_READV, pid, local.as_ptr(), local.len(), remote.as_ptr(), remote.len(), flags))
 }
 
+/// Copy the buffers `local` of the caller into the buffers `remote` in the process `pid`
+///
+/// # Safety
+/// The `local` buffers have to be valid for reads
 #[no_mangle]
-pub fn process_vm_writev() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_PROCESS_VM_WRITEV) })
+pub unsafe fn process_vm_writev(pid: pid_t, local: &[iovec], remote: &[iovec], flags: u64) -> Result<usize> {
+    result(syscall!(super::SYS_PROCESS_VM_WRITEV, pid, local.as_ptr(), local.len(), remote.as_ptr(), remote.len(), flags))
 }
 #[no_mangle]
-pub fn memfd_create() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_MEMFD_CREATE) })
+pub fn memfd_create(name: &core::ffi::CStr, flags: u32) -> Result<OwnedFd> {
+    result(unsafe { syscall!(super::SYS_MEMFD_CREATE, name.as_ptr(), flags) }).map(|fd| unsafe { OwnedFd::from_raw_fd(fd as u32) })
 }
 
 #[no_mangle]
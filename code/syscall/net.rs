@@ -1,84 +1,191 @@
 use crate::types::*;
+use crate::fd::{FromRawFd, OwnedFd};
 use crate::error::{Error, Result, result};
 
+// ==============================================================================
+// Address families (include/linux/socket.h)
+// ==============================================================================
+pub const AF_UNSPEC:  i32 = 0;
+pub const AF_UNIX:    i32 = 1;
+pub const AF_LOCAL:   i32 = AF_UNIX;
+pub const AF_INET:    i32 = 2;
+pub const AF_INET6:   i32 = 10;
+pub const AF_NETLINK: i32 = 16;
+pub const AF_PACKET:  i32 = 17;
+
+// ==============================================================================
+// Socket types (include/linux/net.h)
+// ==============================================================================
+pub const SOCK_STREAM:    i32 = 1;
+pub const SOCK_DGRAM:     i32 = 2;
+pub const SOCK_RAW:       i32 = 3;
+pub const SOCK_SEQPACKET: i32 = 5;
+pub const SOCK_NONBLOCK:  i32 = 0o0004000;
+pub const SOCK_CLOEXEC:   i32 = 0o2000000;
+
+// ==============================================================================
+// Protocols (include/uapi/linux/in.h)
+// ==============================================================================
+pub const IPPROTO_IP:   i32 = 0;
+pub const IPPROTO_ICMP: i32 = 1;
+pub const IPPROTO_TCP:  i32 = 6;
+pub const IPPROTO_UDP:  i32 = 17;
+pub const IPPROTO_IPV6: i32 = 41;
+
+// ==============================================================================
+// Socket options (include/uapi/asm-generic/socket.h, include/uapi/linux/tcp.h)
+// ==============================================================================
+pub const SOL_SOCKET:   i32 = 1;
+
+pub const SO_DEBUG:     i32 = 1;
+pub const SO_REUSEADDR: i32 = 2;
+pub const SO_TYPE:      i32 = 3;
+pub const SO_ERROR:     i32 = 4;
+pub const SO_BROADCAST: i32 = 6;
+pub const SO_SNDBUF:    i32 = 7;
+pub const SO_RCVBUF:    i32 = 8;
+pub const SO_KEEPALIVE: i32 = 9;
+pub const SO_LINGER:    i32 = 13;
+pub const SO_REUSEPORT: i32 = 15;
+pub const SO_PEERCRED:  i32 = 17;
+pub const SO_RCVTIMEO:  i32 = 20;
+pub const SO_SNDTIMEO:  i32 = 21;
+
+pub const TCP_NODELAY:  i32 = 1;
+pub const IPV6_V6ONLY:  i32 = 26;
+
+// ==============================================================================
+// Message flags (include/linux/socket.h)
+// ==============================================================================
+pub const MSG_OOB:          i32 = 0x00000001;
+pub const MSG_PEEK:         i32 = 0x00000002;
+pub const MSG_DONTROUTE:    i32 = 0x00000004;
+pub const MSG_TRUNC:        i32 = 0x00000020;
+pub const MSG_DONTWAIT:     i32 = 0x00000040;
+pub const MSG_WAITALL:      i32 = 0x00000100;
+pub const MSG_NOSIGNAL:     i32 = 0x00004000;
+pub const MSG_CMSG_CLOEXEC: i32 = 0x40000000;
+
+// ==============================================================================
+// Shutdown (include/linux/net.h)
+// ==============================================================================
+pub const SHUT_RD:   i32 = 0;
+pub const SHUT_WR:   i32 = 1;
+pub const SHUT_RDWR: i32 = 2;
+
 #[no_mangle]
-pub fn socket() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_SOCKET) })
+pub fn socket(domain: i32, ty: i32, protocol: i32) -> Result<OwnedFd> {
+    result(unsafe { syscall!(super::SYS_SOCKET, domain, ty, protocol) }).map(|fd| unsafe { OwnedFd::from_raw_fd(fd as u32) })
 }
 
 #[no_mangle]
-pub fn connect() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_CONNECT) })
+pub fn connect(fd: u32, addr: &sockaddr_storage, len: socklen_t) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_CONNECT, fd, addr as *const sockaddr_storage, len) }).map(|_| ())
 }
 
+/// Returns the new socket and the length of the peer address written into `addr`
 #[no_mangle]
-pub fn accept() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_ACCEPT) })
+pub fn accept(fd: u32, addr: &mut sockaddr_storage) -> Result<(OwnedFd, socklen_t)> {
+    let mut len = core::mem::size_of::<sockaddr_storage>() as socklen_t;
+    result(unsafe { syscall!(super::SYS_ACCEPT, fd, addr as *mut sockaddr_storage, &mut len as *mut socklen_t) })
+        .map(|fd| (unsafe { OwnedFd::from_raw_fd(fd as u32) }, len))
 }
 
+/// Send to `addr` or to the connected peer if it is None
 #[no_mangle]
-pub fn sendto() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_SENDTO) })
+pub fn sendto(fd: u32, buf: &[u8], flags: i32, addr: Option<(&sockaddr_storage, socklen_t)>) -> Result<usize> {
+    let (addr, len) = match addr {
+        Some((addr, len)) => (addr as *const sockaddr_storage, len),
+        None => (core::ptr::null(), 0),
+    };
+    result(unsafe { syscall!(super::SYS_SENDTO, fd, buf.as_ptr(), buf.len(), flags, addr, len) })
 }
 
-#[no_mangle]
-pub fn recvfrom() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_RECVFROM) })
+/// Returns the number of bytes received and the length of the sender address written into `addr`
+#[no_mangle]
+pub fn recvfrom(fd: u32, buf: &mut [u8], flags: i32, addr: Option<&mut sockaddr_storage>) -> Result<(usize, socklen_t)> {
+    let mut len = core::mem::size_of::<sockaddr_storage>() as socklen_t;
+    let (addr, plen) = match addr {
+        Some(addr) => (addr as *mut sockaddr_storage, &mut len as *mut socklen_t),
+        None => (core::ptr::null_mut(), core::ptr::null_mut()),
+    };
+    let n = result(unsafe { syscall!(super::SYS_RECVFROM, fd, buf.as_mut_ptr(), buf.len(), flags, addr, plen) })?;
+    match plen.is_null() {
+        true => Ok((n, 0)),
+        false => Ok((n, len)),
+    }
 }
 
+/// The buffers referenced by the message must stay valid during the call
 #[no_mangle]
-pub fn sendmsg() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_SENDMSG) })
+pub unsafe fn sendmsg(fd: u32, msg: &msghdr, flags: i32) -> Result<usize> {
+    result(syscall!(super::SYS_SENDMSG, fd, msg as *const msghdr, flags))
 }
 
+/// The buffers referenced by the message must stay valid during the call
 #[no_mangle]
-pub fn recvmsg() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_RECVMSG) })
+pub unsafe fn recvmsg(fd: u32, msg: &mut msghdr, flags: i32) -> Result<usize> {
+    result(syscall!(super::SYS_RECVMSG, fd, msg as *mut msghdr, flags))
 }
 
 #[no_mangle]
-pub fn shutdown() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_SHUTDOWN) })
+pub fn shutdown(fd: u32, how: i32) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_SHUTDOWN, fd, how) }).map(|_| ())
 }
 
 #[no_mangle]
-pub fn bind() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_BIND) })
+pub fn bind(fd: u32, addr: &sockaddr_storage, len: socklen_t) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_BIND, fd, addr as *const sockaddr_storage, len) }).map(|_| ())
 }
 
 #[no_mangle]
-pub fn listen() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_LISTEN) })
+pub fn listen(fd: u32, backlog: i32) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_LISTEN, fd, backlog) }).map(|_| ())
 }
 
+/// Returns the length of the address written into `addr`
 #[no_mangle]
-pub fn getsockname() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_GETSOCKNAME) })
+pub fn getsockname(fd: u32, addr: &mut sockaddr_storage) -> Result<socklen_t> {
+    let mut len = core::mem::size_of::<sockaddr_storage>() as socklen_t;
+    result(unsafe { syscall!(super::SYS_GETSOCKNAME, fd, addr as *mut sockaddr_storage, &mut len as *mut socklen_t) })
+        .map(|_| len)
 }
 
+/// Returns the length of the address written into `addr`
 #[no_mangle]
-pub fn getpeername() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_GETPEERNAME) })
+pub fn getpeername(fd: u32, addr: &mut sockaddr_storage) -> Result<socklen_t> {
+    let mut len = core::mem::size_of::<sockaddr_storage>() as socklen_t;
+    result(unsafe { syscall!(super::SYS_GETPEERNAME, fd, addr as *mut sockaddr_storage, &mut len as *mut socklen_t) })
+        .map(|_| len)
 }
 
 #[no_mangle]
-pub fn socketpair() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_SOCKETPAIR) })
+pub fn socketpair(domain: i32, ty: i32, protocol: i32) -> Result<(OwnedFd, OwnedFd)> {
+    let mut fds = [0u32; 2];
+    result(unsafe { syscall!(super::SYS_SOCKETPAIR, domain, ty, protocol, fds.as_mut_ptr()) })
+        .map(|_| unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) })
 }
 
 #[no_mangle]
-pub fn setsockopt() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_SETSOCKOPT) })
+pub fn setsockopt(fd: u32, level: i32, name: i32, val: &[u8]) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_SETSOCKOPT, fd, level, name, val.as_ptr(), val.len()) }).map(|_| ())
 }
 
+/// Returns the length of the option written into `val`
 #[no_mangle]
-pub fn getsockopt() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_GETSOCKOPT) })
+pub fn getsockopt(fd: u32, level: i32, name: i32, val: &mut [u8]) -> Result<socklen_t> {
+    let mut len = val.len() as socklen_t;
+    result(unsafe { syscall!(super::SYS_GETSOCKOPT, fd, level, name, val.as_mut_ptr(), &mut len as *mut socklen_t) })
+        .map(|_| len)
 }
+
 #[no_mangle]
-pub fn accept4() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_ACCEPT4) })
+pub fn accept4(fd: u32, addr: &mut sockaddr_storage, flags: i32) -> Result<(OwnedFd, socklen_t)> {
+    let mut len = core::mem::size_of::<sockaddr_storage>() as socklen_t;
+    result(unsafe { syscall!(super::SYS_ACCEPT4, fd, addr as *mut sockaddr_storage, &mut len as *mut socklen_t, flags) })
+        .map(|fd| (unsafe { OwnedFd::from_raw_fd(fd as u32) }, len))
 }
+
 #[no_mangle]
 pub fn recvmmsg() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_RECVMMSG) })
@@ -1,24 +1,177 @@
+use crate::cstr;
 use crate::types::*;
+use crate::fd::{FromRawFd, OwnedFd};
 use crate::error::{Error, Result, result};
 
+// ==============================================================================
+// Wait options (include/uapi/linux/wait.h)
+// ==============================================================================
+pub const WNOHANG:     i32 = 0x00000001;
+pub const WUNTRACED:   i32 = 0x00000002;
+pub const WSTOPPED:    i32 = WUNTRACED;
+pub const WEXITED:     i32 = 0x00000004;
+pub const WCONTINUED:  i32 = 0x00000008;
+pub const WNOWAIT:     i32 = 0x01000000;
+pub const __WNOTHREAD: i32 = 0x20000000;
+pub const __WALL:      i32 = 0x40000000;
+pub const __WCLONE:    i32 = 0x80000000u32 as i32;
+
+// waitid() id types
+pub const P_ALL:   i32 = 0;
+pub const P_PID:   i32 = 1;
+pub const P_PGID:  i32 = 2;
+pub const P_PIDFD: i32 = 3;
+
+// pidfd_open() flags (include/uapi/linux/pidfd.h)
+pub const PIDFD_NONBLOCK: u32 = 0o0004000;
+
+// ==============================================================================
+// Clone flags (include/uapi/linux/sched.h)
+// ==============================================================================
+pub const CSIGNAL:              u64 = 0x000000ff;
+pub const CLONE_NEWTIME:        u64 = 0x00000080;
+pub const CLONE_VM:             u64 = 0x00000100;
+pub const CLONE_FS:             u64 = 0x00000200;
+pub const CLONE_FILES:          u64 = 0x00000400;
+pub const CLONE_SIGHAND:        u64 = 0x00000800;
+pub const CLONE_PIDFD:          u64 = 0x00001000;
+pub const CLONE_PTRACE:         u64 = 0x00002000;
+pub const CLONE_VFORK:          u64 = 0x00004000;
+pub const CLONE_PARENT:         u64 = 0x00008000;
+pub const CLONE_THREAD:         u64 = 0x00010000;
+pub const CLONE_NEWNS:          u64 = 0x00020000;
+pub const CLONE_SYSVSEM:        u64 = 0x00040000;
+pub const CLONE_SETTLS:         u64 = 0x00080000;
+pub const CLONE_PARENT_SETTID:  u64 = 0x00100000;
+pub const CLONE_CHILD_CLEARTID: u64 = 0x00200000;
+pub const CLONE_DETACHED:       u64 = 0x00400000;
+pub const CLONE_UNTRACED:       u64 = 0x00800000;
+pub const CLONE_CHILD_SETTID:   u64 = 0x01000000;
+pub const CLONE_NEWCGROUP:      u64 = 0x02000000;
+pub const CLONE_NEWUTS:         u64 = 0x04000000;
+pub const CLONE_NEWIPC:         u64 = 0x08000000;
+pub const CLONE_NEWUSER:        u64 = 0x10000000;
+pub const CLONE_NEWPID:         u64 = 0x20000000;
+pub const CLONE_NEWNET:         u64 = 0x40000000;
+pub const CLONE_IO:             u64 = 0x80000000;
+pub const CLONE_CLEAR_SIGHAND:  u64 = 0x100000000;
+pub const CLONE_INTO_CGROUP:    u64 = 0x200000000;
+
+// ==============================================================================
+// Ptrace requests (include/uapi/linux/ptrace.h)
+// ==============================================================================
+pub const PTRACE_TRACEME:           i32 = 0;
+pub const PTRACE_PEEKTEXT:          i32 = 1;
+pub const PTRACE_PEEKDATA:          i32 = 2;
+pub const PTRACE_PEEKUSR:           i32 = 3;
+pub const PTRACE_POKETEXT:          i32 = 4;
+pub const PTRACE_POKEDATA:          i32 = 5;
+pub const PTRACE_POKEUSR:           i32 = 6;
+pub const PTRACE_CONT:              i32 = 7;
+pub const PTRACE_KILL:              i32 = 8;
+pub const PTRACE_SINGLESTEP:        i32 = 9;
+pub const PTRACE_GETREGS:           i32 = 12;
+pub const PTRACE_SETREGS:           i32 = 13;
+pub const PTRACE_ATTACH:            i32 = 16;
+pub const PTRACE_DETACH:            i32 = 17;
+pub const PTRACE_SYSCALL:           i32 = 24;
+pub const PTRACE_SETOPTIONS:        i32 = 0x4200;
+pub const PTRACE_GETEVENTMSG:       i32 = 0x4201;
+pub const PTRACE_GETSIGINFO:        i32 = 0x4202;
+pub const PTRACE_SETSIGINFO:        i32 = 0x4203;
+pub const PTRACE_SEIZE:             i32 = 0x4206;
+pub const PTRACE_INTERRUPT:         i32 = 0x4207;
+pub const PTRACE_LISTEN:            i32 = 0x4208;
+pub const PTRACE_GET_SYSCALL_INFO:  i32 = 0x420e;
+
+// Options of PTRACE_SETOPTIONS and PTRACE_SEIZE
+pub const PTRACE_O_TRACESYSGOOD:    u64 = 0x00000001;
+pub const PTRACE_O_TRACEFORK:       u64 = 0x00000002;
+pub const PTRACE_O_TRACEVFORK:      u64 = 0x00000004;
+pub const PTRACE_O_TRACECLONE:      u64 = 0x00000008;
+pub const PTRACE_O_TRACEEXEC:       u64 = 0x00000010;
+pub const PTRACE_O_TRACEVFORKDONE:  u64 = 0x00000020;
+pub const PTRACE_O_TRACEEXIT:       u64 = 0x00000040;
+pub const PTRACE_O_TRACESECCOMP:    u64 = 0x00000080;
+pub const PTRACE_O_EXITKILL:        u64 = 0x00100000;
+pub const PTRACE_O_SUSPEND_SECCOMP: u64 = 0x00200000;
+
+// Events in bits 16..24 of the wait status
+pub const PTRACE_EVENT_FORK:       i32 = 1;
+pub const PTRACE_EVENT_VFORK:      i32 = 2;
+pub const PTRACE_EVENT_CLONE:      i32 = 3;
+pub const PTRACE_EVENT_EXEC:       i32 = 4;
+pub const PTRACE_EVENT_VFORK_DONE: i32 = 5;
+pub const PTRACE_EVENT_EXIT:       i32 = 6;
+pub const PTRACE_EVENT_SECCOMP:    i32 = 7;
+pub const PTRACE_EVENT_STOP:       i32 = 128;
+
+// The child of clone3 starts with the stack pointer at the top of the new stack, so it can't
+// return into rust code. The trampoline keeps the entry point and its argument in registers
+// which are copied into the child, and calls the entry point on the new stack.
+core::arch::global_asm!(
+    ".globl __clone3_thread",
+    ".type __clone3_thread, @function",
+    "__clone3_thread:",
+    "mov r8, rdx",
+    "mov r9, rcx",
+    "mov rax, {}",
+    "syscall",
+    "test rax, rax",
+    "jnz 1f",
+    "xor ebp, ebp",
+    "mov rdi, r9",
+    "call r8",
+    "ud2",
+    "1:",
+    "ret",
+    const super::SYS_CLONE3,
+);
+
+extern "C" {
+    fn __clone3_thread(args: *const clone_args, size: usize, entry: unsafe extern "C" fn(*mut u8) -> !, arg: *mut u8) -> isize;
+}
+
 #[no_mangle]
 pub fn clone() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_CLONE) })
 }
 
 #[no_mangle]
-pub fn fork() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_FORK) })
+pub fn fork() -> Result<pid_t> {
+    result(unsafe { syscall!(super::SYS_FORK) }).map(|n| n as pid_t)
 }
 
-#[no_mangle]
-pub fn vfork() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_VFORK) })
+/// The child borrows the memory and the stack of the parent until it calls execve or exit, and
+/// the parent is suspended until then. This is why the function is always inlined: a child
+/// returning from a real function call would overwrite the return address of the parent.
+///
+/// SAFETY: The child must not return from the calling function and must only call execve or exit.
+#[inline(always)]
+pub unsafe fn vfork() -> Result<pid_t> {
+    result(syscall!(super::SYS_VFORK)).map(|n| n as pid_t)
 }
 
+/// Replace the current process image. It returns only if the execution has failed, so
+/// there is nothing else to return than the error.
+///
+/// SAFETY: `argv` and `envp` must be terminated by a null pointer and every other element
+/// must point to a nul terminated string.
 #[no_mangle]
-pub fn execve() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_EXECVE) })
+pub unsafe fn execve(path: &str, argv: &[*const i8], envp: &[*const i8]) -> Error {
+    if argv.last().map_or(true, |p| !p.is_null()) || envp.last().map_or(true, |p| !p.is_null()) {
+        return Error::EINVAL;
+    }
+
+    let mut dst = [0u8; crate::limits::PATH_MAX];
+    if let Err(e) = cstr(path.as_bytes(), &mut dst) {
+        return e;
+    }
+
+    match result(syscall!(super::SYS_EXECVE, dst.as_ptr(), argv.as_ptr(), envp.as_ptr())) {
+        Ok(_) => unreachable!(),
+        Err(e) => e,
+    }
 }
 
 #[no_mangle]
@@ -28,33 +181,45 @@ pub fn exit(rc: u8) -> ! {
 }
 
 #[no_mangle]
-pub fn wait4() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_WAIT4) })
+pub fn wait4(pid: pid_t, status: &mut i32, options: i32, rusage: Option<&mut rusage>) -> Result<pid_t> {
+    let rusage = rusage.map_or(core::ptr::null_mut(), |r| r as *mut _);
+    result(unsafe { syscall!(super::SYS_WAIT4, pid, status as *mut _, options, rusage) }).map(|n| n as pid_t)
 }
+/// The meaning of `addr` and `data` depends on the request, most take a pointer to a buffer
+/// in one of them. PTRACE_PEEK* store the word at `data` instead of returning it.
+///
+/// # Safety
+/// The pointers have to be valid for the request
 #[no_mangle]
-pub fn ptrace() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_PTRACE) })
+pub unsafe fn ptrace(request: i32, pid: pid_t, addr: usize, data: usize) -> Result<usize> {
+    result(syscall!(super::SYS_PTRACE, request, pid, addr, data))
 }
 #[no_mangle]
-pub fn gettid() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_GETTID) })
+pub fn gettid() -> pid_t {
+    unsafe { syscall!(super::SYS_GETTID) as pid_t }
 }
+/// The kernel writes 0 to `tidptr` and wakes a futex on it when the calling thread exits
 #[no_mangle]
-pub fn set_tid_address() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_SET_TID_ADDRESS) })
+pub unsafe fn set_tid_address(tidptr: *mut pid_t) -> pid_t {
+    syscall!(super::SYS_SET_TID_ADDRESS, tidptr) as pid_t
 }
+/// Terminate all threads of the process, exit() only terminates the calling thread
 #[no_mangle]
-pub fn exit_group() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_EXIT_GROUP) })
+pub fn exit_group(rc: u8) -> ! {
+    unsafe { syscall!(super::SYS_EXIT_GROUP, rc as u32); }
+    unreachable!();
 }
 
 #[no_mangle]
-pub fn waitid() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_WAITID) })
+pub fn waitid(idtype: i32, id: i32, infop: &mut siginfo_t, options: i32, rusage: Option<&mut rusage>) -> Result<()> {
+    let rusage = rusage.map_or(core::ptr::null_mut(), |r| r as *mut _);
+    result(unsafe { syscall!(super::SYS_WAITID, idtype, id, infop as *mut _, options, rusage) }).map(|_| ())
 }
+/// Move the process into new namespaces of the CLONE_NEW* types. With CLONE_NEWPID the
+/// children are in the new namespace, the caller stays in its own.
 #[no_mangle]
-pub fn unshare() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_UNSHARE) })
+pub fn unshare(flags: u64) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_UNSHARE, flags) }).map(|_| ())
 }
 #[no_mangle]
 pub fn kcmp() -> Result<usize> {
@@ -69,13 +234,45 @@ pub fn rseq() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_RSEQ) })
 }
 #[no_mangle]
-pub fn pidfd_open() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_PIDFD_OPEN) })
+pub fn pidfd_open(pid: pid_t, flags: u32) -> Result<OwnedFd> {
+    result(unsafe { syscall!(super::SYS_PIDFD_OPEN, pid, flags) }).map(|fd| unsafe { OwnedFd::from_raw_fd(fd as u32) })
 }
 
+/// Without a new stack in `args` the child continues on a copy of the stack of the parent
+/// like with fork, so CLONE_VM requires a stack and clone_thread().
+///
+/// SAFETY: The flags and the pointers in `args` must be valid for the new child.
 #[no_mangle]
-pub fn clone3() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_CLONE3) })
+pub unsafe fn clone3(args: &clone_args) -> Result<pid_t> {
+    result(syscall!(super::SYS_CLONE3, args as *const clone_args, core::mem::size_of::<clone_args>()))
+        .map(|n| n as pid_t)
+}
+
+/// clone3 which runs `entry(arg)` in the child on the stack from `args`. Returns the tid
+/// of the child in the parent.
+///
+/// SAFETY: The stack must be valid and `entry` must never return, it has to end the thread
+/// with exit().
+pub unsafe fn clone_thread(args: &clone_args, entry: unsafe extern "C" fn(*mut u8) -> !, arg: *mut u8) -> Result<pid_t> {
+    result(__clone3_thread(args, core::mem::size_of::<clone_args>(), entry, arg)).map(|n| n as pid_t)
+}
+
+/// Unmap the stack of the calling thread and exit it. The function must not touch the
+/// stack after the munmap, so it is written in assembly.
+///
+/// SAFETY: No signal may be delivered to the thread anymore, block them first.
+pub unsafe fn unmap_and_exit(addr: *mut u8, len: usize) -> ! {
+    core::arch::asm!(
+        "syscall",
+        "mov eax, {exit}",
+        "xor edi, edi",
+        "syscall",
+        exit = const super::SYS_EXIT,
+        in("rax") super::SYS_MUNMAP,
+        in("rdi") addr,
+        in("rsi") len,
+        options(noreturn, nostack),
+    )
 }
 
 #[no_mangle]
@@ -1,6 +1,33 @@
+use crate::cstr;
 use crate::types::*;
 use crate::error::{Error, Result, result};
 
+// ==============================================================================
+// arch_prctl codes (arch/x86/include/uapi/asm/prctl.h)
+// ==============================================================================
+pub const ARCH_SET_GS: i32 = 0x1001;
+pub const ARCH_SET_FS: i32 = 0x1002;
+pub const ARCH_GET_FS: i32 = 0x1003;
+pub const ARCH_GET_GS: i32 = 0x1004;
+
+// ==============================================================================
+// prctl options (include/uapi/linux/prctl.h)
+// ==============================================================================
+pub const PR_SET_PDEATHSIG:        i32 = 1;
+pub const PR_GET_PDEATHSIG:        i32 = 2;
+pub const PR_GET_DUMPABLE:         i32 = 3;
+pub const PR_SET_DUMPABLE:         i32 = 4;
+pub const PR_SET_NAME:             i32 = 15;
+pub const PR_GET_NAME:             i32 = 16;
+pub const PR_GET_SECCOMP:          i32 = 21;
+pub const PR_SET_SECCOMP:          i32 = 22;
+pub const PR_CAPBSET_READ:         i32 = 23;
+pub const PR_CAPBSET_DROP:         i32 = 24;
+pub const PR_SET_CHILD_SUBREAPER:  i32 = 36;
+pub const PR_GET_CHILD_SUBREAPER:  i32 = 37;
+pub const PR_SET_NO_NEW_PRIVS:     i32 = 38;
+pub const PR_GET_NO_NEW_PRIVS:     i32 = 39;
+
 #[no_mangle]
 pub fn getpid() -> pid_t {
     // SAFETY: This syscall never fails and it always returns with pid_t
@@ -12,13 +39,15 @@ pub fn getcwd() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_GETCWD) })
 }
 #[no_mangle]
-pub fn chdir() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_CHDIR) })
+pub fn chdir(path: &str) -> Result<()> {
+    let mut dst = [0u8; crate::limits::PATH_MAX];
+    cstr(path.as_bytes(), &mut dst)?;
+    result(unsafe { syscall!(super::SYS_CHDIR, dst.as_ptr()) }).map(|_| ())
 }
 
 #[no_mangle]
-pub fn fchdir() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_FCHDIR) })
+pub fn fchdir(fd: u32) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_FCHDIR, fd) }).map(|_| ())
 }
 #[no_mangle]
 pub fn getrlimit() -> Result<usize> {
@@ -163,30 +192,47 @@ pub fn modify_ldt() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_MODIFY_LDT) })
 }
 
+/// Make `new_root` the root of the mount namespace and move the old root to `put_old`.
+/// `new_root` has to be a mount, `put_old` may be the same directory.
 #[no_mangle]
-pub fn pivot_root() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_PIVOT_ROOT) })
+pub fn pivot_root(new_root: &str, put_old: &str) -> Result<()> {
+    let mut new = [0u8; crate::limits::PATH_MAX];
+    let mut old = [0u8; crate::limits::PATH_MAX];
+    cstr(new_root.as_bytes(), &mut new)?;
+    cstr(put_old.as_bytes(), &mut old)?;
+    result(unsafe { syscall!(super::SYS_PIVOT_ROOT, new.as_ptr(), old.as_ptr()) }).map(|_| ())
 }
+/// The meaning of the arguments and the result depends on `option`, unused arguments have
+/// to be 0 for most options
+///
+/// SAFETY: Some options take a pointer to a buffer in an argument.
 #[no_mangle]
-pub fn prctl() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_PRCTL) })
+pub unsafe fn prctl(option: i32, arg2: usize, arg3: usize, arg4: usize, arg5: usize) -> Result<usize> {
+    result(syscall!(super::SYS_PRCTL, option, arg2, arg3, arg4, arg5))
 }
 
+/// For the ARCH_GET_* codes `addr` is the address of the u64 which receives the value
+///
+/// SAFETY: Changing the FS base replaces the thread local storage of the thread.
 #[no_mangle]
-pub fn arch_prctl() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_ARCH_PRCTL) })
+pub unsafe fn arch_prctl(code: i32, addr: usize) -> Result<()> {
+    result(syscall!(super::SYS_ARCH_PRCTL, code, addr)).map(|_| ())
 }
 #[no_mangle]
-pub fn chroot() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_CHROOT) })
+pub fn chroot(path: &str) -> Result<()> {
+    let mut dst = [0u8; crate::limits::PATH_MAX];
+    cstr(path.as_bytes(), &mut dst)?;
+    result(unsafe { syscall!(super::SYS_CHROOT, dst.as_ptr()) }).map(|_| ())
 }
 #[no_mangle]
 pub fn perf_event_open() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_PERF_EVENT_OPEN) })
 }
+/// Move the thread into the namespace `fd` refers to, a /proc/<pid>/ns file or a pidfd.
+/// `nstype` is the CLONE_NEW* type the namespace has to be or 0 for any.
 #[no_mangle]
-pub fn setns() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_SETNS) })
+pub fn setns(fd: u32, nstype: u64) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_SETNS, fd, nstype) }).map(|_| ())
 }
 
 #[no_mangle]
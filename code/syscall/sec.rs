@@ -1,28 +1,150 @@
 use crate::types::*;
 use crate::error::{Error, Result, result};
 
+// ==============================================================================
+// Seccomp (include/uapi/linux/seccomp.h)
+// ==============================================================================
+pub const SECCOMP_SET_MODE_STRICT:  u32 = 0;
+pub const SECCOMP_SET_MODE_FILTER:  u32 = 1;
+pub const SECCOMP_GET_ACTION_AVAIL: u32 = 2;
+pub const SECCOMP_GET_NOTIF_SIZES:  u32 = 3;
+
+pub const SECCOMP_FILTER_FLAG_TSYNC:              u32 = 1 << 0;
+pub const SECCOMP_FILTER_FLAG_LOG:                u32 = 1 << 1;
+pub const SECCOMP_FILTER_FLAG_SPEC_ALLOW:         u32 = 1 << 2;
+pub const SECCOMP_FILTER_FLAG_NEW_LISTENER:       u32 = 1 << 3;
+pub const SECCOMP_FILTER_FLAG_TSYNC_ESRCH:        u32 = 1 << 4;
+pub const SECCOMP_FILTER_FLAG_WAIT_KILLABLE_RECV: u32 = 1 << 5;
+
+// Return values of a filter, the lower 16 bits are the data of the action
+pub const SECCOMP_RET_KILL_PROCESS: u32 = 0x80000000;
+pub const SECCOMP_RET_KILL_THREAD:  u32 = 0x00000000;
+pub const SECCOMP_RET_TRAP:         u32 = 0x00030000;
+pub const SECCOMP_RET_ERRNO:        u32 = 0x00050000;
+pub const SECCOMP_RET_USER_NOTIF:   u32 = 0x7fc00000;
+pub const SECCOMP_RET_TRACE:        u32 = 0x7ff00000;
+pub const SECCOMP_RET_LOG:          u32 = 0x7ffc0000;
+pub const SECCOMP_RET_ALLOW:        u32 = 0x7fff0000;
+pub const SECCOMP_RET_ACTION_FULL:  u32 = 0xffff0000;
+pub const SECCOMP_RET_DATA:         u32 = 0x0000ffff;
+
+pub const SECCOMP_USER_NOTIF_FLAG_CONTINUE: u32 = 1 << 0;
+
+// ioctls of the listener
+pub const SECCOMP_IOCTL_NOTIF_RECV:     u64 = 0xc0502100;
+pub const SECCOMP_IOCTL_NOTIF_SEND:     u64 = 0xc0182101;
+pub const SECCOMP_IOCTL_NOTIF_ID_VALID: u64 = 0x40082102;
+
+/// The value of seccomp_data.arch for x86-64 (include/uapi/linux/audit.h)
+pub const AUDIT_ARCH_X86_64: u32 = 0xc000003e;
+
+/// Set in the numbers of the x32 system calls, which are x86-64 as well
+pub const X32_SYSCALL_BIT: u32 = 0x40000000;
+
+// ==============================================================================
+// Classic BPF instructions (include/uapi/linux/bpf_common.h)
+// ==============================================================================
+pub const BPF_LD:  u16 = 0x00;
+pub const BPF_ALU: u16 = 0x04;
+pub const BPF_JMP: u16 = 0x05;
+pub const BPF_RET: u16 = 0x06;
+
+pub const BPF_W:   u16 = 0x00;
+pub const BPF_ABS: u16 = 0x20;
+
+pub const BPF_AND: u16 = 0x50;
+
+pub const BPF_JA:   u16 = 0x00;
+pub const BPF_JEQ:  u16 = 0x10;
+pub const BPF_JGT:  u16 = 0x20;
+pub const BPF_JGE:  u16 = 0x30;
+pub const BPF_JSET: u16 = 0x40;
+
+pub const BPF_K: u16 = 0x00;
+
+/// The maximum number of instructions of a program
+pub const BPF_MAXINSNS: usize = 4096;
+
+// ==============================================================================
+// Landlock (include/uapi/linux/landlock.h)
+// ==============================================================================
+pub const LANDLOCK_CREATE_RULESET_VERSION: u32 = 1 << 0;
+pub const LANDLOCK_CREATE_RULESET_ERRATA:  u32 = 1 << 1;
+
+// Rule types of landlock_add_rule
+pub const LANDLOCK_RULE_PATH_BENEATH: u32 = 1;
+pub const LANDLOCK_RULE_NET_PORT:     u32 = 2;
+
+// Flags of landlock_restrict_self
+pub const LANDLOCK_RESTRICT_SELF_LOG_SAME_EXEC_OFF:  u32 = 1 << 0;
+pub const LANDLOCK_RESTRICT_SELF_LOG_NEW_EXEC_ON:    u32 = 1 << 1;
+pub const LANDLOCK_RESTRICT_SELF_LOG_SUBDOMAINS_OFF: u32 = 1 << 2;
+
+// Filesystem access rights, the ABI version which added them is in the comment
+pub const LANDLOCK_ACCESS_FS_EXECUTE:     u64 = 1 << 0;
+pub const LANDLOCK_ACCESS_FS_WRITE_FILE:  u64 = 1 << 1;
+pub const LANDLOCK_ACCESS_FS_READ_FILE:   u64 = 1 << 2;
+pub const LANDLOCK_ACCESS_FS_READ_DIR:    u64 = 1 << 3;
+pub const LANDLOCK_ACCESS_FS_REMOVE_DIR:  u64 = 1 << 4;
+pub const LANDLOCK_ACCESS_FS_REMOVE_FILE: u64 = 1 << 5;
+pub const LANDLOCK_ACCESS_FS_MAKE_CHAR:   u64 = 1 << 6;
+pub const LANDLOCK_ACCESS_FS_MAKE_DIR:    u64 = 1 << 7;
+pub const LANDLOCK_ACCESS_FS_MAKE_REG:    u64 = 1 << 8;
+pub const LANDLOCK_ACCESS_FS_MAKE_SOCK:   u64 = 1 << 9;
+pub const LANDLOCK_ACCESS_FS_MAKE_FIFO:   u64 = 1 << 10;
+pub const LANDLOCK_ACCESS_FS_MAKE_BLOCK:  u64 = 1 << 11;
+pub const LANDLOCK_ACCESS_FS_MAKE_SYM:    u64 = 1 << 12;
+pub const LANDLOCK_ACCESS_FS_REFER:       u64 = 1 << 13; // 2
+pub const LANDLOCK_ACCESS_FS_TRUNCATE:    u64 = 1 << 14; // 3
+pub const LANDLOCK_ACCESS_FS_IOCTL_DEV:   u64 = 1 << 15; // 5
+
+// Network access rights
+pub const LANDLOCK_ACCESS_NET_BIND_TCP:    u64 = 1 << 0; // 4
+pub const LANDLOCK_ACCESS_NET_CONNECT_TCP: u64 = 1 << 1; // 4
+
+// IPC scopes
+pub const LANDLOCK_SCOPE_ABSTRACT_UNIX_SOCKET: u64 = 1 << 0; // 6
+pub const LANDLOCK_SCOPE_SIGNAL:               u64 = 1 << 1; // 6
+
+/// `args` depends on `op`, a sock_fprog for SECCOMP_SET_MODE_FILTER. Returns the descriptor
+/// of the listener with SECCOMP_FILTER_FLAG_NEW_LISTENER.
+///
+/// # Safety
+/// `args` has to point to what `op` expects
 #[no_mangle]
-pub fn seccomp() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_SECCOMP) })
+pub unsafe fn seccomp(op: u32, flags: u32, args: *const u8) -> Result<usize> {
+    result(syscall!(super::SYS_SECCOMP, op, flags, args))
 }
 
 #[no_mangle]
 pub fn getrandom() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_GETRANDOM) })
 }
+/// Create a ruleset and return its descriptor. With LANDLOCK_CREATE_RULESET_VERSION and no
+/// `attr` it returns the highest supported ABI version instead.
 #[no_mangle]
-pub fn landlock_create_ruleset() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_LANDLOCK_CREATE_RULESET) })
+pub fn landlock_create_ruleset(attr: Option<&landlock_ruleset_attr>, flags: u32) -> Result<usize> {
+    let (ptr, size) = match attr {
+        Some(attr) => (attr as *const landlock_ruleset_attr, core::mem::size_of::<landlock_ruleset_attr>()),
+        None => (core::ptr::null(), 0),
+    };
+    result(unsafe { syscall!(super::SYS_LANDLOCK_CREATE_RULESET, ptr, size, flags) })
 }
 
+/// Add a rule to the ruleset, `attr` points to a landlock_path_beneath_attr or a
+/// landlock_net_port_attr depending on `rule_type`
+///
+/// # Safety
+/// `attr` has to point to the struct of the rule type
 #[no_mangle]
-pub fn landlock_add_rule() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_LANDLOCK_ADD_RULE) })
+pub unsafe fn landlock_add_rule(fd: u32, rule_type: u32, attr: *const u8, flags: u32) -> Result<()> {
+    result(syscall!(super::SYS_LANDLOCK_ADD_RULE, fd, rule_type, attr, flags)).map(|_| ())
 }
 
+/// Enforce the ruleset on the calling thread, which needs no_new_privs or CAP_SYS_ADMIN
 #[no_mangle]
-pub fn landlock_restrict_self() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_LANDLOCK_RESTRICT_SELF) })
+pub fn landlock_restrict_self(fd: u32, flags: u32) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_LANDLOCK_RESTRICT_SELF, fd, flags) }).map(|_| ())
 }
 #[no_mangle]
 pub fn lsm_get_self_attr() -> Result<usize> {
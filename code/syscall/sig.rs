@@ -1,26 +1,136 @@
+use core::mem::size_of;
+use core::ptr::{null, null_mut};
+
 use crate::types::*;
 use crate::error::{Error, Result, result};
 
+// ==============================================================================
+// Signal numbers (arch/x86/include/uapi/asm/signal.h)
+// ==============================================================================
+pub const SIGHUP:    i32 = 1;
+pub const SIGINT:    i32 = 2;
+pub const SIGQUIT:   i32 = 3;
+pub const SIGILL:    i32 = 4;
+pub const SIGTRAP:   i32 = 5;
+pub const SIGABRT:   i32 = 6;
+pub const SIGIOT:    i32 = 6;
+pub const SIGBUS:    i32 = 7;
+pub const SIGFPE:    i32 = 8;
+pub const SIGKILL:   i32 = 9;
+pub const SIGUSR1:   i32 = 10;
+pub const SIGSEGV:   i32 = 11;
+pub const SIGUSR2:   i32 = 12;
+pub const SIGPIPE:   i32 = 13;
+pub const SIGALRM:   i32 = 14;
+pub const SIGTERM:   i32 = 15;
+pub const SIGSTKFLT: i32 = 16;
+pub const SIGCHLD:   i32 = 17;
+pub const SIGCONT:   i32 = 18;
+pub const SIGSTOP:   i32 = 19;
+pub const SIGTSTP:   i32 = 20;
+pub const SIGTTIN:   i32 = 21;
+pub const SIGTTOU:   i32 = 22;
+pub const SIGURG:    i32 = 23;
+pub const SIGXCPU:   i32 = 24;
+pub const SIGXFSZ:   i32 = 25;
+pub const SIGVTALRM: i32 = 26;
+pub const SIGPROF:   i32 = 27;
+pub const SIGWINCH:  i32 = 28;
+pub const SIGIO:     i32 = 29;
+pub const SIGPOLL:   i32 = SIGIO;
+pub const SIGPWR:    i32 = 30;
+pub const SIGSYS:    i32 = 31;
+pub const SIGRTMIN:  i32 = 32;
+pub const SIGRTMAX:  i32 = 64;
+
+// sigaction.sa_flags
+pub const SA_NOCLDSTOP: u64 = 0x00000001;
+pub const SA_NOCLDWAIT: u64 = 0x00000002;
+pub const SA_SIGINFO:   u64 = 0x00000004;
+pub const SA_RESTORER:  u64 = 0x04000000;
+pub const SA_ONSTACK:   u64 = 0x08000000;
+pub const SA_RESTART:   u64 = 0x10000000;
+pub const SA_NODEFER:   u64 = 0x40000000;
+pub const SA_RESETHAND: u64 = 0x80000000;
+
+// sigaction.sa_handler
+pub const SIG_DFL: usize = 0;
+pub const SIG_IGN: usize = 1;
+
+// rt_sigprocmask() how
+pub const SIG_BLOCK:   i32 = 0;
+pub const SIG_UNBLOCK: i32 = 1;
+pub const SIG_SETMASK: i32 = 2;
+
+
+// The kernel pushes the address in sa_restorer as the return address of the signal handler.
+// When the handler returns it lands here and rt_sigreturn restores the interrupted context
+// from the signal frame which is still on the stack. It can't be a rust function because
+// the prologue would move the stack pointer away from the frame.
+core::arch::global_asm!(
+    ".globl __restore_rt",
+    ".type __restore_rt, @function",
+    "__restore_rt:",
+    "mov rax, {}",
+    "syscall",
+    const super::SYS_RT_SIGRETURN,
+);
+
+extern "C" {
+    fn __restore_rt();
+}
+
+/// On x86-64 the kernel requires a restorer, so if the caller hasn't provided one
+/// the __restore_rt trampoline is used.
+#[no_mangle]
+pub fn rt_sigaction(sig: i32, act: Option<&sigaction>, oldact: Option<&mut sigaction>) -> Result<()> {
+    let act = act.cloned().map(|mut act| {
+        if act.sa_flags & SA_RESTORER == 0 {
+            act.sa_flags |= SA_RESTORER;
+            act.sa_restorer = __restore_rt as *const () as usize;
+        }
+        act
+    });
+    let act = act.as_ref().map_or(null(), |a| a as *const sigaction);
+    let oldact = oldact.map_or(null_mut(), |a| a as *mut sigaction);
+    result(unsafe { syscall!(super::SYS_RT_SIGACTION, sig, act, oldact, size_of::<sigset_t>()) }).map(|_| ())
+}
+
+#[no_mangle]
+pub fn rt_sigprocmask(how: i32, set: Option<&sigset_t>, oldset: Option<&mut sigset_t>) -> Result<()> {
+    let set = set.map_or(null(), |s| s as *const sigset_t);
+    let oldset = oldset.map_or(null_mut(), |s| s as *mut sigset_t);
+    result(unsafe { syscall!(super::SYS_RT_SIGPROCMASK, how, set, oldset, size_of::<sigset_t>()) }).map(|_| ())
+}
+
+/// It returns only when a signal handler has been executed so the result is always EINTR
 #[no_mangle]
-pub fn pause() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_PAUSE) })
+pub fn pause() -> Error {
+    match result(unsafe { syscall!(super::SYS_PAUSE) }) {
+        Ok(_) => unreachable!(),
+        Err(e) => e,
+    }
 }
 #[no_mangle]
-pub fn alarm() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_ALARM) })
+pub fn alarm(seconds: u32) -> u32 {
+    // SAFETY: alarm never fails, it returns the seconds remaining from the previous alarm
+    unsafe { syscall!(super::SYS_ALARM, seconds) as u32 }
 }
 #[no_mangle]
-pub fn kill() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_KILL) })
+pub fn kill(pid: pid_t, sig: i32) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_KILL, pid, sig) }).map(|_| ())
 }
 #[no_mangle]
-pub fn rt_sigpending() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_RT_SIGPENDING) })
+pub fn rt_sigpending(set: &mut sigset_t) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_RT_SIGPENDING, set as *mut _, size_of::<sigset_t>()) }).map(|_| ())
 }
 
+/// Returns with the number of the accepted signal
 #[no_mangle]
-pub fn rt_sigtimedwait() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_RT_SIGTIMEDWAIT) })
+pub fn rt_sigtimedwait(set: &sigset_t, info: Option<&mut siginfo_t>, timeout: Option<&timespec>) -> Result<i32> {
+    let info = info.map_or(null_mut(), |i| i as *mut siginfo_t);
+    let timeout = timeout.map_or(null(), |t| t as *const timespec);
+    result(unsafe { syscall!(super::SYS_RT_SIGTIMEDWAIT, set as *const _, info, timeout, size_of::<sigset_t>()) }).map(|n| n as i32)
 }
 
 #[no_mangle]
@@ -28,9 +138,13 @@ pub fn rt_sigqueueinfo() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_RT_SIGQUEUEINFO) })
 }
 
+/// Like pause() it returns only after a signal handler has run, so the result is always EINTR
 #[no_mangle]
-pub fn rt_sigsuspend() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_RT_SIGSUSPEND) })
+pub fn rt_sigsuspend(mask: &sigset_t) -> Error {
+    match result(unsafe { syscall!(super::SYS_RT_SIGSUSPEND, mask as *const _, size_of::<sigset_t>()) }) {
+        Ok(_) => unreachable!(),
+        Err(e) => e,
+    }
 }
 
 #[no_mangle]
@@ -38,12 +152,12 @@ pub fn sigaltstack() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_SIGALTSTACK) })
 }
 #[no_mangle]
-pub fn tkill() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_TKILL) })
+pub fn tkill(tid: pid_t, sig: i32) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_TKILL, tid, sig) }).map(|_| ())
 }
 #[no_mangle]
-pub fn tgkill() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_TGKILL) })
+pub fn tgkill(tgid: pid_t, tid: pid_t, sig: i32) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_TGKILL, tgid, tid, sig) }).map(|_| ())
 }
 #[no_mangle]
 pub fn signalfd() -> Result<usize> {
@@ -1,6 +1,39 @@
 use crate::types::*;
 use crate::error::{Error, Result, result};
 
+// ==============================================================================
+// Futex operations (include/uapi/linux/futex.h)
+// ==============================================================================
+pub const FUTEX_WAIT:            i32 = 0;
+pub const FUTEX_WAKE:            i32 = 1;
+pub const FUTEX_FD:              i32 = 2;
+pub const FUTEX_REQUEUE:         i32 = 3;
+pub const FUTEX_CMP_REQUEUE:     i32 = 4;
+pub const FUTEX_WAKE_OP:         i32 = 5;
+pub const FUTEX_LOCK_PI:         i32 = 6;
+pub const FUTEX_UNLOCK_PI:       i32 = 7;
+pub const FUTEX_TRYLOCK_PI:      i32 = 8;
+pub const FUTEX_WAIT_BITSET:     i32 = 9;
+pub const FUTEX_WAKE_BITSET:     i32 = 10;
+pub const FUTEX_WAIT_REQUEUE_PI: i32 = 11;
+pub const FUTEX_CMP_REQUEUE_PI:  i32 = 12;
+pub const FUTEX_LOCK_PI2:        i32 = 13;
+
+pub const FUTEX_PRIVATE_FLAG:    i32 = 128;
+pub const FUTEX_CLOCK_REALTIME:  i32 = 256;
+
+pub const FUTEX_BITSET_MATCH_ANY: u32 = 0xffffffff;
+
+// ==============================================================================
+// futex2 flags (include/uapi/linux/futex.h)
+// ==============================================================================
+pub const FUTEX2_SIZE_U8:  u32 = 0x00;
+pub const FUTEX2_SIZE_U16: u32 = 0x01;
+pub const FUTEX2_SIZE_U32: u32 = 0x02;
+pub const FUTEX2_SIZE_U64: u32 = 0x03;
+pub const FUTEX2_NUMA:     u32 = 0x04;
+pub const FUTEX2_PRIVATE:  u32 = FUTEX_PRIVATE_FLAG as u32;
+
 #[no_mangle]
 pub fn semget() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_SEMGET) })
@@ -15,10 +48,15 @@ pub fn semop() -> Result<usize> {
 pub fn semctl() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_SEMCTL) })
 }
+/// The meaning of the arguments depends on the operation, e.g. `timeout` is the number of
+/// waiters to requeue (val2) for FUTEX_CMP_REQUEUE.
+///
+/// SAFETY: The addresses must be valid for the operation.
 #[no_mangle]
-pub fn futex() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_FUTEX) })
+pub unsafe fn futex(uaddr: *const u32, op: i32, val: u32, timeout: usize, uaddr2: *const u32, val3: u32) -> Result<usize> {
+    result(syscall!(super::SYS_FUTEX, uaddr, op, val, timeout, uaddr2, val3))
 }
+
 #[no_mangle]
 pub fn semtimedop() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_SEMTIMEDOP) })
@@ -33,21 +71,37 @@ pub fn set_robust_list() -> Result<usize> {
 pub fn get_robust_list() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_GET_ROBUST_LIST) })
 }
+/// Wait on several futexes at once and return the index of the one which was woken. The
+/// timeout is an absolute time of `clock`.
+///
+/// SAFETY: The addresses in `waiters` must be valid.
 #[no_mangle]
-pub fn futex_waitv() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_FUTEX_WAITV) })
+pub unsafe fn futex_waitv(waiters: &[futex_waitv], timeout: Option<&timespec>, clock: clockid_t) -> Result<usize> {
+    let timeout = timeout.map_or(core::ptr::null(), |ts| ts as *const timespec);
+    result(syscall!(super::SYS_FUTEX_WAITV, waiters.as_ptr(), waiters.len() as u32, 0, timeout, clock))
 }
+/// futex2 version of FUTEX_WAKE_BITSET (Linux 6.7), the FUTEX2_* flags select the size of the futex
+///
+/// SAFETY: `uaddr` must be valid for the size in `flags`.
 #[no_mangle]
-pub fn futex_wake() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_FUTEX_WAKE) })
+pub unsafe fn futex_wake(uaddr: *const u8, mask: u64, nr: i32, flags: u32) -> Result<usize> {
+    result(syscall!(super::SYS_FUTEX_WAKE, uaddr, mask, nr, flags))
 }
 
+/// futex2 version of FUTEX_WAIT_BITSET (Linux 6.7). The timeout is an absolute time of `clock`.
+///
+/// SAFETY: `uaddr` must be valid for the size in `flags`.
 #[no_mangle]
-pub fn futex_wait() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_FUTEX_WAIT) })
+pub unsafe fn futex_wait(uaddr: *const u8, val: u64, mask: u64, flags: u32, timeout: Option<&timespec>, clock: clockid_t) -> Result<()> {
+    let timeout = timeout.map_or(core::ptr::null(), |ts| ts as *const timespec);
+    result(syscall!(super::SYS_FUTEX_WAIT, uaddr, val, mask, flags, timeout, clock)).map(|_| ())
 }
 
+/// futex2 version of FUTEX_CMP_REQUEUE (Linux 6.7): wake `nr_wake` waiters of waiters[0] and move up
+/// to `nr_requeue` of the others to waiters[1] if waiters[0] still contains its val
+///
+/// SAFETY: The addresses in `waiters` must be valid.
 #[no_mangle]
-pub fn futex_requeue() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_FUTEX_REQUEUE) })
+pub unsafe fn futex_requeue(waiters: &[futex_waitv; 2], nr_wake: i32, nr_requeue: i32) -> Result<usize> {
+    result(syscall!(super::SYS_FUTEX_REQUEUE, waiters.as_ptr(), 0, nr_wake, nr_requeue))
 }
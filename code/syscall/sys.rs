@@ -41,14 +41,15 @@ pub fn reboot() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_REBOOT) })
 }
 
+/// Set the hostname of the UTS namespace, up to 64 bytes without a nul
 #[no_mangle]
-pub fn sethostname() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_SETHOSTNAME) })
+pub fn sethostname(name: &[u8]) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_SETHOSTNAME, name.as_ptr(), name.len()) }).map(|_| ())
 }
 
 #[no_mangle]
-pub fn setdomainname() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_SETDOMAINNAME) })
+pub fn setdomainname(name: &[u8]) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_SETDOMAINNAME, name.as_ptr(), name.len()) }).map(|_| ())
 }
 
 #[no_mangle]
@@ -0,0 +1,468 @@
+//! The names and the arguments of the system calls, indexed by number
+use crate::syscall::*;
+
+/// What an argument of a system call is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgKind {
+    /// A file descriptor, or a directory descriptor like AT_FDCWD
+    Fd,
+
+    /// A pointer to a null-terminated path
+    Path,
+
+    /// A pointer to another null-terminated string
+    Str,
+
+    /// A bit mask, including modes
+    Flags,
+
+    /// A pointer to a buffer or a struct
+    Ptr,
+
+    /// The size of a buffer
+    Len,
+
+    /// Any other number, like a pid, an id, an offset or a command
+    Int,
+}
+
+/// A system call of the table
+#[derive(Debug)]
+pub struct Syscall {
+    pub nr: isize,
+    pub name: &'static str,
+    pub args: &'static [ArgKind],
+}
+
+impl Syscall {
+    /// The number of arguments
+    pub fn arity(&self) -> usize {
+        self.args.len()
+    }
+}
+
+/// The system calls of x86-64
+pub struct SyscallTable;
+
+impl SyscallTable {
+    pub fn get(nr: isize) -> Option<&'static Syscall> {
+        if nr < 0 {
+            return None;
+        }
+        match INDEX.get(nr as usize) {
+            Some(&i) if i != NONE => Some(&SYSCALLS[i as usize]),
+            _ => None,
+        }
+    }
+
+    /// The name of the system call `nr`, like "openat" for SYS_OPENAT
+    pub fn name(nr: isize) -> Option<&'static str> {
+        Self::get(nr).map(|syscall| syscall.name)
+    }
+
+    pub fn lookup(name: &str) -> Option<&'static Syscall> {
+        SYSCALLS.iter().find(|syscall| syscall.name == name)
+    }
+
+    /// The number of the system call `name`
+    pub fn number(name: &str) -> Option<isize> {
+        Self::lookup(name).map(|syscall| syscall.nr)
+    }
+
+    pub fn arity(nr: isize) -> Option<usize> {
+        Self::get(nr).map(Syscall::arity)
+    }
+
+    pub fn args(nr: isize) -> Option<&'static [ArgKind]> {
+        Self::get(nr).map(|syscall| syscall.args)
+    }
+
+    /// All system calls by number
+    pub fn iter() -> impl Iterator<Item = &'static Syscall> {
+        SYSCALLS.iter()
+    }
+}
+
+macro_rules! syscalls {
+    ($($nr:ident => $name:ident($($arg:ident),*),)*) => {
+        const SYSCALLS: &[Syscall] = &[
+            $(Syscall { nr: $nr, name: stringify!($name), args: &[$(ArgKind::$arg),*] },)*
+        ];
+    };
+}
+
+const NONE: u16 = u16::MAX;
+
+/// The position in SYSCALLS by number
+static INDEX: [u16; SYS_LSM_LIST_MODULESV as usize + 1] = index();
+
+const fn index() -> [u16; SYS_LSM_LIST_MODULESV as usize + 1] {
+    let mut index = [NONE; SYS_LSM_LIST_MODULESV as usize + 1];
+    let mut i = 0;
+    while i < SYSCALLS.len() {
+        index[SYSCALLS[i].nr as usize] = i as u16;
+        i += 1;
+    }
+    index
+}
+
+// The names are the ones of arch/x86/entry/syscalls/syscall_64.tbl
+syscalls! {
+    SYS_READ                    => read(Fd, Ptr, Len),
+    SYS_WRITE                   => write(Fd, Ptr, Len),
+    SYS_OPEN                    => open(Path, Flags, Flags),
+    SYS_CLOSE                   => close(Fd),
+    SYS_NEWSTAT                 => stat(Path, Ptr),
+    SYS_NEWFSTAT                => fstat(Fd, Ptr),
+    SYS_NEWLSTAT                => lstat(Path, Ptr),
+    SYS_POLL                    => poll(Ptr, Int, Int),
+    SYS_LSEEK                   => lseek(Fd, Int, Int),
+    SYS_MMAP                    => mmap(Ptr, Len, Flags, Flags, Fd, Int),
+    SYS_MPROTECT                => mprotect(Ptr, Len, Flags),
+    SYS_MUNMAP                  => munmap(Ptr, Len),
+    SYS_BRK                     => brk(Ptr),
+    SYS_RT_SIGACTION            => rt_sigaction(Int, Ptr, Ptr, Len),
+    SYS_RT_SIGPROCMASK          => rt_sigprocmask(Int, Ptr, Ptr, Len),
+    SYS_RT_SIGRETURN            => rt_sigreturn(),
+    SYS_IOCTL                   => ioctl(Fd, Int, Ptr),
+    SYS_PREAD64                 => pread64(Fd, Ptr, Len, Int),
+    SYS_PWRITE64                => pwrite64(Fd, Ptr, Len, Int),
+    SYS_READV                   => readv(Fd, Ptr, Int),
+    SYS_WRITEV                  => writev(Fd, Ptr, Int),
+    SYS_ACCESS                  => access(Path, Flags),
+    SYS_PIPE                    => pipe(Ptr),
+    SYS_SELECT                  => select(Int, Ptr, Ptr, Ptr, Ptr),
+    SYS_SCHED_YIELD             => sched_yield(),
+    SYS_MREMAP                  => mremap(Ptr, Len, Len, Flags, Ptr),
+    SYS_MSYNC                   => msync(Ptr, Len, Flags),
+    SYS_MINCORE                 => mincore(Ptr, Len, Ptr),
+    SYS_MADVISE                 => madvise(Ptr, Len, Int),
+    SYS_SHMGET                  => shmget(Int, Len, Flags),
+    SYS_SHMAT                   => shmat(Int, Ptr, Flags),
+    SYS_SHMCTL                  => shmctl(Int, Int, Ptr),
+    SYS_DUP                     => dup(Fd),
+    SYS_DUP2                    => dup2(Fd, Fd),
+    SYS_PAUSE                   => pause(),
+    SYS_NANOSLEEP               => nanosleep(Ptr, Ptr),
+    SYS_GETITIMER               => getitimer(Int, Ptr),
+    SYS_ALARM                   => alarm(Int),
+    SYS_SETITIMER               => setitimer(Int, Ptr, Ptr),
+    SYS_GETPID                  => getpid(),
+    SYS_SENDFILE64              => sendfile(Fd, Fd, Ptr, Len),
+    SYS_SOCKET                  => socket(Int, Int, Int),
+    SYS_CONNECT                 => connect(Fd, Ptr, Len),
+    SYS_ACCEPT                  => accept(Fd, Ptr, Ptr),
+    SYS_SENDTO                  => sendto(Fd, Ptr, Len, Flags, Ptr, Len),
+    SYS_RECVFROM                => recvfrom(Fd, Ptr, Len, Flags, Ptr, Ptr),
+    SYS_SENDMSG                 => sendmsg(Fd, Ptr, Flags),
+    SYS_RECVMSG                 => recvmsg(Fd, Ptr, Flags),
+    SYS_SHUTDOWN                => shutdown(Fd, Int),
+    SYS_BIND                    => bind(Fd, Ptr, Len),
+    SYS_LISTEN                  => listen(Fd, Int),
+    SYS_GETSOCKNAME             => getsockname(Fd, Ptr, Ptr),
+    SYS_GETPEERNAME             => getpeername(Fd, Ptr, Ptr),
+    SYS_SOCKETPAIR              => socketpair(Int, Int, Int, Ptr),
+    SYS_SETSOCKOPT              => setsockopt(Fd, Int, Int, Ptr, Len),
+    SYS_GETSOCKOPT              => getsockopt(Fd, Int, Int, Ptr, Ptr),
+    SYS_CLONE                   => clone(Flags, Ptr, Ptr, Ptr, Ptr),
+    SYS_FORK                    => fork(),
+    SYS_VFORK                   => vfork(),
+    SYS_EXECVE                  => execve(Path, Ptr, Ptr),
+    SYS_EXIT                    => exit(Int),
+    SYS_WAIT4                   => wait4(Int, Ptr, Flags, Ptr),
+    SYS_KILL                    => kill(Int, Int),
+    SYS_NEWUNAME                => uname(Ptr),
+    SYS_SEMGET                  => semget(Int, Int, Flags),
+    SYS_SEMOP                   => semop(Int, Ptr, Len),
+    SYS_SEMCTL                  => semctl(Int, Int, Int, Ptr),
+    SYS_SHMDT                   => shmdt(Ptr),
+    SYS_MSGGET                  => msgget(Int, Flags),
+    SYS_MSGSND                  => msgsnd(Int, Ptr, Len, Flags),
+    SYS_MSGRCV                  => msgrcv(Int, Ptr, Len, Int, Flags),
+    SYS_MSGCTL                  => msgctl(Int, Int, Ptr),
+    SYS_FCNTL                   => fcntl(Fd, Int, Int),
+    SYS_FLOCK                   => flock(Fd, Flags),
+    SYS_FSYNC                   => fsync(Fd),
+    SYS_FDATASYNC               => fdatasync(Fd),
+    SYS_TRUNCATE                => truncate(Path, Int),
+    SYS_FTRUNCATE               => ftruncate(Fd, Int),
+    SYS_GETDENTS                => getdents(Fd, Ptr, Len),
+    SYS_GETCWD                  => getcwd(Ptr, Len),
+    SYS_CHDIR                   => chdir(Path),
+    SYS_FCHDIR                  => fchdir(Fd),
+    SYS_RENAME                  => rename(Path, Path),
+    SYS_MKDIR                   => mkdir(Path, Flags),
+    SYS_RMDIR                   => rmdir(Path),
+    SYS_CREAT                   => creat(Path, Flags),
+    SYS_LINK                    => link(Path, Path),
+    SYS_UNLINK                  => unlink(Path),
+    SYS_SYMLINK                 => symlink(Path, Path),
+    SYS_READLINK                => readlink(Path, Ptr, Len),
+    SYS_CHMOD                   => chmod(Path, Flags),
+    SYS_FCHMOD                  => fchmod(Fd, Flags),
+    SYS_CHOWN                   => chown(Path, Int, Int),
+    SYS_FCHOWN                  => fchown(Fd, Int, Int),
+    SYS_LCHOWN                  => lchown(Path, Int, Int),
+    SYS_UMASK                   => umask(Flags),
+    SYS_GETTIMEOFDAY            => gettimeofday(Ptr, Ptr),
+    SYS_GETRLIMIT               => getrlimit(Int, Ptr),
+    SYS_GETRUSAGE               => getrusage(Int, Ptr),
+    SYS_SYSINFO                 => sysinfo(Ptr),
+    SYS_TIMES                   => times(Ptr),
+    SYS_PTRACE                  => ptrace(Int, Int, Ptr, Ptr),
+    SYS_GETUID                  => getuid(),
+    SYS_SYSLOG                  => syslog(Int, Ptr, Len),
+    SYS_GETGID                  => getgid(),
+    SYS_SETUID                  => setuid(Int),
+    SYS_SETGID                  => setgid(Int),
+    SYS_GETEUID                 => geteuid(),
+    SYS_GETEGID                 => getegid(),
+    SYS_SETPGID                 => setpgid(Int, Int),
+    SYS_GETPPID                 => getppid(),
+    SYS_GETPGRP                 => getpgrp(),
+    SYS_SETSID                  => setsid(),
+    SYS_SETREUID                => setreuid(Int, Int),
+    SYS_SETREGID                => setregid(Int, Int),
+    SYS_GETGROUPS               => getgroups(Int, Ptr),
+    SYS_SETGROUPS               => setgroups(Int, Ptr),
+    SYS_SETRESUID               => setresuid(Int, Int, Int),
+    SYS_GETRESUID               => getresuid(Ptr, Ptr, Ptr),
+    SYS_SETRESGID               => setresgid(Int, Int, Int),
+    SYS_GETRESGID               => getresgid(Ptr, Ptr, Ptr),
+    SYS_GETPGID                 => getpgid(Int),
+    SYS_SETFSUID                => setfsuid(Int),
+    SYS_SETFSGID                => setfsgid(Int),
+    SYS_GETSID                  => getsid(Int),
+    SYS_CAPGET                  => capget(Ptr, Ptr),
+    SYS_CAPSET                  => capset(Ptr, Ptr),
+    SYS_RT_SIGPENDING           => rt_sigpending(Ptr, Len),
+    SYS_RT_SIGTIMEDWAIT         => rt_sigtimedwait(Ptr, Ptr, Ptr, Len),
+    SYS_RT_SIGQUEUEINFO         => rt_sigqueueinfo(Int, Int, Ptr),
+    SYS_RT_SIGSUSPEND           => rt_sigsuspend(Ptr, Len),
+    SYS_SIGALTSTACK             => sigaltstack(Ptr, Ptr),
+    SYS_UTIME                   => utime(Path, Ptr),
+    SYS_MKNOD                   => mknod(Path, Flags, Int),
+    SYS_PERSONALITY             => personality(Int),
+    SYS_USTAT                   => ustat(Int, Ptr),
+    SYS_STATFS                  => statfs(Path, Ptr),
+    SYS_FSTATFS                 => fstatfs(Fd, Ptr),
+    SYS_SYSFS                   => sysfs(Int, Int, Int),
+    SYS_GETPRIORITY             => getpriority(Int, Int),
+    SYS_SETPRIORITY             => setpriority(Int, Int, Int),
+    SYS_SCHED_SETPARAM          => sched_setparam(Int, Ptr),
+    SYS_SCHED_GETPARAM          => sched_getparam(Int, Ptr),
+    SYS_SCHED_SETSCHEDULER      => sched_setscheduler(Int, Int, Ptr),
+    SYS_SCHED_GETSCHEDULER      => sched_getscheduler(Int),
+    SYS_SCHED_GET_PRIORITY_MAX  => sched_get_priority_max(Int),
+    SYS_SCHED_GET_PRIORITY_MIN  => sched_get_priority_min(Int),
+    SYS_SCHED_RR_GET_INTERVAL   => sched_rr_get_interval(Int, Ptr),
+    SYS_MLOCK                   => mlock(Ptr, Len),
+    SYS_MUNLOCK                 => munlock(Ptr, Len),
+    SYS_MLOCKALL                => mlockall(Flags),
+    SYS_MUNLOCKALL              => munlockall(),
+    SYS_VHANGUP                 => vhangup(),
+    SYS_MODIFY_LDT              => modify_ldt(Int, Ptr, Len),
+    SYS_PIVOT_ROOT              => pivot_root(Path, Path),
+    SYS_NI_SYSCALL              => _sysctl(Ptr),
+    SYS_PRCTL                   => prctl(Int, Int, Int, Int, Int),
+    SYS_ARCH_PRCTL              => arch_prctl(Int, Ptr),
+    SYS_ADJTIMEX                => adjtimex(Ptr),
+    SYS_SETRLIMIT               => setrlimit(Int, Ptr),
+    SYS_CHROOT                  => chroot(Path),
+    SYS_SYNC                    => sync(),
+    SYS_ACCT                    => acct(Path),
+    SYS_SETTIMEOFDAY            => settimeofday(Ptr, Ptr),
+    SYS_MOUNT                   => mount(Str, Path, Str, Flags, Ptr),
+    SYS_UMOUNT                  => umount2(Path, Flags),
+    SYS_SWAPON                  => swapon(Path, Flags),
+    SYS_SWAPOFF                 => swapoff(Path),
+    SYS_REBOOT                  => reboot(Int, Int, Int, Ptr),
+    SYS_SETHOSTNAME             => sethostname(Str, Len),
+    SYS_SETDOMAINNAME           => setdomainname(Str, Len),
+    SYS_IOPL                    => iopl(Int),
+    SYS_IOPERM                  => ioperm(Int, Int, Int),
+    SYS_INIT_MODULE             => init_module(Ptr, Len, Str),
+    SYS_DELETE_MODULE           => delete_module(Str, Flags),
+    SYS_QUOTACTL                => quotactl(Int, Path, Int, Ptr),
+    SYS_GETTID                  => gettid(),
+    SYS_READAHEAD               => readahead(Fd, Int, Len),
+    SYS_SETXATTR                => setxattr(Path, Str, Ptr, Len, Flags),
+    SYS_LSETXATTR               => lsetxattr(Path, Str, Ptr, Len, Flags),
+    SYS_FSETXATTR               => fsetxattr(Fd, Str, Ptr, Len, Flags),
+    SYS_GETXATTR                => getxattr(Path, Str, Ptr, Len),
+    SYS_LGETXATTR               => lgetxattr(Path, Str, Ptr, Len),
+    SYS_FGETXATTR               => fgetxattr(Fd, Str, Ptr, Len),
+    SYS_LISTXATTR               => listxattr(Path, Ptr, Len),
+    SYS_LLISTXATTR              => llistxattr(Path, Ptr, Len),
+    SYS_FLISTXATTR              => flistxattr(Fd, Ptr, Len),
+    SYS_REMOVEXATTR             => removexattr(Path, Str),
+    SYS_LREMOVEXATTR            => lremovexattr(Path, Str),
+    SYS_FREMOVEXATTR            => fremovexattr(Fd, Str),
+    SYS_TKILL                   => tkill(Int, Int),
+    SYS_TIME                    => time(Ptr),
+    SYS_FUTEX                   => futex(Ptr, Int, Int, Ptr, Ptr, Int),
+    SYS_SCHED_SETAFFINITY       => sched_setaffinity(Int, Len, Ptr),
+    SYS_SCHED_GETAFFINITY       => sched_getaffinity(Int, Len, Ptr),
+    SYS_IO_SETUP                => io_setup(Int, Ptr),
+    SYS_IO_DESTROY              => io_destroy(Int),
+    SYS_IO_GETEVENTS            => io_getevents(Int, Int, Int, Ptr, Ptr),
+    SYS_IO_SUBMIT               => io_submit(Int, Int, Ptr),
+    SYS_IO_CANCEL               => io_cancel(Int, Ptr, Ptr),
+    SYS_EPOLL_CREATE            => epoll_create(Int),
+    SYS_REMAP_FILE_PAGES        => remap_file_pages(Ptr, Len, Int, Int, Flags),
+    SYS_GETDENTS64              => getdents64(Fd, Ptr, Len),
+    SYS_SET_TID_ADDRESS         => set_tid_address(Ptr),
+    SYS_RESTART_SYSCALL         => restart_syscall(),
+    SYS_SEMTIMEDOP              => semtimedop(Int, Ptr, Len, Ptr),
+    SYS_FADVISE64               => fadvise64(Fd, Int, Len, Int),
+    SYS_TIMER_CREATE            => timer_create(Int, Ptr, Ptr),
+    SYS_TIMER_SETTIME           => timer_settime(Int, Flags, Ptr, Ptr),
+    SYS_TIMER_GETTIME           => timer_gettime(Int, Ptr),
+    SYS_TIMER_GETOVERRUN        => timer_getoverrun(Int),
+    SYS_TIMER_DELETE            => timer_delete(Int),
+    SYS_CLOCK_SETTIME           => clock_settime(Int, Ptr),
+    SYS_CLOCK_GETTIME           => clock_gettime(Int, Ptr),
+    SYS_CLOCK_GETRES            => clock_getres(Int, Ptr),
+    SYS_CLOCK_NANOSLEEP         => clock_nanosleep(Int, Flags, Ptr, Ptr),
+    SYS_EXIT_GROUP              => exit_group(Int),
+    SYS_EPOLL_WAIT              => epoll_wait(Fd, Ptr, Int, Int),
+    SYS_EPOLL_CTL               => epoll_ctl(Fd, Int, Fd, Ptr),
+    SYS_TGKILL                  => tgkill(Int, Int, Int),
+    SYS_UTIMES                  => utimes(Path, Ptr),
+    SYS_MBIND                   => mbind(Ptr, Len, Int, Ptr, Int, Flags),
+    SYS_SET_MEMPOLICY           => set_mempolicy(Int, Ptr, Int),
+    SYS_GET_MEMPOLICY           => get_mempolicy(Ptr, Ptr, Int, Ptr, Flags),
+    SYS_MQ_OPEN                 => mq_open(Str, Flags, Flags, Ptr),
+    SYS_MQ_UNLINK               => mq_unlink(Str),
+    SYS_MQ_TIMEDSEND            => mq_timedsend(Fd, Ptr, Len, Int, Ptr),
+    SYS_MQ_TIMEDRECEIVE         => mq_timedreceive(Fd, Ptr, Len, Ptr, Ptr),
+    SYS_MQ_NOTIFY               => mq_notify(Fd, Ptr),
+    SYS_MQ_GETSETATTR           => mq_getsetattr(Fd, Ptr, Ptr),
+    SYS_KEXEC_LOAD              => kexec_load(Int, Int, Ptr, Flags),
+    SYS_WAITID                  => waitid(Int, Int, Ptr, Flags, Ptr),
+    SYS_ADD_KEY                 => add_key(Str, Str, Ptr, Len, Int),
+    SYS_REQUEST_KEY             => request_key(Str, Str, Str, Int),
+    SYS_KEYCTL                  => keyctl(Int, Int, Int, Int, Int),
+    SYS_IOPRIO_SET              => ioprio_set(Int, Int, Int),
+    SYS_IOPRIO_GET              => ioprio_get(Int, Int),
+    SYS_INOTIFY_INIT            => inotify_init(),
+    SYS_INOTIFY_ADD_WATCH       => inotify_add_watch(Fd, Path, Flags),
+    SYS_INOTIFY_RM_WATCH        => inotify_rm_watch(Fd, Int),
+    SYS_MIGRATE_PAGES           => migrate_pages(Int, Int, Ptr, Ptr),
+    SYS_OPENAT                  => openat(Fd, Path, Flags, Flags),
+    SYS_MKDIRAT                 => mkdirat(Fd, Path, Flags),
+    SYS_MKNODAT                 => mknodat(Fd, Path, Flags, Int),
+    SYS_FCHOWNAT                => fchownat(Fd, Path, Int, Int, Flags),
+    SYS_FUTIMESAT               => futimesat(Fd, Path, Ptr),
+    SYS_NEWFSTATAT              => newfstatat(Fd, Path, Ptr, Flags),
+    SYS_UNLINKAT                => unlinkat(Fd, Path, Flags),
+    SYS_RENAMEAT                => renameat(Fd, Path, Fd, Path),
+    SYS_LINKAT                  => linkat(Fd, Path, Fd, Path, Flags),
+    SYS_SYMLINKAT               => symlinkat(Path, Fd, Path),
+    SYS_READLINKAT              => readlinkat(Fd, Path, Ptr, Len),
+    SYS_FCHMODAT                => fchmodat(Fd, Path, Flags),
+    SYS_FACCESSAT               => faccessat(Fd, Path, Flags),
+    SYS_PSELECT6                => pselect6(Int, Ptr, Ptr, Ptr, Ptr, Ptr),
+    SYS_PPOLL                   => ppoll(Ptr, Int, Ptr, Ptr, Len),
+    SYS_UNSHARE                 => unshare(Flags),
+    SYS_SET_ROBUST_LIST         => set_robust_list(Ptr, Len),
+    SYS_GET_ROBUST_LIST         => get_robust_list(Int, Ptr, Ptr),
+    SYS_SPLICE                  => splice(Fd, Ptr, Fd, Ptr, Len, Flags),
+    SYS_TEE                     => tee(Fd, Fd, Len, Flags),
+    SYS_SYNC_FILE_RANGE         => sync_file_range(Fd, Int, Int, Flags),
+    SYS_VMSPLICE                => vmsplice(Fd, Ptr, Int, Flags),
+    SYS_MOVE_PAGES              => move_pages(Int, Int, Ptr, Ptr, Ptr, Flags),
+    SYS_UTIMENSAT               => utimensat(Fd, Path, Ptr, Flags),
+    SYS_EPOLL_PWAIT             => epoll_pwait(Fd, Ptr, Int, Int, Ptr, Len),
+    SYS_SIGNALFD                => signalfd(Fd, Ptr, Len),
+    SYS_TIMERFD_CREATE          => timerfd_create(Int, Flags),
+    SYS_EVENTFD                 => eventfd(Int),
+    SYS_FALLOCATE               => fallocate(Fd, Flags, Int, Int),
+    SYS_TIMERFD_SETTIME         => timerfd_settime(Fd, Flags, Ptr, Ptr),
+    SYS_TIMERFD_GETTIME         => timerfd_gettime(Fd, Ptr),
+    SYS_ACCEPT4                 => accept4(Fd, Ptr, Ptr, Flags),
+    SYS_SIGNALFD4               => signalfd4(Fd, Ptr, Len, Flags),
+    SYS_EVENTFD2                => eventfd2(Int, Flags),
+    SYS_EPOLL_CREATE1           => epoll_create1(Flags),
+    SYS_DUP3                    => dup3(Fd, Fd, Flags),
+    SYS_PIPE2                   => pipe2(Ptr, Flags),
+    SYS_INOTIFY_INIT1           => inotify_init1(Flags),
+    SYS_PREADV                  => preadv(Fd, Ptr, Int, Int, Int),
+    SYS_PWRITEV                 => pwritev(Fd, Ptr, Int, Int, Int),
+    SYS_RT_TGSIGQUEUEINFO       => rt_tgsigqueueinfo(Int, Int, Int, Ptr),
+    SYS_PERF_EVENT_OPEN         => perf_event_open(Ptr, Int, Int, Fd, Flags),
+    SYS_RECVMMSG                => recvmmsg(Fd, Ptr, Int, Flags, Ptr),
+    SYS_FANOTIFY_INIT           => fanotify_init(Flags, Flags),
+    SYS_FANOTIFY_MARK           => fanotify_mark(Fd, Flags, Int, Fd, Path),
+    SYS_PRLIMIT64               => prlimit64(Int, Int, Ptr, Ptr),
+    SYS_NAME_TO_HANDLE_AT       => name_to_handle_at(Fd, Path, Ptr, Ptr, Flags),
+    SYS_OPEN_BY_HANDLE_AT       => open_by_handle_at(Fd, Ptr, Flags),
+    SYS_CLOCK_ADJTIME           => clock_adjtime(Int, Ptr),
+    SYS_SYNCFS                  => syncfs(Fd),
+    SYS_SENDMMSG                => sendmmsg(Fd, Ptr, Int, Flags),
+    SYS_SETNS                   => setns(Fd, Flags),
+    SYS_GETCPU                  => getcpu(Ptr, Ptr, Ptr),
+    SYS_PROCESS_VM_READV        => process_vm_readv(Int, Ptr, Int, Ptr, Int, Flags),
+    SYS_PROCESS_VM_WRITEV       => process_vm_writev(Int, Ptr, Int, Ptr, Int, Flags),
+    SYS_KCMP                    => kcmp(Int, Int, Int, Int, Int),
+    SYS_FINIT_MODULE            => finit_module(Fd, Str, Flags),
+    SYS_SCHED_SETATTR           => sched_setattr(Int, Ptr, Flags),
+    SYS_SCHED_GETATTR           => sched_getattr(Int, Ptr, Len, Flags),
+    SYS_RENAMEAT2               => renameat2(Fd, Path, Fd, Path, Flags),
+    SYS_SECCOMP                 => seccomp(Int, Flags, Ptr),
+    SYS_GETRANDOM               => getrandom(Ptr, Len, Flags),
+    SYS_MEMFD_CREATE            => memfd_create(Str, Flags),
+    SYS_KEXEC_FILE_LOAD         => kexec_file_load(Fd, Fd, Len, Str, Flags),
+    SYS_BPF                     => bpf(Int, Ptr, Len),
+    SYS_EXECVEAT                => execveat(Fd, Path, Ptr, Ptr, Flags),
+    SYS_USERFAULTFD             => userfaultfd(Flags),
+    SYS_MEMBARRIER              => membarrier(Int, Flags, Int),
+    SYS_MLOCK2                  => mlock2(Ptr, Len, Flags),
+    SYS_COPY_FILE_RANGE         => copy_file_range(Fd, Ptr, Fd, Ptr, Len, Flags),
+    SYS_PREADV2                 => preadv2(Fd, Ptr, Int, Int, Int, Flags),
+    SYS_PWRITEV2                => pwritev2(Fd, Ptr, Int, Int, Int, Flags),
+    SYS_PKEY_MPROTECT           => pkey_mprotect(Ptr, Len, Flags, Int),
+    SYS_PKEY_ALLOC              => pkey_alloc(Flags, Flags),
+    SYS_PKEY_FREE               => pkey_free(Int),
+    SYS_STATX                   => statx(Fd, Path, Flags, Flags, Ptr),
+    SYS_IO_PGETEVENTS           => io_pgetevents(Int, Int, Int, Ptr, Ptr, Ptr),
+    SYS_RSEQ                    => rseq(Ptr, Len, Flags, Int),
+    SYS_PIDFD_SEND_SIGNAL       => pidfd_send_signal(Fd, Int, Ptr, Flags),
+    SYS_IO_URING_SETUP          => io_uring_setup(Int, Ptr),
+    SYS_IO_URING_ENTER          => io_uring_enter(Fd, Int, Int, Flags, Ptr, Len),
+    SYS_IO_URING_REGISTER       => io_uring_register(Fd, Int, Ptr, Int),
+    SYS_OPEN_TREE               => open_tree(Fd, Path, Flags),
+    SYS_MOVE_MOUNT              => move_mount(Fd, Path, Fd, Path, Flags),
+    SYS_FSOPEN                  => fsopen(Str, Flags),
+    SYS_FSCONFIG                => fsconfig(Fd, Int, Str, Ptr, Int),
+    SYS_FSMOUNT                 => fsmount(Fd, Flags, Flags),
+    SYS_FSPICK                  => fspick(Fd, Path, Flags),
+    SYS_PIDFD_OPEN              => pidfd_open(Int, Flags),
+    SYS_CLONE3                  => clone3(Ptr, Len),
+    SYS_CLOSE_RANGE             => close_range(Fd, Fd, Flags),
+    SYS_OPENAT2                 => openat2(Fd, Path, Ptr, Len),
+    SYS_PIDFD_GETFD             => pidfd_getfd(Fd, Fd, Flags),
+    SYS_FACCESSAT2              => faccessat2(Fd, Path, Flags, Flags),
+    SYS_PROCESS_MADVISE         => process_madvise(Fd, Ptr, Int, Int, Flags),
+    SYS_EPOLL_PWAIT2            => epoll_pwait2(Fd, Ptr, Int, Ptr, Ptr, Len),
+    SYS_MOUNT_SETATTR           => mount_setattr(Fd, Path, Flags, Ptr, Len),
+    SYS_QUOTACTL_FD             => quotactl_fd(Fd, Int, Int, Ptr),
+    SYS_LANDLOCK_CREATE_RULESET => landlock_create_ruleset(Ptr, Len, Flags),
+    SYS_LANDLOCK_ADD_RULE       => landlock_add_rule(Fd, Int, Ptr, Flags),
+    SYS_LANDLOCK_RESTRICT_SELF  => landlock_restrict_self(Fd, Flags),
+    SYS_MEMFD_SECRET            => memfd_secret(Flags),
+    SYS_PROCESS_MRELEASE        => process_mrelease(Fd, Flags),
+    SYS_FUTEX_WAITV             => futex_waitv(Ptr, Int, Flags, Ptr, Int),
+    SYS_SET_MEMPOLICY_HOME_NODE => set_mempolicy_home_node(Ptr, Len, Int, Flags),
+    SYS_CACHESTAT               => cachestat(Fd, Ptr, Ptr, Flags),
+    SYS_FCHMODAT2               => fchmodat2(Fd, Path, Flags, Flags),
+    SYS_MAP_SHADOW_STACK        => map_shadow_stack(Ptr, Len, Flags),
+    SYS_FUTEX_WAKE              => futex_wake(Ptr, Int, Int, Flags),
+    SYS_FUTEX_WAIT              => futex_wait(Ptr, Int, Int, Flags, Ptr, Int),
+    SYS_FUTEX_REQUEUE           => futex_requeue(Ptr, Flags, Int, Int),
+    SYS_STATMOUNT               => statmount(Ptr, Ptr, Len, Flags),
+    SYS_LISTMOUNT               => listmount(Ptr, Ptr, Len, Flags),
+    SYS_LSM_GET_SELF_ATTR       => lsm_get_self_attr(Int, Ptr, Ptr, Flags),
+    SYS_LSM_SET_SELF_ATTR       => lsm_set_self_attr(Int, Ptr, Len, Flags),
+    SYS_LSM_LIST_MODULESV       => lsm_list_modules(Ptr, Ptr, Flags),
+}
@@ -1,6 +1,14 @@
 use crate::types::*;
+use crate::fd::{FromRawFd, OwnedFd};
 use crate::error::{Error, Result, result};
 
+// ==============================================================================
+// Timerfd flags (include/uapi/linux/timerfd.h)
+// ==============================================================================
+pub const TFD_TIMER_ABSTIME: i32 = 1 << 0;
+pub const TFD_NONBLOCK:      i32 = 0o0004000;
+pub const TFD_CLOEXEC:       i32 = 0o2000000;
+
 #[no_mangle]
 pub fn nanosleep(req: &timespec, rem: &mut timespec) -> Result<usize> {
     result(unsafe { syscall!(super::SYS_NANOSLEEP, req as *const _, rem as *mut _) })
@@ -71,8 +79,8 @@ pub fn clock_settime() -> Result<usize> {
 }
 
 #[no_mangle]
-pub fn clock_gettime() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_CLOCK_GETTIME) })
+pub fn clock_gettime(clock: clockid_t, tp: &mut timespec) -> Result<()> {
+    result(unsafe { syscall!(super::SYS_CLOCK_GETTIME, clock, tp as *mut timespec) }).map(|_| ())
 }
 
 #[no_mangle]
@@ -89,8 +97,8 @@ pub fn utimes() -> Result<usize> {
     result(unsafe { syscall!(super::SYS_UTIMES) })
 }
 #[no_mangle]
-pub fn timerfd_create() -> Result<usize> {
-    result(unsafe { syscall!(super::SYS_TIMERFD_CREATE) })
+pub fn timerfd_create(clock: clockid_t, flags: i32) -> Result<OwnedFd> {
+    result(unsafe { syscall!(super::SYS_TIMERFD_CREATE, clock, flags) }).map(|fd| unsafe { OwnedFd::from_raw_fd(fd as u32) })
 }
 #[no_mangle]
 pub fn timerfd_settime() -> Result<usize> {
@@ -0,0 +1,220 @@
+//! demangle() on known names of both schemes, in the full and the alternate `{:#}` form.
+//!
+//! The expected names are the ones rustc-demangle prints. Invalid names have to come out
+//! unchanged, also a v0 name whose nested backrefs expand beyond the output limit.
+//! Run with `./cargo.sh test`.
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate linux;
+use core::fmt::{self, Write};
+use linux::demangle::demangle;
+use linux::io::Buf;
+
+struct Case {
+    mangled: &'static str,
+    full: &'static str,
+    short: &'static str,
+}
+
+const CASES: [Case; 25] = [
+    // Legacy
+    Case {
+        mangled: "_ZN5linux7syscall2io4read17h0123456789abcdefE",
+        full: "linux::syscall::io::read::h0123456789abcdef",
+        short: "linux::syscall::io::read",
+    },
+    Case {
+        mangled: "_ZN4core3ptr85drop_in_place$LT$std..rt..lang_start$LT$$LP$$RP$$GT$..$u7b$$u7b$closure$u7d$$u7d$$GT$17h0123456789abcdefE",
+        full: "core::ptr::drop_in_place<std::rt::lang_start<()>::{{closure}}>::h0123456789abcdef",
+        short: "core::ptr::drop_in_place<std::rt::lang_start<()>::{{closure}}>",
+    },
+    Case {
+        mangled: "_ZN54_$LT$$BP$const$u20$T$u20$as$u20$core..fmt..Pointer$GT$3fmt17h0123456789abcdefE",
+        full: "<*const T as core::fmt::Pointer>::fmt::h0123456789abcdef",
+        short: "<*const T as core::fmt::Pointer>::fmt",
+    },
+    Case {
+        mangled: "_ZN4core3fmt5write17h0123456789abcdefE.llvm.1234567",
+        full: "core::fmt::write::h0123456789abcdef",
+        short: "core::fmt::write",
+    },
+    Case {
+        mangled: "_ZN3foo3BAR17h0123456789abcdefE.0",
+        full: "foo::BAR::h0123456789abcdef.0",
+        short: "foo::BAR.0",
+    },
+    Case { mangled: "_ZN3foo3barE", full: "foo::bar", short: "foo::bar" },
+    Case { mangled: "_ZN2\u{e9}E", full: "\u{e9}", short: "\u{e9}" },
+
+    // v0
+    Case {
+        mangled: "_RNvCs1234_7mycrate3foo",
+        full: "mycrate[3c1c0]::foo",
+        short: "mycrate::foo",
+    },
+    Case {
+        mangled: "_RNvNtCs1234_7mycrate3bar3foo",
+        full: "mycrate[3c1c0]::bar::foo",
+        short: "mycrate::bar::foo",
+    },
+    Case {
+        mangled: "_RINvNtCs1234_4core3mem4swaplEB4_",
+        full: "core[3c1c0]::mem::swap::<i32>",
+        short: "core::mem::swap::<i32>",
+    },
+    Case {
+        mangled: "_RNvXs_Cs1234_7mycrateINtB4_3FoolENtNtCs5678_4core5clone5Clone5clone",
+        full: "<mycrate[3c1c0]::Foo<i32> as core[128aac]::clone::Clone>::clone",
+        short: "<mycrate::Foo<i32> as core::clone::Clone>::clone",
+    },
+    Case {
+        mangled: "_RNvMNtCs1234_7mycrate3fooNtB2_3Bar3new",
+        full: "<mycrate[3c1c0]::foo::Bar>::new",
+        short: "<mycrate::foo::Bar>::new",
+    },
+    Case {
+        mangled: "_RINvCs1234_7mycrate3fooFG_RL0_hEuE",
+        full: "mycrate[3c1c0]::foo::<for<'a> fn(&'a u8)>",
+        short: "mycrate::foo::<for<'a> fn(&'a u8)>",
+    },
+    Case {
+        mangled: "_RINvCs1234_7mycrate3fooDNtNtCs5678_4core3ops2FnEL_E",
+        full: "mycrate[3c1c0]::foo::<dyn core[128aac]::ops::Fn>",
+        short: "mycrate::foo::<dyn core::ops::Fn>",
+    },
+    Case {
+        mangled: "_RINvCs1234_7mycrate3fooDINtNtCs5678_4core3ops2FnTlEEp6OutputuEL_E",
+        full: "mycrate[3c1c0]::foo::<dyn core[128aac]::ops::Fn<(i32,), Output = ()>>",
+        short: "mycrate::foo::<dyn core::ops::Fn<(i32,), Output = ()>>",
+    },
+    Case {
+        mangled: "_RINvCs1234_7mycrate3fooKj2a_E",
+        full: "mycrate[3c1c0]::foo::<42usize>",
+        short: "mycrate::foo::<42>",
+    },
+    Case {
+        mangled: "_RINvCs1234_7mycrate3fooKlne_E",
+        full: "mycrate[3c1c0]::foo::<-14i32>",
+        short: "mycrate::foo::<-14>",
+    },
+    Case {
+        mangled: "_RINvCs1234_7mycrate3fooKb1_E",
+        full: "mycrate[3c1c0]::foo::<true>",
+        short: "mycrate::foo::<true>",
+    },
+    Case {
+        mangled: "_RINvCs1234_7mycrate3fooKc78_E",
+        full: "mycrate[3c1c0]::foo::<'x'>",
+        short: "mycrate::foo::<'x'>",
+    },
+    Case {
+        mangled: "_RNvCs1234_7mycrate3foo.llvm.9876",
+        full: "mycrate[3c1c0]::foo",
+        short: "mycrate::foo",
+    },
+
+    // Invalid, printed as they are
+    Case { mangled: "main", full: "main", short: "main" },
+    Case { mangled: "_ZN3fooE_", full: "_ZN3fooE_", short: "_ZN3fooE_" },
+    Case { mangled: "_ZN2$u$E", full: "_ZN2$u$E", short: "_ZN2$u$E" },
+    Case { mangled: "_RNvC", full: "_RNvC", short: "_RNvC" },
+    Case {
+        mangled: "_RINvC1a1bFGzzzzzzzzzz_EuE",
+        full: "_RINvC1a1bFGzzzzzzzzzz_EuE",
+        short: "_RINvC1a1bFGzzzzzzzzzz_EuE",
+    },
+];
+
+/// Levels of `T B<prev> B<prev> E` tuples, each one doubles the output. 20 levels stay below
+/// the recursion limit and would expand to 14 MB.
+const BLOWUP_LEVELS: usize = 20;
+
+#[no_mangle]
+fn main() -> u8 {
+    let mut failed = 0;
+    for case in CASES.iter() {
+        let mut full = Buf::<512>::new();
+        let mut short = Buf::<512>::new();
+        let _ = write!(full, "{}", demangle(case.mangled));
+        let _ = write!(short, "{:#}", demangle(case.mangled));
+
+        if full.as_str() == case.full && short.as_str() == case.short {
+            println!("test {} ... ok", case.mangled);
+        } else {
+            println!("test {} ... FAILED: {:?} and {:?}", case.mangled, full.as_str(), short.as_str());
+            failed += 1;
+        }
+    }
+
+    let name = blowup(BLOWUP_LEVELS).unwrap();
+    let mut out = Count(0);
+    write!(out, "{}", demangle(name.as_str())).unwrap();
+    if out.0 == name.as_str().len() && out.0 < 1024 {
+        println!("test nested backrefs ... ok");
+    } else {
+        println!("test nested backrefs ... FAILED: {} bytes", out.0);
+        failed += 1;
+    }
+
+    println!("");
+    match failed {
+        0 => println!("test result: ok. {} passed", CASES.len() + 1),
+        _ => println!("test result: FAILED. {} passed; {} failed", CASES.len() + 1 - failed, failed),
+    }
+    (failed > 0) as u8
+}
+
+/// `a::f::<i32, (i32, i32), ((i32, i32), (i32, i32)), ...>` with backrefs to the previous
+/// argument, which would expand to 2^levels tuples. The positions of the backrefs start
+/// after `_R`.
+fn blowup(levels: usize) -> Result<Buf<1024>, fmt::Error> {
+    let mut sym = Buf::<1024>::new();
+    sym.write_str("INvC1a1f")?;
+    let mut prev = sym.as_str().len();
+    sym.write_str("l")?;
+    for _ in 0..levels {
+        let pos = sym.as_str().len();
+        sym.write_str("TB")?;
+        base62(&mut sym, prev)?;
+        sym.write_str("B")?;
+        base62(&mut sym, prev)?;
+        sym.write_str("E")?;
+        prev = pos;
+    }
+    sym.write_str("E")?;
+
+    let mut name = Buf::<1024>::new();
+    write!(name, "_R{}", sym.as_str())?;
+    Ok(name)
+}
+
+/// An integer of the v0 scheme, `_` for 0 and n - 1 in base 62 followed by `_` otherwise
+fn base62<W: Write>(out: &mut W, n: usize) -> fmt::Result {
+    const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    if n > 0 {
+        let mut digits = [0u8; 8];
+        let (mut n, mut i) = (n - 1, digits.len());
+        loop {
+            i -= 1;
+            digits[i] = DIGITS[n % 62];
+            n /= 62;
+            if n == 0 {
+                break;
+            }
+        }
+        out.write_str(core::str::from_utf8(&digits[i..]).unwrap())?;
+    }
+    out.write_char('_')
+}
+
+/// Counts the bytes written
+struct Count(usize);
+
+impl Write for Count {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
@@ -0,0 +1,256 @@
+//! File::parse and the checked accessors on a corpus of broken ELF files.
+//!
+//! The corpus is made from the test executable itself: every case maps a private copy of
+//! /proc/self/exe, corrupts one field or cuts the file and checks the error parse() or an
+//! accessor reports. The accessors and the dumps then run over whatever parse() has accepted,
+//! none of them may panic. Run with `./cargo.sh test`.
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate linux;
+use linux::constants::*;
+use linux::elf::{ElfError, File, Ehdr, Shdr, Sym, SHT, EI_CLASS, EI_DATA, ELFCLASS32, ELFDATA2MSB};
+use linux::fd::{AsRawFd, OwnedFd};
+use linux::syscall::*;
+use linux::types::*;
+
+type ElfResult = core::result::Result<(), ElfError>;
+
+struct Case {
+    name: &'static str,
+
+    /// Breaks the file and returns its new length
+    corrupt: fn(&mut [u8]) -> usize,
+
+    /// What parse() fails with, or check() once parse() has succeeded
+    expect: ElfError,
+    check: fn(&File) -> ElfResult,
+}
+
+const CORPUS: [Case; 17] = [
+    Case { name: "empty", corrupt: |_| 0, expect: ElfError::TruncatedHeader, check: |_| Ok(()) },
+    Case { name: "magic only", corrupt: |_| 4, expect: ElfError::TruncatedHeader, check: |_| Ok(()) },
+    Case {
+        name: "truncated header",
+        corrupt: |_| core::mem::size_of::<Ehdr>() - 1,
+        expect: ElfError::TruncatedHeader,
+        check: |_| Ok(()),
+    },
+    Case {
+        name: "bad magic",
+        corrupt: |buf| { buf[1] = b'X'; buf.len() },
+        expect: ElfError::BadMagic,
+        check: |_| Ok(()),
+    },
+    Case {
+        name: "32 bit class",
+        corrupt: |buf| { buf[EI_CLASS] = ELFCLASS32; buf.len() },
+        expect: ElfError::UnsupportedClass(ELFCLASS32),
+        check: |_| Ok(()),
+    },
+    Case {
+        name: "big endian",
+        corrupt: |buf| { buf[EI_DATA] = ELFDATA2MSB; buf.len() },
+        expect: ElfError::UnsupportedEncoding(ELFDATA2MSB),
+        check: |_| Ok(()),
+    },
+    Case {
+        name: "e_phoff past the end",
+        corrupt: |buf| { ehdr(buf).e_phoff = buf.len() as u64; buf.len() },
+        expect: ElfError::OutOfRange("program headers"),
+        check: |_| Ok(()),
+    },
+    Case {
+        name: "e_phoff overflowing",
+        corrupt: |buf| { ehdr(buf).e_phoff = u64::MAX - 8; buf.len() },
+        expect: ElfError::OutOfRange("program headers"),
+        check: |_| Ok(()),
+    },
+    Case {
+        name: "wrong e_phentsize",
+        corrupt: |buf| { ehdr(buf).e_phentsize = 32; buf.len() },
+        expect: ElfError::OutOfRange("program headers"),
+        check: |_| Ok(()),
+    },
+    Case {
+        name: "e_shoff past the end",
+        corrupt: |buf| { ehdr(buf).e_shoff = buf.len() as u64 - 8; buf.len() },
+        expect: ElfError::OutOfRange("section headers"),
+        check: |_| Ok(()),
+    },
+    Case {
+        name: "cut in the section headers",
+        corrupt: |buf| ehdr(buf).e_shoff as usize + 100,
+        expect: ElfError::OutOfRange("section headers"),
+        check: |_| Ok(()),
+    },
+    Case {
+        name: "bad e_shstrndx",
+        corrupt: |buf| { ehdr(buf).e_shstrndx = 0xfff0; buf.len() },
+        expect: ElfError::OutOfRange("section names"),
+        check: |file| file.section_name(&file.shdrs[1]).map(drop),
+    },
+    Case {
+        name: "unterminated strtab",
+        corrupt: |buf| {
+            let link = section(buf, SHT::SHT_SYMTAB).sh_link as usize;
+            let strtab = shdrs(buf)[link].clone();
+            buf[strtab.sh_offset as usize..][..strtab.sh_size as usize].fill(b'x');
+            symbols(buf)[1].st_name = 1;
+            buf.len()
+        },
+        expect: ElfError::BadStringIndex(1),
+        check: |file| symbol_name(file, 1),
+    },
+    Case {
+        name: "st_name past the strtab",
+        corrupt: |buf| { symbols(buf)[1].st_name = u32::MAX; buf.len() },
+        expect: ElfError::BadStringIndex(u32::MAX),
+        check: |file| symbol_name(file, 1),
+    },
+    Case {
+        name: "wrong sh_entsize",
+        corrupt: |buf| { section(buf, SHT::SHT_SYMTAB).sh_entsize = 16; buf.len() },
+        expect: ElfError::OutOfRange("symbol table"),
+        check: |file| symbol_name(file, 1),
+    },
+    Case {
+        name: "sh_offset past the end",
+        corrupt: |buf| { section(buf, SHT::SHT_SYMTAB).sh_offset = buf.len() as u64; buf.len() },
+        expect: ElfError::OutOfRange("symbol table"),
+        check: |file| symbol_name(file, 1),
+    },
+    Case {
+        name: "sh_link past the section headers",
+        corrupt: |buf| { section(buf, SHT::SHT_SYMTAB).sh_link = 0xffff; buf.len() },
+        expect: ElfError::OutOfRange("linked section"),
+        check: |file| symbol_name(file, 1),
+    },
+];
+
+/// Every this many bytes the file is cut off
+const TRUNCATE_STEP: usize = 4093;
+
+#[no_mangle]
+fn main() -> u8 {
+    let exe = open("/proc/self/exe", O_RDONLY | O_CLOEXEC, 0).unwrap();
+    let null = open("/dev/null", O_WRONLY | O_CLOEXEC, 0).unwrap();
+    let mut stat = stat64::default();
+    fstat(exe.as_raw_fd(), &mut stat).unwrap();
+    let size = stat.st_size as usize;
+
+    let mut failed = 0;
+    for case in CORPUS.iter() {
+        let buf = map(&exe, size);
+        let len = (case.corrupt)(buf);
+        let res = match File::parse(&buf[..len]) {
+            Ok(file) => {
+                exercise(&file, &null);
+                (case.check)(&file)
+            }
+            Err(err) => Err(err),
+        };
+        unsafe { munmap(buf.as_mut_ptr(), size).unwrap() };
+
+        match res {
+            Err(err) if err == case.expect => println!("test {} ... ok", case.name),
+            other => {
+                println!("test {} ... FAILED: {:?} instead of {:?}", case.name, other, case.expect);
+                failed += 1;
+            }
+        }
+    }
+
+    // Cut anywhere, including in the middle of the tables the accessors read
+    let buf = map(&exe, size);
+    for len in (0..size).step_by(TRUNCATE_STEP) {
+        if let Ok(file) = File::parse(&buf[..len]) {
+            exercise(&file, &null);
+        }
+    }
+    println!("test truncated every {} bytes ... ok", TRUNCATE_STEP);
+
+    println!("");
+    match failed {
+        0 => println!("test result: ok. {} passed", CORPUS.len() + 1),
+        _ => println!("test result: FAILED. {} passed; {} failed", CORPUS.len() + 1 - failed, failed),
+    }
+    (failed > 0) as u8
+}
+
+/// Run all accessors and the dumps, with the output of the dumps going to `null`
+fn exercise(file: &File, null: &OwnedFd) {
+    for h in file.phdrs {
+        let _ = file.segment_data(h);
+    }
+    for h in file.shdrs {
+        let _ = file.section_name(h);
+        let _ = file.section_data(h);
+        let _ = file.linked(h);
+        let _ = file.shstr(h.sh_name);
+        match h.sh_type() {
+            SHT::SHT_SYMTAB | SHT::SHT_DYNSYM => {
+                for sym in file.symbols(h).unwrap_or(&[]) {
+                    let _ = file.symbol_name(h, sym);
+                    let _ = file.symtab_str(sym.st_name);
+                    let _ = file.strtab(sym.st_name);
+                }
+            }
+            SHT::SHT_DYNAMIC => { let _ = file.dyns(h); }
+            _ => {}
+        }
+    }
+    for section in file.relocation_sections() {
+        let _ = section;
+    }
+    let _ = (file.symtab(), file.dynsym(), file.dynamic(), file.rel(), file.rela());
+
+    let stdout = dup(1).unwrap();
+    dup2(null.as_raw_fd(), 1).unwrap();
+    file.dump_phdrs();
+    file.dump_shdrs();
+    file.dump_symtab(true);
+    file.dump_dynsym(true);
+    file.dump_dynamic();
+    file.dump_rel(true);
+    file.dump_rela(true);
+    dup2(stdout.as_raw_fd(), 1).unwrap();
+}
+
+/// A private copy of the executable, the changes aren't written back
+fn map(exe: &OwnedFd, size: usize) -> &'static mut [u8] {
+    unsafe {
+        let p = mmap(core::ptr::null_mut(), size, PROT_READ | PROT_WRITE, MAP_PRIVATE, exe.as_raw_fd(), 0).unwrap();
+        core::slice::from_raw_parts_mut(p, size)
+    }
+}
+
+fn ehdr(buf: &mut [u8]) -> &mut Ehdr {
+    unsafe { &mut *(buf.as_mut_ptr() as *mut Ehdr) }
+}
+
+fn shdrs(buf: &mut [u8]) -> &mut [Shdr] {
+    let ehdr = ehdr(buf);
+    let (off, num) = (ehdr.e_shoff as usize, ehdr.e_shnum as usize);
+    unsafe { core::slice::from_raw_parts_mut(buf.as_mut_ptr().add(off) as *mut Shdr, num) }
+}
+
+/// The first section of the type, the executable has a .symtab
+fn section(buf: &mut [u8], sh_type: SHT) -> &mut Shdr {
+    let sh_type = sh_type as u32;
+    shdrs(buf).iter_mut().find(|h| h.sh_type == sh_type).unwrap()
+}
+
+fn symbols(buf: &mut [u8]) -> &mut [Sym] {
+    let symtab = section(buf, SHT::SHT_SYMTAB);
+    let (off, num) = (symtab.sh_offset as usize, symtab.sh_size as usize / core::mem::size_of::<Sym>());
+    unsafe { core::slice::from_raw_parts_mut(buf.as_mut_ptr().add(off) as *mut Sym, num) }
+}
+
+/// The name of the symbol `idx` of .symtab through the checked accessors
+fn symbol_name(file: &File, idx: usize) -> ElfResult {
+    let symtab = file.section(SHT::SHT_SYMTAB).ok_or(ElfError::OutOfRange("symbol table"))?;
+    let sym = file.symbols(symtab)?.get(idx).ok_or(ElfError::OutOfRange("symbol table"))?;
+    file.symbol_name(symtab, sym).map(drop)
+}
@@ -0,0 +1,357 @@
+//! Native threads created with clone3.
+//!
+//! Every thread gets its own anonymous mapping: a guard page at the bottom, the stack above it,
+//! the thread-local storage and a control block at the top which holds the closure, its result
+//! and the state shared with the [JoinHandle]. The FS base of the thread points to the control
+//! block, the TLS block ends right below it (x86-64 variant II, see tls.rs). The kernel clears
+//! the tid in the control block and wakes a futex on it when the thread has exited
+//! (CLONE_CHILD_CLEARTID), this is what join() waits for before the mapping is released.
+//!
+//! There is no unwinding: a panicking thread prints the message, stores it in the control
+//! block and exits. join() returns it as a [Panic].
+//!
+//! ```ignore
+//! let handle = thread::spawn(|| 6 * 7)?;
+//! assert_eq!(handle.join().unwrap(), 42);
+//! ```
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::mem::{align_of, size_of, ManuallyDrop, MaybeUninit};
+use core::ptr::{addr_of_mut, null_mut};
+use core::sync::atomic::{AtomicU32, Ordering};
+use core::time::Duration;
+
+use crate::types::*;
+use crate::syscall::*;
+use crate::error::{Error, Result};
+use crate::tls;
+
+const PAGE: usize = 4096;
+const DEFAULT_STACK_SIZE: usize = 2 * 1024 * 1024;
+
+const RUNNING: u32 = 0;
+const FINISHED: u32 = 1;
+const PANICKED: u32 = 2;
+const DETACHED: u32 = 3;
+
+/// The FS base of a thread points here, the TLS block ends right below it. The first word points
+/// to itself so the address can be read with `mov rax, fs:0`.
+#[repr(C)]
+pub(crate) struct Tcb {
+    self_ptr: *mut Tcb,
+    thread: *mut Inner,
+}
+
+/// The state shared between a thread and its JoinHandle
+struct Inner {
+    /// Set to the tid by the kernel before clone3 returns and cleared when the thread has exited
+    tid: AtomicU32,
+
+    /// RUNNING until the thread has finished or panicked, or until the JoinHandle was dropped
+    state: AtomicU32,
+
+    /// The mapping with the stack and this control block
+    map: *mut u8,
+    map_len: usize,
+
+    panic: UnsafeCell<Panic>,
+}
+
+/// The control block. The fields of the closure come last so the offset of the result
+/// doesn't depend on the type of the closure.
+#[repr(C)]
+struct Packet<T, F> {
+    tcb: Tcb,
+    inner: Inner,
+    result: MaybeUninit<T>,
+    f: MaybeUninit<F>,
+}
+
+/// The message of a panicked thread, truncated to 256 bytes
+pub struct Panic {
+    buf: [u8; 256],
+    len: usize,
+}
+
+impl Panic {
+    const fn new() -> Self {
+        Self { buf: [0; 256], len: 0 }
+    }
+
+    pub fn message(&self) -> &str {
+        let bytes = &self.buf[..self.len];
+        match core::str::from_utf8(bytes) {
+            Ok(s) => s,
+            // Cut at the end of the last complete character
+            Err(e) => unsafe { core::str::from_utf8_unchecked(&bytes[..e.valid_up_to()]) },
+        }
+    }
+}
+
+impl fmt::Write for Panic {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let n = s.len().min(self.buf.len() - self.len);
+        self.buf[self.len..self.len + n].copy_from_slice(&s.as_bytes()[..n]);
+        self.len += n;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Panic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Panic").field(&self.message()).finish()
+    }
+}
+
+impl fmt::Display for Panic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+pub struct Builder {
+    stack_size: usize,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Self { stack_size: DEFAULT_STACK_SIZE }
+    }
+
+    /// Size of the stack without the guard page, rounded up to whole pages. The default is 2 MiB.
+    pub fn stack_size(mut self, size: usize) -> Self {
+        self.stack_size = size;
+        self
+    }
+
+    pub fn spawn<F, T>(self, f: F) -> Result<JoinHandle<T>>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let size = size_of::<Packet<T, F>>();
+        let align = align_of::<Packet<T, F>>().max(tls::align()).max(16);
+        let len = PAGE + page_align(self.stack_size) + page_align(tls::size() + size + align);
+
+        let prot = PROT_READ | PROT_WRITE;
+        let flags = MAP_PRIVATE | MAP_ANONYMOUS | MAP_STACK;
+        let map = unsafe { mmap(null_mut(), len, prot, flags, u32::MAX, 0)? };
+
+        unsafe {
+            if let Err(err) = mprotect(map, PAGE, PROT_NONE) {
+                let _ = munmap(map, len);
+                return Err(err);
+            }
+
+            // The control block sits at the top of the mapping with the TLS block below it,
+            // the stack grows down below the TLS block
+            let packet = ((map as usize + len - size) & !(align - 1)) as *mut Packet<T, F>;
+            tls::copy(packet as *mut u8);
+            packet.write(Packet {
+                tcb: Tcb { self_ptr: packet as *mut Tcb, thread: addr_of_mut!((*packet).inner) },
+                inner: Inner {
+                    tid: AtomicU32::new(0),
+                    state: AtomicU32::new(RUNNING),
+                    map,
+                    map_len: len,
+                    panic: UnsafeCell::new(Panic::new()),
+                },
+                result: MaybeUninit::uninit(),
+                f: MaybeUninit::new(f),
+            });
+
+            let tid = (*packet).inner.tid.as_ptr() as u64;
+            let args = clone_args {
+                flags: CLONE_VM | CLONE_FS | CLONE_FILES | CLONE_SIGHAND | CLONE_THREAD | CLONE_SYSVSEM
+                    | CLONE_SETTLS | CLONE_PARENT_SETTID | CLONE_CHILD_CLEARTID,
+                child_tid: tid,
+                parent_tid: tid,
+                stack: map as u64,
+                stack_size: ((packet as usize - tls::size()) & !15) as u64 - map as u64,
+                tls: packet as u64,
+                ..Default::default()
+            };
+
+            match clone_thread(&args, start::<T, F>, packet as *mut u8) {
+                Ok(tid) => Ok(JoinHandle { packet: packet as *mut Packet<T, ()>, tid }),
+                Err(err) => {
+                    (*packet).f.assume_init_drop();
+                    let _ = munmap(map, len);
+                    Err(err)
+                }
+            }
+        }
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn page_align(len: usize) -> usize {
+    (len + PAGE - 1) & !(PAGE - 1)
+}
+
+/// Set up the thread-local storage of the main thread, called once at startup
+pub(crate) unsafe fn init() -> Result<()> {
+    tls::init();
+
+    let align = tls::align().max(align_of::<Tcb>());
+    let len = page_align(tls::size() + size_of::<Tcb>() + align);
+    let map = mmap(null_mut(), len, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS, u32::MAX, 0)?;
+
+    let tcb = ((map as usize + tls::size() + align - 1) & !(align - 1)) as *mut Tcb;
+    tls::copy(tcb as *mut u8);
+    tcb.write(Tcb { self_ptr: tcb, thread: null_mut() });
+    arch_prctl(ARCH_SET_FS, tcb as usize)
+}
+
+/// Entry point of the new thread, called by the clone3 trampoline on the new stack
+unsafe extern "C" fn start<T, F: FnOnce() -> T>(arg: *mut u8) -> ! {
+    let packet = arg as *mut Packet<T, F>;
+    let f = (*packet).f.assume_init_read();
+    (*packet).result.write(f());
+
+    let inner = &(*packet).inner;
+    if inner.state.swap(FINISHED, Ordering::AcqRel) == DETACHED {
+        (*packet).result.assume_init_drop();
+        exit_detached(inner);
+    }
+    exit(0)
+}
+
+/// Nobody is going to join the thread, so it has to release its own mapping. The kernel
+/// mustn't clear the tid in it (CLONE_CHILD_CLEARTID) once it is unmapped.
+unsafe fn exit_detached(inner: &Inner) -> ! {
+    let _ = rt_sigprocmask(SIG_BLOCK, Some(&sigset_t::full()), None);
+    set_tid_address(null_mut());
+    unmap_and_exit(inner.map, inner.map_len)
+}
+
+/// The control block of the calling thread, null before thread::init()
+fn current() -> *mut Tcb {
+    let mut fs = 0u64;
+    let _ = unsafe { arch_prctl(ARCH_GET_FS, &mut fs as *mut u64 as usize) };
+    fs as *mut Tcb
+}
+
+/// Called by the panic handler. Ends the calling thread if it was started by spawn(),
+/// otherwise it returns and the whole process exits.
+pub(crate) fn panicked(info: &core::panic::PanicInfo) {
+    let tcb = current();
+    if tcb.is_null() || unsafe { (*tcb).thread.is_null() } {
+        return;
+    }
+
+    unsafe {
+        let inner = &*(*tcb).thread;
+        let _ = fmt::Write::write_fmt(&mut *inner.panic.get(), format_args!("{}", info.message()));
+        if inner.state.swap(PANICKED, Ordering::AcqRel) == DETACHED {
+            exit_detached(inner);
+        }
+    }
+    exit(0)
+}
+
+/// Block until the kernel has cleared the tid, i.e. the thread doesn't use its stack anymore
+fn wait(inner: &Inner) {
+    loop {
+        let tid = inner.tid.load(Ordering::Acquire);
+        if tid == 0 {
+            return;
+        }
+        // The kernel wakes the cleared tid with a shared futex
+        let _ = unsafe { futex(inner.tid.as_ptr(), FUTEX_WAIT, tid, 0, core::ptr::null(), 0) };
+    }
+}
+
+/// Owns a spawned thread. Dropping the handle detaches the thread, it releases its stack
+/// itself when it finishes.
+pub struct JoinHandle<T> {
+    packet: *mut Packet<T, ()>,
+    tid: pid_t,
+}
+
+unsafe impl<T: Send> Send for JoinHandle<T> {}
+unsafe impl<T: Send> Sync for JoinHandle<T> {}
+
+impl<T> JoinHandle<T> {
+    pub fn id(&self) -> pid_t {
+        self.tid
+    }
+
+    pub fn is_finished(&self) -> bool {
+        unsafe { (*self.packet).inner.tid.load(Ordering::Acquire) == 0 }
+    }
+
+    /// Wait for the thread and return its result, or its message if it has panicked
+    pub fn join(self) -> core::result::Result<T, Panic> {
+        let this = ManuallyDrop::new(self);
+        unsafe {
+            let packet = this.packet;
+            let inner = &(*packet).inner;
+            wait(inner);
+
+            let ret = match inner.state.load(Ordering::Acquire) {
+                FINISHED => Ok((*packet).result.assume_init_read()),
+                _ => Err(inner.panic.get().read()),
+            };
+            let _ = munmap(inner.map, inner.map_len);
+            ret
+        }
+    }
+}
+
+impl<T> Drop for JoinHandle<T> {
+    fn drop(&mut self) {
+        unsafe {
+            let packet = self.packet;
+            let inner = &(*packet).inner;
+            if inner.state.compare_exchange(RUNNING, DETACHED, Ordering::AcqRel, Ordering::Acquire).is_ok() {
+                return;
+            }
+
+            // The thread has already finished, clean up as if it was joined
+            wait(inner);
+            if inner.state.load(Ordering::Acquire) == FINISHED {
+                (*packet).result.assume_init_drop();
+            }
+            let _ = munmap(inner.map, inner.map_len);
+        }
+    }
+}
+
+/// Run `f` in a new thread with the default stack size
+pub fn spawn<F, T>(f: F) -> Result<JoinHandle<T>>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    Builder::new().spawn(f)
+}
+
+/// The kernel's id of the calling thread
+pub fn current_id() -> pid_t {
+    gettid()
+}
+
+pub fn yield_now() {
+    sched_yield();
+}
+
+/// Sleep for at least `duration`, also when interrupted by a signal
+pub fn sleep(duration: Duration) {
+    let mut req = timespec {
+        tv_sec: duration.as_secs() as i64,
+        tv_nsec: duration.subsec_nanos() as i64,
+    };
+    loop {
+        let mut rem = timespec::default();
+        match nanosleep(&req, &mut rem) {
+            Err(Error::EINTR) => req = rem,
+            _ => return,
+        }
+    }
+}
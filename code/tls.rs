@@ -0,0 +1,85 @@
+//! Thread-local storage for `#[thread_local]` statics.
+//!
+//! The initial values of the thread-local variables are in the PT_TLS segment of the executable:
+//! p_filesz bytes of .tdata followed by zeros up to p_memsz for .tbss. Every thread gets its
+//! own copy of this image. x86-64 uses the TLS variant II layout, the block ends right below the
+//! thread pointer and the linker addresses the variables with negative offsets from %fs:
+//!
+//! ```text
+//!   block = tp - size()        tp = %fs
+//!   | .tdata | .tbss | padding | Tcb (self pointer, ...) |
+//! ```
+//!
+//! The first word of the TCB at the thread pointer has to point to itself, the compiler reads
+//! it with `mov rax, fs:0` to get the address of a variable.
+use core::ptr::null_mut;
+use core::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+
+use crate::elf::*;
+use crate::constants::*;
+
+static IMAGE: AtomicPtr<u8> = AtomicPtr::new(null_mut());
+static FILESZ: AtomicUsize = AtomicUsize::new(0);
+static MEMSZ: AtomicUsize = AtomicUsize::new(0);
+static ALIGN: AtomicUsize = AtomicUsize::new(1);
+
+/// Find the PT_TLS segment through the program headers in the aux vector.
+/// Has to be called once at startup before any thread is created.
+pub(crate) unsafe fn init() {
+    let mut phdr: *const Phdr = null_mut();
+    let mut phnum = 0;
+
+    let mut p = crate::AUXV.load(Ordering::Relaxed);
+    while (*p).key != AT_NULL {
+        match (*p).key {
+            AT_PHDR => { phdr = (*p).val as *const Phdr; }
+            AT_PHNUM => { phnum = (*p).val as usize; }
+            _ => { /* ignore */ }
+        }
+        p = p.add(1);
+    }
+    if phdr.is_null() {
+        return;
+    }
+    let phdrs = core::slice::from_raw_parts(phdr, phnum);
+
+    // The headers of a position independent executable are not at their link address
+    let mut bias = 0;
+    for ph in phdrs {
+        if let PT::PT_PHDR = ph.p_type() {
+            bias = phdr as u64 - ph.p_vaddr;
+        }
+    }
+
+    for ph in phdrs {
+        if let PT::PT_TLS = ph.p_type() {
+            IMAGE.store((bias + ph.p_vaddr) as *mut u8, Ordering::Relaxed);
+            FILESZ.store(ph.p_filesz as usize, Ordering::Relaxed);
+            MEMSZ.store(ph.p_memsz as usize, Ordering::Relaxed);
+            ALIGN.store((ph.p_align as usize).max(1), Ordering::Relaxed);
+        }
+    }
+}
+
+/// Alignment of the thread pointer
+pub(crate) fn align() -> usize {
+    ALIGN.load(Ordering::Relaxed)
+}
+
+/// Size of the block below the thread pointer, 0 when the executable has no PT_TLS
+pub(crate) fn size() -> usize {
+    let align = align();
+    (MEMSZ.load(Ordering::Relaxed) + align - 1) & !(align - 1)
+}
+
+/// Copy the TLS image into the block which ends at the thread pointer `tp`
+pub(crate) unsafe fn copy(tp: *mut u8) {
+    let filesz = FILESZ.load(Ordering::Relaxed);
+    let memsz = MEMSZ.load(Ordering::Relaxed);
+    let block = tp.sub(size());
+
+    if filesz > 0 {
+        core::ptr::copy_nonoverlapping(IMAGE.load(Ordering::Relaxed), block, filesz);
+    }
+    core::ptr::write_bytes(block.add(filesz), 0, memsz - filesz);
+}
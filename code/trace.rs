@@ -0,0 +1,248 @@
+//! trace <command> [args...]
+//!
+//! Run a command and print its system calls to stderr with the decoded arguments and the
+//! return values, following the children it forks.
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate linux;
+use core::fmt::{self, Write};
+use linux::io::Buf;
+use linux::process::Command;
+use linux::ptrace::{self, Stop};
+use linux::syscall::*;
+use linux::types::*;
+use linux::{Error, Result};
+
+const MAX_TRACEES: usize = 64;
+
+/// The longest string argument which is printed
+const MAX_STR: usize = 48;
+
+#[derive(Clone, Copy)]
+struct Tracee {
+    pid: pid_t,
+
+    /// Between the entry and the exit of a system call, the line printed at the exit
+    in_syscall: bool,
+    line: Buf<512>,
+}
+
+#[no_mangle]
+fn main() -> u8 {
+    let mut args = linux::env::args().skip(1);
+    let program = match args.next() {
+        Some(program) => program,
+        None => {
+            eprintln!("usage: trace <command> [args...]");
+            return 2;
+        }
+    };
+
+    match run(program, args) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("trace: {}", err);
+            125
+        }
+    }
+}
+
+fn run<'a>(program: &'a str, args: impl Iterator<Item = &'a str>) -> Result<u8> {
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    unsafe { cmd.pre_exec(&ptrace::traceme) };
+    let child = cmd.spawn()?;
+
+    // The child stops with SIGTRAP once execve has succeeded
+    let pid = child.id();
+    match ptrace::wait(pid)? {
+        (_, Stop::Signal(SIGTRAP)) => {}
+        (_, stop) => {
+            eprintln!("trace: unexpected stop {:?}", stop);
+            return Ok(125);
+        }
+    }
+    ptrace::set_options(
+        pid,
+        PTRACE_O_TRACESYSGOOD | PTRACE_O_TRACEFORK | PTRACE_O_TRACEVFORK
+        | PTRACE_O_TRACECLONE | PTRACE_O_TRACEEXEC | PTRACE_O_EXITKILL
+    )?;
+    ptrace::syscall(pid, 0)?;
+
+    let mut tracees = [None::<Tracee>; MAX_TRACEES];
+    tracees[0] = Some(Tracee { pid, in_syscall: false, line: Buf::new() });
+    let mut many = false;
+    let mut code = 0;
+
+    loop {
+        let (pid, stop) = match ptrace::wait(-1) {
+            Err(Error::ECHILD) => return Ok(code),
+            other => other?,
+        };
+
+        let slot = match tracees.iter().position(|t| t.map_or(false, |t| t.pid == pid)) {
+            Some(slot) => slot,
+            None => {
+                // A new child, which starts with SIGSTOP
+                let Some(slot) = tracees.iter().position(Option::is_none) else {
+                    eprintln!("trace: too many processes, detaching {}", pid);
+                    let _ = ptrace::detach(pid, 0);
+                    continue;
+                };
+                tracees[slot] = Some(Tracee { pid, in_syscall: false, line: Buf::new() });
+                many = true;
+                if stop == Stop::Signal(SIGSTOP) {
+                    ptrace::syscall(pid, 0)?;
+                    continue;
+                }
+                slot
+            }
+        };
+        let tracee = tracees[slot].as_mut().unwrap();
+
+        let mut sig = 0;
+        match stop {
+            Stop::Syscall => {
+                let regs = ptrace::get_regs(pid)?;
+                if tracee.in_syscall {
+                    let _ = format_ret(&mut tracee.line, &regs);
+                    print(pid, many, tracee.line.as_str());
+                } else {
+                    tracee.line.clear();
+                    let _ = format_call(&mut tracee.line, pid, &regs);
+                }
+                tracee.in_syscall = !tracee.in_syscall;
+            }
+            Stop::Signal(s) => {
+                let mut line = Buf::<64>::new();
+                let _ = write!(line, "--- {} ---", linux::signal::name(s));
+                print(pid, many, line.as_str());
+                sig = s;
+            }
+            Stop::Event(_) => {}
+            Stop::Exited(_) | Stop::Killed(_) => {
+                // exit and exit_group don't return
+                if tracee.in_syscall {
+                    let _ = write!(tracee.line, " = ?");
+                    print(pid, many, tracee.line.as_str());
+                }
+
+                let mut line = Buf::<64>::new();
+                let _ = match stop {
+                    Stop::Exited(c) => write!(line, "+++ exited with {} +++", c),
+                    Stop::Killed(s) => write!(line, "+++ killed by {} +++", linux::signal::name(s)),
+                    _ => unreachable!(),
+                };
+                print(pid, many, line.as_str());
+
+                if pid == child.id() {
+                    code = match stop {
+                        Stop::Exited(c) => c as u8,
+                        Stop::Killed(s) => 128 + s as u8,
+                        _ => unreachable!(),
+                    };
+                }
+                tracees[slot] = None;
+                continue;
+            }
+        }
+
+        // The tracee may have been killed meanwhile, its exit is reported by the next wait
+        match ptrace::syscall(pid, sig) {
+            Err(Error::ESRCH) => {}
+            other => other?,
+        }
+    }
+}
+
+fn print(pid: pid_t, many: bool, line: &str) {
+    match many {
+        true => eprintln!("[pid {}] {}", pid, line),
+        false => eprintln!("{}", line),
+    }
+}
+
+/// The name and the arguments at the entry of a system call
+fn format_call(out: &mut Buf<512>, pid: pid_t, regs: &user_regs_struct) -> fmt::Result {
+    let nr = regs.orig_rax as isize;
+    let args = [regs.rdi, regs.rsi, regs.rdx, regs.r10, regs.r8, regs.r9];
+
+    let Some(syscall) = SyscallTable::get(nr) else {
+        write!(out, "syscall_{}(", nr)?;
+        for (i, arg) in args.iter().enumerate() {
+            write!(out, "{}{:#x}", if i > 0 { ", " } else { "" }, arg)?;
+        }
+        return write!(out, ")");
+    };
+
+    write!(out, "{}(", syscall.name)?;
+    for (i, (kind, &arg)) in syscall.args.iter().zip(args.iter()).enumerate() {
+        if i > 0 {
+            write!(out, ", ")?;
+        }
+        format_arg(out, pid, *kind, arg)?;
+    }
+    write!(out, ")")
+}
+
+fn format_arg(out: &mut Buf<512>, pid: pid_t, kind: ArgKind, arg: u64) -> fmt::Result {
+    match kind {
+        ArgKind::Fd if arg as i32 == AT_FDCWD => write!(out, "AT_FDCWD"),
+        ArgKind::Fd => write!(out, "{}", arg as i32),
+        ArgKind::Path | ArgKind::Str | ArgKind::Ptr if arg == 0 => write!(out, "NULL"),
+        ArgKind::Path | ArgKind::Str => {
+            let mut buf = [0u8; MAX_STR];
+            match ptrace::read_str(pid, arg as usize, &mut buf) {
+                Ok(s) => {
+                    quote(out, s)?;
+                    if s.len() == MAX_STR {
+                        write!(out, "...")?;
+                    }
+                    Ok(())
+                }
+                Err(_) => write!(out, "{:#x}", arg),
+            }
+        }
+        ArgKind::Flags if arg == 0 => write!(out, "0"),
+        ArgKind::Flags | ArgKind::Ptr => write!(out, "{:#x}", arg),
+        ArgKind::Len => write!(out, "{}", arg),
+        // Mostly a C int, whose upper half isn't sign extended
+        ArgKind::Int if arg >> 32 == 0 => write!(out, "{}", arg as u32 as i32),
+        ArgKind::Int => write!(out, "{}", arg as i64),
+    }
+}
+
+fn quote(out: &mut Buf<512>, s: &[u8]) -> fmt::Result {
+    write!(out, "\"")?;
+    for &c in s {
+        match c {
+            b'"' => write!(out, "\\\"")?,
+            b'\\' => write!(out, "\\\\")?,
+            b'\n' => write!(out, "\\n")?,
+            b'\t' => write!(out, "\\t")?,
+            0x20..=0x7e => write!(out, "{}", c as char)?,
+            _ => write!(out, "\\x{:02x}", c)?,
+        }
+    }
+    write!(out, "\"")
+}
+
+/// The return value at the exit of a system call
+fn format_ret(out: &mut Buf<512>, regs: &user_regs_struct) -> fmt::Result {
+    let ret = regs.rax as i64;
+    let last = isize::from(Error::EHWPOISON) as i64;
+
+    match regs.orig_rax as isize {
+        _ if (-last..0).contains(&ret) => {
+            let err = Error::from(-ret as isize);
+            write!(out, " = -1 {:?} ({})", err, err.as_str())
+        }
+        // Restarted after a signal, these never reach the process
+        _ if (-4095..0).contains(&ret) => write!(out, " = ? (errno {})", -ret),
+        SYS_MMAP | SYS_MREMAP | SYS_BRK | SYS_SHMAT => write!(out, " = {:#x}", ret),
+        _ => write!(out, " = {}", ret),
+    }
+}
+
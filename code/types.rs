@@ -6,6 +6,8 @@ pub type off_t = usize;
 pub type nfds_t = u64;
 pub type time_t = i64;
 pub type pid_t = i32;
+pub type uid_t = u32;
+pub type gid_t = u32;
 pub type subseconds_t = i64;
 pub type clockid_t = i32;
 
@@ -62,15 +64,549 @@ pub struct timespec {
     pub tv_nsec: i64,
 }
 
-/*
+#[repr(C)]
+#[derive(Debug, Clone, Default)]
+pub struct rusage {
+    pub ru_utime: timeval,
+    pub ru_stime: timeval,
+    pub ru_maxrss: i64,
+    pub ru_ixrss: i64,
+    pub ru_idrss: i64,
+    pub ru_isrss: i64,
+    pub ru_minflt: i64,
+    pub ru_majflt: i64,
+    pub ru_nswap: i64,
+    pub ru_inblock: i64,
+    pub ru_oublock: i64,
+    pub ru_msgsnd: i64,
+    pub ru_msgrcv: i64,
+    pub ru_nsignals: i64,
+    pub ru_nvcsw: i64,
+    pub ru_nivcsw: i64,
+}
+
+/// The kernel's siginfo is a 128 byte struct where everything after the first three fields
+/// is a union whose active member depends on the signal and si_code. The accessors below
+/// read the union at the offsets used on x86-64.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct siginfo_t {
+    pub si_signo: i32,
+    pub si_errno: i32,
+    pub si_code: i32,
+    __pad0: i32,
+    __fields: [u64; 14],
+}
+
+impl siginfo_t {
+    fn field<T: Copy>(&self, offset: usize) -> T {
+        unsafe { (self.__fields.as_ptr() as *const u8).add(offset).cast::<T>().read_unaligned() }
+    }
+
+    /// Sending process (kill, SIGCHLD)
+    pub fn si_pid(&self) -> pid_t {
+        self.field(0)
+    }
+
+    /// Real user id of the sending process (kill, SIGCHLD)
+    pub fn si_uid(&self) -> uid_t {
+        self.field(4)
+    }
+
+    /// Exit code or signal of the child (SIGCHLD)
+    pub fn si_status(&self) -> i32 {
+        self.field(8)
+    }
+
+    /// Faulting address (SIGSEGV, SIGBUS, SIGILL, SIGFPE)
+    pub fn si_addr(&self) -> *const u8 {
+        self.field(0)
+    }
+}
+
+/// Signal mask where the bit n-1 stands for the signal n
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct sigset_t(pub u64);
+
+impl sigset_t {
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn full() -> Self {
+        Self(!0)
+    }
+
+    /// The bit of the signal, none for a number outside of 1..=64
+    const fn bit(sig: i32) -> u64 {
+        match sig {
+            1..=64 => 1 << (sig - 1),
+            _ => 0,
+        }
+    }
+
+    pub const fn with(self, sig: i32) -> Self {
+        Self(self.0 | Self::bit(sig))
+    }
+
+    pub fn add(&mut self, sig: i32) {
+        self.0 |= Self::bit(sig);
+    }
+
+    pub fn del(&mut self, sig: i32) {
+        self.0 &= !Self::bit(sig);
+    }
+
+    pub fn contains(&self, sig: i32) -> bool {
+        self.0 & Self::bit(sig) != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// The kernel version of the struct which differs from the one in glibc
+#[repr(C)]
+#[derive(Debug, Clone, Default)]
+pub struct sigaction {
+    pub sa_handler: usize,
+    pub sa_flags: u64,
+    pub sa_restorer: usize,
+    pub sa_mask: sigset_t,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct statx_timestamp {
+    pub tv_sec: i64,
+    pub tv_nsec: u32,
+    __reserved: i32,
+}
+
+/// The fields which are not set in stx_mask are zero
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct statx {
+    pub stx_mask: u32,
+    pub stx_blksize: u32,
+    pub stx_attributes: u64,
+    pub stx_nlink: u32,
+    pub stx_uid: u32,
+    pub stx_gid: u32,
+    pub stx_mode: u16,
+    __spare0: u16,
+    pub stx_ino: u64,
+    pub stx_size: u64,
+    pub stx_blocks: u64,
+    pub stx_attributes_mask: u64,
+    pub stx_atime: statx_timestamp,
+    pub stx_btime: statx_timestamp,
+    pub stx_ctime: statx_timestamp,
+    pub stx_mtime: statx_timestamp,
+    pub stx_rdev_major: u32,
+    pub stx_rdev_minor: u32,
+    pub stx_dev_major: u32,
+    pub stx_dev_minor: u32,
+    pub stx_mnt_id: u64,
+    pub stx_dio_mem_align: u32,
+    pub stx_dio_offset_align: u32,
+    pub stx_subvol: u64,
+    pub stx_atomic_write_unit_min: u32,
+    pub stx_atomic_write_unit_max: u32,
+    pub stx_atomic_write_segments_max: u32,
+    pub stx_dio_read_offset_align: u32,
+    pub stx_atomic_write_unit_max_opt: u32,
+    __spare2: u32,
+    __spare3: [u64; 8],
+}
+
+/// Attributes of mount_setattr, the propagation is one of the MS_SHARED, MS_SLAVE, MS_PRIVATE
+/// and MS_UNBINDABLE flags
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct mount_attr {
+    pub attr_set: u64,
+    pub attr_clr: u64,
+    pub propagation: u64,
+    pub userns_fd: u64,
+}
+
+/// The mount of statmount and listmount, the mounts are identified by their unique id
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct mnt_id_req {
+    pub size: u32,
+    pub mnt_ns_fd: u32,
+    pub mnt_id: u64,
+    pub param: u64,
+    pub mnt_ns_id: u64,
+}
+
+/// Header of the buffer of statmount, the [str] fields are offsets into the strings after it
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct statmount {
+    pub size: u32,
+    pub mnt_opts: u32,
+    pub mask: u64,
+    pub sb_dev_major: u32,
+    pub sb_dev_minor: u32,
+    pub sb_magic: u64,
+    pub sb_flags: u32,
+    pub fs_type: u32,
+    pub mnt_id: u64,
+    pub mnt_parent_id: u64,
+    pub mnt_id_old: u32,
+    pub mnt_parent_id_old: u32,
+    pub mnt_attr: u64,
+    pub mnt_propagation: u64,
+    pub mnt_peer_group: u64,
+    pub mnt_master: u64,
+    pub propagate_from: u64,
+    pub mnt_root: u32,
+    pub mnt_point: u32,
+    pub mnt_ns_id: u64,
+    pub fs_subtype: u32,
+    pub sb_source: u32,
+    pub opt_num: u32,
+    pub opt_array: u32,
+    pub opt_sec_num: u32,
+    pub opt_sec_array: u32,
+    pub supported_mask: u64,
+    pub mnt_uidmap_num: u32,
+    pub mnt_uidmap: u32,
+    pub mnt_gidmap_num: u32,
+    pub mnt_gidmap: u32,
+    __spare2: [u64; 43],
+}
+
+/// An instruction of a classic BPF program
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct sock_filter {
+    pub code: u16,
+    pub jt: u8,
+    pub jf: u8,
+    pub k: u32,
+}
+
 #[repr(C)]
 #[derive(Debug)]
-pub(crate) struct iovec {
+pub struct sock_fprog {
+    pub len: u16,
+    pub filter: *const sock_filter,
+}
+
+/// The input of a seccomp filter, which loads its fields as 32 bit words
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct seccomp_data {
+    pub nr: i32,
+    pub arch: u32,
+    pub instruction_pointer: u64,
+    pub args: [u64; 6],
+}
+
+/// A system call which waits for the supervisor with SECCOMP_RET_USER_NOTIF
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct seccomp_notif {
+    pub id: u64,
+    pub pid: u32,
+    pub flags: u32,
+    pub data: seccomp_data,
+}
+
+/// The answer of the supervisor, the system call returns `val` or fails with `error`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct seccomp_notif_resp {
+    pub id: u64,
+    pub val: i64,
+    pub error: i32,
+    pub flags: u32,
+}
+
+/// The access rights a landlock ruleset handles, everything else stays allowed
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct landlock_ruleset_attr {
+    pub handled_access_fs: u64,
+    pub handled_access_net: u64,
+    pub scoped: u64,
+}
+
+/// Rule of LANDLOCK_RULE_PATH_BENEATH, allows the access to the file hierarchy of parent_fd
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct landlock_path_beneath_attr {
+    pub allowed_access: u64,
+    pub parent_fd: i32,
+}
+
+/// Rule of LANDLOCK_RULE_NET_PORT, the port is in host byte order
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct landlock_net_port_attr {
+    pub allowed_access: u64,
+    pub port: u64,
+}
+
+/// How openat2 opens the path, `flags` and `mode` are the ones of open() and `resolve`
+/// restricts the path resolution with RESOLVE_*
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct open_how {
+    pub flags: u64,
+    pub mode: u64,
+    pub resolve: u64,
+}
+
+/// Header of a record returned by getdents64, followed by the nul terminated name and padding
+/// up to d_reclen
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct linux_dirent64 {
+    pub d_ino: u64,
+    pub d_off: i64,
+    pub d_reclen: u16,
+    pub d_type: u8,
+}
+
+/// Packed on x86_64 to stay compatible with the 32 bit layout
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct epoll_event {
+    pub events: u32,
+    pub data: u64,
+}
+
+/// The registers of a tracee (arch/x86/include/asm/user_64.h). At a syscall stop `orig_rax`
+/// is the number, `rax` the return value and the arguments are in rdi, rsi, rdx, r10, r8, r9.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct user_regs_struct {
+    pub r15: u64,
+    pub r14: u64,
+    pub r13: u64,
+    pub r12: u64,
+    pub rbp: u64,
+    pub rbx: u64,
+    pub r11: u64,
+    pub r10: u64,
+    pub r9: u64,
+    pub r8: u64,
+    pub rax: u64,
+    pub rcx: u64,
+    pub rdx: u64,
+    pub rsi: u64,
+    pub rdi: u64,
+    pub orig_rax: u64,
+    pub rip: u64,
+    pub cs: u64,
+    pub eflags: u64,
+    pub rsp: u64,
+    pub ss: u64,
+    pub fs_base: u64,
+    pub gs_base: u64,
+    pub ds: u64,
+    pub es: u64,
+    pub fs: u64,
+    pub gs: u64,
+}
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct iovec {
     pub iov_base: *mut u8,
     pub iov_len: usize,
 }
-*/
 
+pub type sa_family_t = u16;
+pub type socklen_t = u32;
+
+/// IPv4 address in network byte order
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct in_addr {
+    pub s_addr: u32,
+}
+
+/// The port and the address are in network byte order
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct sockaddr_in {
+    pub sin_family: sa_family_t,
+    pub sin_port: u16,
+    pub sin_addr: in_addr,
+    pub sin_zero: [u8; 8],
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct in6_addr {
+    pub s6_addr: [u8; 16],
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct sockaddr_in6 {
+    pub sin6_family: sa_family_t,
+    pub sin6_port: u16,
+    pub sin6_flowinfo: u32,
+    pub sin6_addr: in6_addr,
+    pub sin6_scope_id: u32,
+}
+
+/// A path when sun_path starts with a non-zero byte, an abstract name otherwise. The length
+/// passed along with the address tells where the name ends.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct sockaddr_un {
+    pub sun_family: sa_family_t,
+    pub sun_path: [u8; 108],
+}
+
+impl Default for sockaddr_un {
+    fn default() -> Self {
+        Self { sun_family: 0, sun_path: [0; 108] }
+    }
+}
+
+/// Large enough and suitably aligned for any of the sockaddr types
+#[repr(C, align(8))]
+#[derive(Debug, Clone, Copy)]
+pub struct sockaddr_storage {
+    pub ss_family: sa_family_t,
+    pub __ss_data: [u8; 126],
+}
+
+impl Default for sockaddr_storage {
+    fn default() -> Self {
+        Self { ss_family: 0, __ss_data: [0; 126] }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct msghdr {
+    pub msg_name: *mut u8,
+    pub msg_namelen: socklen_t,
+    pub msg_iov: *mut iovec,
+    pub msg_iovlen: usize,
+    pub msg_control: *mut u8,
+    pub msg_controllen: usize,
+    pub msg_flags: i32,
+}
+
+
+/// One futex of futex_waitv and futex_requeue
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct futex_waitv {
+    pub val: u64,
+    pub uaddr: u64,
+    pub flags: u32,
+    pub __reserved: u32,
+}
+
+/// Arguments of clone3, the pointers are passed as u64
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct clone_args {
+    pub flags: u64,
+    pub pidfd: u64,
+    pub child_tid: u64,
+    pub parent_tid: u64,
+    pub exit_signal: u64,
+    pub stack: u64,
+    pub stack_size: u64,
+    pub tls: u64,
+    pub set_tid: u64,
+    pub set_tid_size: u64,
+    pub cgroup: u64,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct io_sqring_offsets {
+    pub head: u32,
+    pub tail: u32,
+    pub ring_mask: u32,
+    pub ring_entries: u32,
+    pub flags: u32,
+    pub dropped: u32,
+    pub array: u32,
+    pub resv1: u32,
+    pub user_addr: u64,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct io_cqring_offsets {
+    pub head: u32,
+    pub tail: u32,
+    pub ring_mask: u32,
+    pub ring_entries: u32,
+    pub overflow: u32,
+    pub cqes: u32,
+    pub flags: u32,
+    pub resv1: u32,
+    pub user_addr: u64,
+}
+
+/// Filled by io_uring_setup with the sizes of the rings and the offsets of their fields
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct io_uring_params {
+    pub sq_entries: u32,
+    pub cq_entries: u32,
+    pub flags: u32,
+    pub sq_thread_cpu: u32,
+    pub sq_thread_idle: u32,
+    pub features: u32,
+    pub wq_fd: u32,
+    pub resv: [u32; 3],
+    pub sq_off: io_sqring_offsets,
+    pub cq_off: io_cqring_offsets,
+}
+
+/// Submission queue entry. The kernel overlays several fields with unions, they are named
+/// after the most common member here:
+/// - off: addr2
+/// - addr: splice_off_in
+/// - op_flags: rw_flags, fsync_flags, timeout_flags, accept_flags, open_flags, msg_flags, ...
+/// - buf_index: buf_group
+/// - file_index: splice_fd_in
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct io_uring_sqe {
+    pub opcode: u8,
+    pub flags: u8,
+    pub ioprio: u16,
+    pub fd: i32,
+    pub off: u64,
+    pub addr: u64,
+    pub len: u32,
+    pub op_flags: u32,
+    pub user_data: u64,
+    pub buf_index: u16,
+    pub personality: u16,
+    pub file_index: i32,
+    pub addr3: u64,
+    pub __pad2: u64,
+}
+
+/// Completion queue entry, res is the return value of the operation or a negative errno
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct io_uring_cqe {
+    pub user_data: u64,
+    pub res: i32,
+    pub flags: u32,
+}
 
 #[repr(C)]
 #[derive(Clone, Copy)]
@@ -0,0 +1,470 @@
+//! Batched asynchronous I/O with io_uring.
+//!
+//! The submission queue (SQ) and the completion queue (CQ) are rings shared with the kernel.
+//! Entries are built with [Sqe], pushed into the SQ and handed to the kernel with one
+//! io_uring_enter call for the whole batch. The results are read back from the CQ as [Cqe]s
+//! which carry the user_data of their submission.
+//!
+//! ```ignore
+//! let mut ring = IoUring::new(8)?;
+//! let mut buf = [0u8; 4096];
+//! unsafe { ring.push(&Sqe::read(fd, &mut buf, 0).user_data(1))? };
+//! ring.submit_and_wait(1)?;
+//! let cqe = ring.completion().unwrap();
+//! let n = cqe.result()?;
+//! ```
+//!
+//! Pushing is unsafe: the kernel reads and writes the memory an entry points to (buffers,
+//! iovecs, addresses, paths, timespecs) until its completion has been reaped.
+use core::ffi::CStr;
+use core::mem::size_of;
+use core::ptr::null_mut;
+use core::sync::atomic::{fence, AtomicU32, Ordering};
+
+use crate::fd::{AsFd, AsRawFd, BorrowedFd, OwnedFd};
+use crate::types::*;
+use crate::syscall::*;
+use crate::error::{Error, Result};
+
+/// A submission queue entry
+#[derive(Debug, Clone, Copy)]
+pub struct Sqe(io_uring_sqe);
+
+impl Sqe {
+    fn new(opcode: u8, fd: i32) -> Self {
+        Self(io_uring_sqe { opcode, fd, ..Default::default() })
+    }
+
+    pub fn nop() -> Self {
+        Self::new(IORING_OP_NOP, -1)
+    }
+
+    /// Read at `off`, or at the file position if `off` is u64::MAX
+    pub fn read(fd: u32, buf: &mut [u8], off: u64) -> Self {
+        let mut sqe = Self::new(IORING_OP_READ, fd as i32);
+        sqe.0.addr = buf.as_mut_ptr() as u64;
+        sqe.0.len = buf.len() as u32;
+        sqe.0.off = off;
+        sqe
+    }
+
+    /// Write at `off`, or at the file position if `off` is u64::MAX
+    pub fn write(fd: u32, buf: &[u8], off: u64) -> Self {
+        let mut sqe = Self::new(IORING_OP_WRITE, fd as i32);
+        sqe.0.addr = buf.as_ptr() as u64;
+        sqe.0.len = buf.len() as u32;
+        sqe.0.off = off;
+        sqe
+    }
+
+    pub fn readv(fd: u32, iov: &[iovec], off: u64) -> Self {
+        let mut sqe = Self::new(IORING_OP_READV, fd as i32);
+        sqe.0.addr = iov.as_ptr() as u64;
+        sqe.0.len = iov.len() as u32;
+        sqe.0.off = off;
+        sqe
+    }
+
+    pub fn writev(fd: u32, iov: &[iovec], off: u64) -> Self {
+        let mut sqe = Self::new(IORING_OP_WRITEV, fd as i32);
+        sqe.0.addr = iov.as_ptr() as u64;
+        sqe.0.len = iov.len() as u32;
+        sqe.0.off = off;
+        sqe
+    }
+
+    /// Read into a part of the buffer registered at `index` with register_buffers()
+    pub fn read_fixed(fd: u32, buf: &mut [u8], off: u64, index: u16) -> Self {
+        let mut sqe = Self::read(fd, buf, off);
+        sqe.0.opcode = IORING_OP_READ_FIXED;
+        sqe.0.buf_index = index;
+        sqe
+    }
+
+    /// Write from a part of the buffer registered at `index` with register_buffers()
+    pub fn write_fixed(fd: u32, buf: &[u8], off: u64, index: u16) -> Self {
+        let mut sqe = Self::write(fd, buf, off);
+        sqe.0.opcode = IORING_OP_WRITE_FIXED;
+        sqe.0.buf_index = index;
+        sqe
+    }
+
+    /// The result is the new socket, the peer address is written into `addr` and `len`
+    /// which has to be initialized with the size of `addr`
+    pub fn accept(fd: u32, addr: &mut sockaddr_storage, len: &mut socklen_t, flags: i32) -> Self {
+        let mut sqe = Self::new(IORING_OP_ACCEPT, fd as i32);
+        sqe.0.addr = addr as *mut sockaddr_storage as u64;
+        sqe.0.off = len as *mut socklen_t as u64;
+        sqe.0.op_flags = flags as u32;
+        sqe
+    }
+
+    pub fn connect(fd: u32, addr: &sockaddr_storage, len: socklen_t) -> Self {
+        let mut sqe = Self::new(IORING_OP_CONNECT, fd as i32);
+        sqe.0.addr = addr as *const sockaddr_storage as u64;
+        sqe.0.off = len as u64;
+        sqe
+    }
+
+    pub fn send(fd: u32, buf: &[u8], flags: i32) -> Self {
+        let mut sqe = Self::new(IORING_OP_SEND, fd as i32);
+        sqe.0.addr = buf.as_ptr() as u64;
+        sqe.0.len = buf.len() as u32;
+        sqe.0.op_flags = flags as u32;
+        sqe
+    }
+
+    pub fn recv(fd: u32, buf: &mut [u8], flags: i32) -> Self {
+        let mut sqe = Self::new(IORING_OP_RECV, fd as i32);
+        sqe.0.addr = buf.as_mut_ptr() as u64;
+        sqe.0.len = buf.len() as u32;
+        sqe.0.op_flags = flags as u32;
+        sqe
+    }
+
+    /// Completes with ETIME when `ts` has elapsed, or with 0 once `count` other entries have
+    /// completed (0 waits for the time only). IORING_TIMEOUT_ABS makes `ts` an absolute
+    /// CLOCK_MONOTONIC time.
+    pub fn timeout(ts: &timespec, count: u32, flags: u32) -> Self {
+        let mut sqe = Self::new(IORING_OP_TIMEOUT, -1);
+        sqe.0.addr = ts as *const timespec as u64;
+        sqe.0.len = 1;
+        sqe.0.off = count as u64;
+        sqe.0.op_flags = flags;
+        sqe
+    }
+
+    /// The result is the new file descriptor. `dirfd` may be AT_FDCWD (-100).
+    pub fn openat(dirfd: i32, path: &CStr, flags: i32, mode: mode_t) -> Self {
+        let mut sqe = Self::new(IORING_OP_OPENAT, dirfd);
+        sqe.0.addr = path.as_ptr() as u64;
+        sqe.0.len = mode as u32;
+        sqe.0.op_flags = flags as u32;
+        sqe
+    }
+
+    pub fn close(fd: u32) -> Self {
+        Self::new(IORING_OP_CLOSE, fd as i32)
+    }
+
+    /// IORING_FSYNC_DATASYNC behaves like fdatasync
+    pub fn fsync(fd: u32, flags: u32) -> Self {
+        let mut sqe = Self::new(IORING_OP_FSYNC, fd as i32);
+        sqe.0.op_flags = flags;
+        sqe
+    }
+
+    /// Returned unchanged in the completion
+    pub fn user_data(mut self, data: u64) -> Self {
+        self.0.user_data = data;
+        self
+    }
+
+    /// IOSQE_* flags, e.g. IOSQE_IO_LINK to start the next entry after this one completed
+    pub fn flags(mut self, flags: u8) -> Self {
+        self.0.flags |= flags;
+        self
+    }
+
+    /// The fd is an index into the files registered with register_files()
+    pub fn fixed_file(self) -> Self {
+        self.flags(IOSQE_FIXED_FILE)
+    }
+
+    pub fn raw(&self) -> &io_uring_sqe {
+        &self.0
+    }
+}
+
+/// A completion queue entry
+#[derive(Debug, Clone, Copy)]
+pub struct Cqe(io_uring_cqe);
+
+impl Cqe {
+    pub fn user_data(&self) -> u64 {
+        self.0.user_data
+    }
+
+    /// The return value of the operation, e.g. the number of bytes or a file descriptor
+    pub fn result(&self) -> Result<u32> {
+        match self.0.res < 0 {
+            true => Err(Error::from(-self.0.res as isize)),
+            false => Ok(self.0.res as u32),
+        }
+    }
+
+    pub fn flags(&self) -> u32 {
+        self.0.flags
+    }
+}
+
+/// A shared memory region of the rings
+struct Mapping {
+    ptr: *mut u8,
+    len: usize,
+}
+
+impl Mapping {
+    fn new(fd: u32, off: usize, len: usize) -> Result<Self> {
+        let prot = PROT_READ | PROT_WRITE;
+        let flags = MAP_SHARED | MAP_POPULATE;
+        let ptr = unsafe { mmap(null_mut(), len, prot, flags, fd, off)? };
+        Ok(Self { ptr, len })
+    }
+
+    /// # Safety
+    /// The kernel's offset has to point to a u32 inside the mapping
+    unsafe fn atomic(&self, off: u32) -> &AtomicU32 {
+        &*(self.ptr.add(off as usize) as *const AtomicU32)
+    }
+
+    unsafe fn value(&self, off: u32) -> u32 {
+        *(self.ptr.add(off as usize) as *const u32)
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        let _ = unsafe { munmap(self.ptr, self.len) };
+    }
+}
+
+/// An io_uring instance with its mapped rings
+pub struct IoUring {
+    fd: OwnedFd,
+    params: io_uring_params,
+
+    sq_ring: Mapping,
+    cq_ring: Option<Mapping>,
+    sqes: Mapping,
+
+    sq_mask: u32,
+    sq_entries: u32,
+    cq_mask: u32,
+
+    /// Entries pushed since the last submit, they are published to the kernel by submit()
+    sq_tail: u32,
+}
+
+impl IoUring {
+    pub fn new(entries: u32) -> Result<Self> {
+        Self::with_params(entries, io_uring_params::default())
+    }
+
+    /// Set up a ring with IORING_SETUP_* flags and their arguments in `params`
+    pub fn with_params(entries: u32, mut params: io_uring_params) -> Result<Self> {
+        let fd = io_uring_setup(entries, &mut params)?;
+        Self::map(fd, params)
+    }
+
+    fn map(fd: OwnedFd, params: io_uring_params) -> Result<Self> {
+        let raw = fd.as_raw_fd();
+        let sq_len = params.sq_off.array as usize + params.sq_entries as usize * size_of::<u32>();
+        let cq_len = params.cq_off.cqes as usize + params.cq_entries as usize * size_of::<io_uring_cqe>();
+
+        // Since Linux 5.4 both rings live in a single mapping
+        let single = params.features & IORING_FEAT_SINGLE_MMAP != 0;
+        let sq_ring = match single {
+            true => Mapping::new(raw, IORING_OFF_SQ_RING, sq_len.max(cq_len))?,
+            false => Mapping::new(raw, IORING_OFF_SQ_RING, sq_len)?,
+        };
+        let cq_ring = match single {
+            true => None,
+            false => Some(Mapping::new(raw, IORING_OFF_CQ_RING, cq_len)?),
+        };
+        let sqes = Mapping::new(raw, IORING_OFF_SQES, params.sq_entries as usize * size_of::<io_uring_sqe>())?;
+
+        let mut ring = Self {
+            fd,
+            params,
+            sq_ring,
+            cq_ring,
+            sqes,
+            sq_mask: 0,
+            sq_entries: 0,
+            cq_mask: 0,
+            sq_tail: 0,
+        };
+
+        unsafe {
+            ring.sq_mask = ring.sq_ring.value(params.sq_off.ring_mask);
+            ring.sq_entries = ring.sq_ring.value(params.sq_off.ring_entries);
+            ring.cq_mask = ring.cq().value(params.cq_off.ring_mask);
+            ring.sq_tail = ring.sq_ring.atomic(params.sq_off.tail).load(Ordering::Relaxed);
+
+            // The indirection array is never used, entry i of the array always points to sqe i
+            let array = ring.sq_ring.ptr.add(params.sq_off.array as usize) as *mut u32;
+            for idx in 0..ring.sq_entries {
+                array.add(idx as usize).write(idx);
+            }
+        }
+        Ok(ring)
+    }
+
+    fn cq(&self) -> &Mapping {
+        self.cq_ring.as_ref().unwrap_or(&self.sq_ring)
+    }
+
+    pub fn fd(&self) -> u32 {
+        self.fd.as_raw_fd()
+    }
+
+    /// The parameters returned by io_uring_setup, e.g. the supported IORING_FEAT_* features
+    pub fn params(&self) -> &io_uring_params {
+        &self.params
+    }
+
+    /// Number of entries which can be pushed before the next submit
+    pub fn sq_space_left(&self) -> u32 {
+        // The kernel advances the head when it has consumed the entries
+        let head = unsafe { self.sq_ring.atomic(self.params.sq_off.head) }.load(Ordering::Acquire);
+        self.sq_entries - self.sq_tail.wrapping_sub(head)
+    }
+
+    /// Copy the entry into the submission queue, fails with EBUSY when the queue is full.
+    ///
+    /// # Safety
+    /// The memory the entry points to has to stay valid and must not be accessed in a
+    /// conflicting way until its completion has been reaped.
+    pub unsafe fn push(&mut self, sqe: &Sqe) -> Result<()> {
+        if self.sq_space_left() == 0 {
+            return Err(Error::EBUSY);
+        }
+        let idx = self.sq_tail & self.sq_mask;
+        (self.sqes.ptr as *mut io_uring_sqe).add(idx as usize).write(sqe.0);
+        self.sq_tail = self.sq_tail.wrapping_add(1);
+        Ok(())
+    }
+
+    /// Push all entries or none of them
+    ///
+    /// # Safety
+    /// See push()
+    pub unsafe fn push_all(&mut self, sqes: &[Sqe]) -> Result<()> {
+        if (self.sq_space_left() as usize) < sqes.len() {
+            return Err(Error::EBUSY);
+        }
+        for sqe in sqes {
+            self.push(sqe)?;
+        }
+        Ok(())
+    }
+
+    /// Publish the pushed entries to the kernel, the store of the tail has to be ordered
+    /// after the writes of the entries. Returns the entries the kernel hasn't consumed yet,
+    /// which includes those left over by an earlier partial submit.
+    fn publish(&self) -> u32 {
+        let tail = unsafe { self.sq_ring.atomic(self.params.sq_off.tail) };
+        tail.store(self.sq_tail, Ordering::Release);
+        let head = unsafe { self.sq_ring.atomic(self.params.sq_off.head) }.load(Ordering::Acquire);
+        self.sq_tail.wrapping_sub(head)
+    }
+
+    /// Hand the pushed entries to the kernel and return how many it has consumed
+    pub fn submit(&mut self) -> Result<usize> {
+        self.submit_and_wait(0)
+    }
+
+    /// Submit and block until at least `want` completions are available
+    pub fn submit_and_wait(&mut self, want: u32) -> Result<usize> {
+        let pending = self.publish();
+        let mut flags = match want > 0 {
+            true => IORING_ENTER_GETEVENTS,
+            false => 0,
+        };
+
+        // With a kernel polling thread only wake it up if it went to sleep. The fence orders
+        // the store of the tail before the load of the flags.
+        if self.params.flags & IORING_SETUP_SQPOLL != 0 {
+            fence(Ordering::SeqCst);
+            let sq_flags = unsafe { self.sq_ring.atomic(self.params.sq_off.flags) }.load(Ordering::Acquire);
+            if sq_flags & IORING_SQ_NEED_WAKEUP != 0 {
+                flags |= IORING_ENTER_SQ_WAKEUP;
+            }
+            if want == 0 && flags == 0 {
+                return Ok(pending as usize);
+            }
+        }
+
+        unsafe { io_uring_enter(self.fd(), pending, want, flags, None) }
+    }
+
+    /// Number of completions which can be reaped
+    pub fn cq_ready(&self) -> u32 {
+        let cq = self.cq();
+        let head = unsafe { cq.atomic(self.params.cq_off.head) }.load(Ordering::Relaxed);
+        let tail = unsafe { cq.atomic(self.params.cq_off.tail) }.load(Ordering::Acquire);
+        tail.wrapping_sub(head)
+    }
+
+    /// Reap the next completion without blocking
+    pub fn completion(&mut self) -> Option<Cqe> {
+        let cq = self.cq();
+        let head = unsafe { cq.atomic(self.params.cq_off.head) };
+        let tail = unsafe { cq.atomic(self.params.cq_off.tail) };
+
+        // The acquire on the tail makes the entries written by the kernel visible
+        let h = head.load(Ordering::Relaxed);
+        if h == tail.load(Ordering::Acquire) {
+            return None;
+        }
+
+        let cqes = unsafe { cq.ptr.add(self.params.cq_off.cqes as usize) } as *const io_uring_cqe;
+        let cqe = unsafe { cqes.add((h & self.cq_mask) as usize).read() };
+
+        // The release orders the read of the entry before the kernel may overwrite it
+        head.store(h.wrapping_add(1), Ordering::Release);
+        Some(Cqe(cqe))
+    }
+
+    /// Reap all available completions
+    pub fn completions(&mut self) -> impl Iterator<Item = Cqe> + '_ {
+        core::iter::from_fn(move || self.completion())
+    }
+
+    /// Block until a completion is available and reap it
+    pub fn wait(&mut self) -> Result<Cqe> {
+        loop {
+            if let Some(cqe) = self.completion() {
+                return Ok(cqe);
+            }
+            match self.submit_and_wait(1) {
+                Ok(_) | Err(Error::EINTR) => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Completions dropped because the completion queue was full (before IORING_FEAT_NODROP)
+    pub fn cq_overflow(&self) -> u32 {
+        unsafe { self.cq().atomic(self.params.cq_off.overflow) }.load(Ordering::Acquire)
+    }
+
+    /// Pin the buffers for read_fixed() and write_fixed(), they are referenced by their index
+    ///
+    /// # Safety
+    /// The buffers have to stay valid until they are unregistered or the ring is dropped
+    pub unsafe fn register_buffers(&self, bufs: &[iovec]) -> Result<()> {
+        io_uring_register(self.fd(), IORING_REGISTER_BUFFERS, bufs.as_ptr() as *const u8, bufs.len() as u32).map(|_| ())
+    }
+
+    pub fn unregister_buffers(&self) -> Result<()> {
+        unsafe { io_uring_register(self.fd(), IORING_UNREGISTER_BUFFERS, core::ptr::null(), 0) }.map(|_| ())
+    }
+
+    /// Register file descriptors which are then referenced by their index with fixed_file().
+    /// A slot with -1 is left empty.
+    pub fn register_files(&self, fds: &[i32]) -> Result<()> {
+        unsafe { io_uring_register(self.fd(), IORING_REGISTER_FILES, fds.as_ptr() as *const u8, fds.len() as u32) }.map(|_| ())
+    }
+
+    pub fn unregister_files(&self) -> Result<()> {
+        unsafe { io_uring_register(self.fd(), IORING_UNREGISTER_FILES, core::ptr::null(), 0) }.map(|_| ())
+    }
+}
+
+impl AsFd for IoUring {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}